@@ -1,6 +1,7 @@
 use egui_demo_lib::DemoWindows;
 use crate::audio_controls::{AudioControlState, show_audio_controls};
-use crate::drag_drop_canvas::{DragDropCanvas, WidgetType, WidgetColor};
+use crate::canvas::widgets::types::{WidgetType, WidgetColor};
+use crate::canvas_tabs::{CanvasTabs, file_name_for};
 use egui::{Color32, Pos2};
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
@@ -14,14 +15,108 @@ pub struct TemplateApp {
     #[serde(skip)]
     audio_state: AudioControlState,
     
-    // Drag and drop canvas
-    #[serde(skip)]
-    canvas: DragDropCanvas,
+    // Drag and drop canvas pages; the active page's name is the layout name it saves under
+    #[serde(with = "tabs_storage")]
+    tabs: CanvasTabs,
     
     // UI mode selection
     show_demo: bool,
     show_audio_controls: bool,
     show_drag_drop: bool,
+    
+    // Crash recovery
+    autosave_interval: f32, // Seconds between recovery saves of all canvas pages, 0 = off
+    last_normal_save: u64,  // Unix time of the last eframe save, to tell whether a recovery file is newer
+    #[serde(skip)]
+    last_autosave: f64, // egui time of the last recovery save
+    #[serde(skip)]
+    recovery_offer: Option<String>, // Recovered pages JSON awaiting the user's restore/discard choice
+    
+    // Layout files
+    #[serde(skip)]
+    pending_load: Option<(String, String)>, // Name and JSON of a layout waiting on the unsaved-changes prompt
+}
+
+/// Canvas pages in app storage, kept as the same JSON the recovery file holds
+mod tabs_storage {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    
+    use crate::canvas_tabs::CanvasTabs;
+    
+    pub fn serialize<S: Serializer>(tabs: &CanvasTabs, serializer: S) -> Result<S::Ok, S::Error> {
+        tabs.to_json().map_err(serde::ser::Error::custom)?.serialize(serializer)
+    }
+    
+    /// Pages that fail to load are dropped with a warning rather than losing the rest of the app state
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CanvasTabs, D::Error> {
+        let json = String::deserialize(deserializer)?;
+        let mut tabs = CanvasTabs::new();
+        if let Err(err) = tabs.from_json(&json) {
+            log::warn!("Stored canvas pages could not be loaded: {}", err);
+        }
+        Ok(tabs)
+    }
+}
+
+/// Answer to the unsaved-changes prompt shown before loading a layout
+enum LoadChoice {
+    SaveFirst,
+    Discard,
+    Cancel,
+}
+
+/// Name of the data directory holding the recovery file and saved layouts (version-independent, unlike the window title)
+#[cfg(not(target_arch = "wasm32"))]
+const RECOVERY_APP_ID: &str = "Ev2";
+
+/// Where the auto-saved canvas layout is written; None where there is no file system
+fn recovery_path() -> Option<std::path::PathBuf> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        eframe::storage_dir(RECOVERY_APP_ID).map(|dir| dir.join("recovery.json"))
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        None
+    }
+}
+
+/// Folder of PNG images written by the canvas's Export PNG button; None where there is no file system
+fn exports_dir() -> Option<std::path::PathBuf> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        eframe::storage_dir(RECOVERY_APP_ID).map(|dir| dir.join("exports"))
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        None
+    }
+}
+
+/// Folder of named layout files written by Save Layout; None where there is no file system
+fn layouts_dir() -> Option<std::path::PathBuf> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        eframe::storage_dir(RECOVERY_APP_ID).map(|dir| dir.join("layouts"))
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        None
+    }
+}
+
+/// Seconds since the Unix epoch (0 on the web, where SystemTime is unavailable and there is no recovery file)
+fn unix_now() -> u64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs())
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        0
+    }
 }
 
 impl Default for TemplateApp {
@@ -29,10 +124,15 @@ impl Default for TemplateApp {
         Self {
             demo_windows: DemoWindows::default(),
             audio_state: AudioControlState::new(),
-            canvas: DragDropCanvas::new(),
+            tabs: CanvasTabs::new(),
             show_demo: false,
             show_audio_controls: false,
             show_drag_drop: true,
+            autosave_interval: 60.0,
+            last_normal_save: 0,
+            last_autosave: 0.0,
+            recovery_offer: None,
+            pending_load: None,
         }
     }
 }
@@ -50,19 +150,238 @@ impl TemplateApp {
 
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
-        }
-
-        // Initialize with some example widgets
-        let mut app = Self::default();
-        app.setup_example_widgets();
+        let mut app = if let Some(storage) = cc.storage {
+            eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default()
+        } else {
+            // Initialize with some example widgets
+            let mut app = Self::default();
+            app.setup_example_widgets();
+            app.tabs.active_mut().mark_clean(); // The examples are a starting point, not unsaved work
+            app
+        };
+        app.recovery_offer = app.newer_recovery_layout();
         app
     }
     
+    /// The auto-saved layout, if one was written after the last normal save (i.e. the app didn't exit cleanly)
+    fn newer_recovery_layout(&self) -> Option<String> {
+        let path = recovery_path()?;
+        let modified = std::fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+        let modified = modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+        if modified <= self.last_normal_save {
+            return None;
+        }
+        std::fs::read_to_string(&path).ok()
+    }
+    
+    /// Write every canvas page to the recovery file every `autosave_interval` seconds
+    fn autosave(&mut self, ctx: &egui::Context) {
+        if self.autosave_interval <= 0.0 || self.recovery_offer.is_some() {
+            return; // Off, or a recovered layout is still waiting and must not be overwritten
+        }
+        let Some(path) = recovery_path() else {
+            return;
+        };
+        
+        let interval = f64::from(self.autosave_interval);
+        let now = ctx.input(|i| i.time);
+        if now - self.last_autosave < interval {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(interval - (now - self.last_autosave)));
+            return;
+        }
+        self.last_autosave = now;
+        
+        let result = self.tabs.to_json()
+            .map_err(std::io::Error::other)
+            .and_then(|json| {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(&path, json)
+            });
+        if let Err(err) = result {
+            log::warn!("Auto-save to {} failed: {}", path.display(), err);
+        }
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(interval));
+    }
+    
+    /// Ask whether to restore a layout recovered from an unclean exit
+    fn show_recovery_prompt(&mut self, ctx: &egui::Context) {
+        let Some(json) = &self.recovery_offer else {
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new("Recover layout?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label("An auto-saved layout newer than the last session was found.");
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Discard").clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
+        
+        match choice {
+            Some(true) => {
+                if let Err(err) = self.tabs.from_json(json) {
+                    log::warn!("Recovered layout could not be loaded: {}", err);
+                }
+                for (_, canvas) in self.tabs.iter_mut() {
+                    canvas.mark_dirty(); // Recovered work was never saved
+                }
+                self.recovery_offer = None;
+            }
+            Some(false) => self.recovery_offer = None,
+            None => {}
+        }
+    }
+    
+    /// Names of the saved layouts, alphabetically
+    fn saved_layouts() -> Vec<String> {
+        let Some(entries) = layouts_dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let is_json = path.extension().is_some_and(|ext| ext == "json");
+                is_json.then(|| path.file_stem()?.to_str().map(str::to_string)).flatten()
+            })
+            .collect();
+        names.sort();
+        names
+    }
+    
+    /// Write the active page's layout under its name, marking it clean on success
+    fn save_layout_file(&mut self) -> bool {
+        let Some(file_name) = file_name_for(self.tabs.active_name()) else {
+            log::warn!("Layout not saved: \"{}\" can't be used as a file name", self.tabs.active_name());
+            return false;
+        };
+        let Some(path) = layouts_dir().map(|dir| dir.join(format!("{file_name}.json"))) else {
+            return false;
+        };
+        let result = self.tabs.active().to_json()
+            .map_err(std::io::Error::other)
+            .and_then(|json| {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(&path, json)
+            });
+        match result {
+            Ok(()) => {
+                self.tabs.active_mut().mark_clean();
+                true
+            }
+            Err(err) => {
+                log::warn!("Saving layout to {} failed: {}", path.display(), err);
+                false
+            }
+        }
+    }
+    
+    /// Write an image exported from the active page as `<page name>.png` in the exports folder
+    fn save_exported_png(&self, png: &[u8]) {
+        let Some(file_name) = file_name_for(self.tabs.active_name()) else {
+            log::warn!("Canvas image not saved: \"{}\" can't be used as a file name", self.tabs.active_name());
+            return;
+        };
+        let Some(path) = exports_dir().map(|dir| dir.join(format!("{file_name}.png"))) else {
+            log::warn!("Canvas image not saved: no file system");
+            return;
+        };
+        let result = path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&path, png));
+        match result {
+            Ok(()) => log::info!("Canvas image saved to {}", path.display()),
+            Err(err) => log::warn!("Saving canvas image to {} failed: {}", path.display(), err),
+        }
+    }
+    
+    /// Read a saved layout into the active page, asking first if that would throw away unsaved changes
+    fn load_layout_file(&mut self, name: &str) {
+        let Some(path) = layouts_dir().map(|dir| dir.join(format!("{name}.json"))) else {
+            return;
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(json) if self.tabs.active().is_dirty() => self.pending_load = Some((name.to_string(), json)),
+            Ok(json) => self.apply_layout(name, &json),
+            Err(err) => log::warn!("Loading layout from {} failed: {}", path.display(), err),
+        }
+    }
+    
+    /// Load a layout into the active page, which takes the layout's name
+    fn apply_layout(&mut self, name: &str, json: &str) {
+        match self.tabs.active_mut().from_json(json) {
+            Ok(()) => {
+                self.tabs.rename_tab(self.tabs.active_index(), name);
+            }
+            Err(err) => log::warn!("Layout {} could not be loaded: {}", name, err),
+        }
+    }
+    
+    /// Ask whether to save, discard or keep the unsaved changes a pending load would replace
+    fn show_unsaved_changes_prompt(&mut self, ctx: &egui::Context) {
+        let Some((name, _)) = &self.pending_load else {
+            return;
+        };
+        // Saving under the name being loaded would overwrite it, so that choice is offered only for other names
+        let can_save = name != self.tabs.active_name();
+        let mut choice = None;
+        egui::Window::new("Unsaved changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!("Loading \"{}\" will replace the changes made since the last save.", name));
+                ui.horizontal(|ui| {
+                    let save = ui.add_enabled(can_save, egui::Button::new(format!("Save as \"{}\" first", self.tabs.active_name())))
+                        .on_disabled_hover_text("The layout being loaded has the same name");
+                    if save.clicked() {
+                        choice = Some(LoadChoice::SaveFirst);
+                    }
+                    if ui.button("Discard").clicked() {
+                        choice = Some(LoadChoice::Discard);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        choice = Some(LoadChoice::Cancel);
+                    }
+                });
+            });
+        
+        let Some(choice) = choice else {
+            return;
+        };
+        let Some((name, json)) = self.pending_load.take() else {
+            return;
+        };
+        match choice {
+            LoadChoice::SaveFirst => {
+                if self.save_layout_file() {
+                    self.apply_layout(&name, &json);
+                } else {
+                    self.pending_load = Some((name, json)); // Keep asking rather than lose either layout
+                }
+            }
+            LoadChoice::Discard => self.apply_layout(&name, &json),
+            LoadChoice::Cancel => {}
+        }
+    }
+    
     fn setup_example_widgets(&mut self) {
+        let canvas = self.tabs.active_mut();
+        
         // Add some example widgets to showcase the system
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::Panel {
                 title: "MASTER CONTROL".to_string(),
                 color: WidgetColor::Cyan,
@@ -71,33 +390,36 @@ impl TemplateApp {
                 collapsed: false,
                 contained_widgets: Vec::new(),
                 minimize_to_settings_icon: false,
+                grid_layout: false,
             },
             Pos2::new(50.0, 50.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::Knob {
                 value: 75.0,
                 min: 0.0,
                 max: 100.0,
                 label: "VOLUME".to_string(),
                 color: WidgetColor::Cyan,
+                default_value: None,
             },
             Pos2::new(80.0, 100.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::Knob {
                 value: 30.0,
                 min: 0.0,
                 max: 100.0,
                 label: "GAIN".to_string(),
                 color: WidgetColor::Pink,
+                default_value: None,
             },
             Pos2::new(180.0, 100.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::Panel {
                 title: "EQ & EFFECTS".to_string(),
                 color: WidgetColor::Pink,
@@ -106,44 +428,48 @@ impl TemplateApp {
                 collapsed: false,
                 contained_widgets: Vec::new(),
                 minimize_to_settings_icon: false,
+                grid_layout: false,
             },
             Pos2::new(300.0, 50.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::HorizontalSlider {
                 value: 60.0,
                 min: 0.0,
                 max: 100.0,
                 label: "LOW".to_string(),
                 color: WidgetColor::Green,
+                default_value: None,
             },
             Pos2::new(320.0, 120.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::HorizontalSlider {
                 value: 45.0,
                 min: 0.0,
                 max: 100.0,
                 label: "MID".to_string(),
                 color: WidgetColor::Yellow,
+                default_value: None,
             },
             Pos2::new(320.0, 150.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::HorizontalSlider {
                 value: 70.0,
                 min: 0.0,
                 max: 100.0,
                 label: "HIGH".to_string(),
                 color: WidgetColor::Pink,
+                default_value: None,
             },
             Pos2::new(320.0, 180.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::Panel {
                 title: "MONITORING".to_string(),
                 color: WidgetColor::Green,
@@ -152,11 +478,12 @@ impl TemplateApp {
                 collapsed: false,
                 contained_widgets: Vec::new(),
                 minimize_to_settings_icon: false,
+                grid_layout: false,
             },
             Pos2::new(550.0, 50.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::VuMeter {
                 level: 75.0,
                 peak_level: 80.0,
@@ -166,7 +493,7 @@ impl TemplateApp {
             Pos2::new(580.0, 100.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::VuMeter {
                 level: 60.0,
                 peak_level: 65.0,
@@ -176,7 +503,7 @@ impl TemplateApp {
             Pos2::new(620.0, 100.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::VuMeter {
                 level: 85.0,
                 peak_level: 90.0,
@@ -187,7 +514,7 @@ impl TemplateApp {
         );
         
         // Add some toggle switches
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::ToggleSwitch {
                 on: false,
                 label: "REVERB".to_string(),
@@ -197,7 +524,7 @@ impl TemplateApp {
             Pos2::new(320.0, 220.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::ToggleSwitch {
                 on: true,
                 label: "ECHO".to_string(),
@@ -207,7 +534,7 @@ impl TemplateApp {
             Pos2::new(400.0, 220.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::ToggleSwitch {
                 on: false,
                 label: "EQ".to_string(),
@@ -218,24 +545,26 @@ impl TemplateApp {
         );
         
         // Add some push buttons
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::PushButton {
                 active: true,
                 icon: "⚡".to_string(),
                 label: "POWER".to_string(),
                 color: WidgetColor::Green,
                 size: 48.0,
+                momentary: false,
             },
             Pos2::new(80.0, 250.0),
         );
         
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::PushButton {
                 active: false,
                 icon: "▶".to_string(),
                 label: "PLAY".to_string(),
                 color: WidgetColor::Cyan,
                 size: 56.0,
+                momentary: false,
             },
             Pos2::new(150.0, 250.0),
         );
@@ -245,20 +574,21 @@ impl TemplateApp {
             let values = [75.0, 60.0, 85.0, 45.0, 90.0, 30.0, 65.0, 50.0];
             let colors = [WidgetColor::Cyan, WidgetColor::Pink, WidgetColor::Green, WidgetColor::Yellow];
             
-            self.canvas.add_widget(
+            canvas.add_widget(
                 WidgetType::VerticalSlider {
                     value: values[i],
                     min: 0.0,
                     max: 100.0,
                     label: format!("CH{}", i + 1),
                     color: colors[i % 4],
+                    default_value: None,
                 },
                 Pos2::new(50.0 + i as f32 * 50.0, 350.0),
             );
         }
         
         // Add level indicators
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::LevelIndicator {
                 level: 62.5,
                 segments: 8,
@@ -268,7 +598,7 @@ impl TemplateApp {
         );
         
         // Add title
-        self.canvas.add_widget(
+        canvas.add_widget(
             WidgetType::TextLabel {
                 text: "AUDIO CONTROL MATRIX".to_string(),
                 size: 24.0,
@@ -282,6 +612,7 @@ impl TemplateApp {
 impl eframe::App for TemplateApp {
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.last_normal_save = unix_now();
         eframe::set_value(storage, eframe::APP_KEY, self);
     }
 
@@ -293,10 +624,49 @@ impl eframe::App for TemplateApp {
         // Top menu bar
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
+                ui.menu_button("File", |ui| {
+                    ui.add_enabled_ui(layouts_dir().is_some(), |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Name");
+                            let mut name = self.tabs.active_name().to_string();
+                            if ui.text_edit_singleline(&mut name).changed() {
+                                self.tabs.rename_tab(self.tabs.active_index(), name);
+                            }
+                        });
+                        let valid_name = file_name_for(self.tabs.active_name()).is_some();
+                        if ui.add_enabled(valid_name, egui::Button::new("💾 Save Layout")).clicked() {
+                            self.save_layout_file();
+                            ui.close_menu();
+                        }
+                        ui.menu_button("📂 Load Layout", |ui| {
+                            let names = Self::saved_layouts();
+                            if names.is_empty() {
+                                ui.label("No saved layouts");
+                            }
+                            for name in names {
+                                if ui.button(&name).clicked() {
+                                    self.load_layout_file(&name);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    });
+                });
                 ui.menu_button("View", |ui| {
                     ui.checkbox(&mut self.show_demo, "Demo Windows");
                     ui.checkbox(&mut self.show_audio_controls, "Audio Controls");
                     ui.checkbox(&mut self.show_drag_drop, "Drag & Drop Canvas");
+                    let zoom_label = format!("Reset Zoom ({:.0}%)", self.tabs.active().zoom * 100.0);
+                    if ui.add_enabled(self.tabs.active().zoom != 1.0, egui::Button::new(zoom_label)).clicked() {
+                        self.tabs.active_mut().reset_zoom();
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label("Auto-save every");
+                        ui.add(egui::DragValue::new(&mut self.autosave_interval).range(0.0..=600.0).suffix(" s"))
+                            .on_hover_text("Writes a recovery copy of the layout; 0 turns it off");
+                    });
                 });
                 
                 ui.separator();
@@ -308,9 +678,9 @@ impl eframe::App for TemplateApp {
         // Show widget palette on the left
         if self.show_drag_drop {
             egui::SidePanel::left("widget_palette")
-                .default_width(220.0)
+                .exact_width(self.tabs.active().settings.palette_width)
                 .show(ctx, |ui| {
-                    self.canvas.show_widget_palette(ui);
+                    self.tabs.active_mut().show_widget_palette(ui);
                 });
         }
 
@@ -329,9 +699,14 @@ impl eframe::App for TemplateApp {
                 
                 ui.add_space(10.0);
                 
-                // Render the canvas
-                self.canvas.render(ui);
+                // Page tabs, then the active page's canvas
+                self.tabs.show_tab_bar(ui);
+                self.tabs.active_mut().render(ui);
             });
+            
+            if let Some(png) = self.tabs.active_mut().take_exported_png() {
+                self.save_exported_png(&png);
+            }
         }
 
         // Show audio controls window if enabled
@@ -347,5 +722,9 @@ impl eframe::App for TemplateApp {
         if self.show_demo {
             self.demo_windows.ui(ctx);
         }
+
+        self.show_recovery_prompt(ctx);
+        self.show_unsaved_changes_prompt(ctx);
+        self.autosave(ctx);
     }
 }
\ No newline at end of file
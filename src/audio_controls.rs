@@ -1,6 +1,8 @@
 use egui::{Color32, Pos2, Rect, Response, Sense, Stroke, Ui, Vec2, Widget, FontId, Align2, RichText};
 use std::f32::consts::PI;
 
+use crate::canvas::constants::{FINE_ADJUST, PEAK_FALL_RATE};
+
 // Color constants matching React version
 const CYAN: Color32 = Color32::from_rgb(6, 182, 212);
 const PINK: Color32 = Color32::from_rgb(236, 72, 153);
@@ -15,6 +17,11 @@ const GRAY_400: Color32 = Color32::from_rgb(156, 163, 175);
 #[allow(dead_code)]
 const GRAY_200: Color32 = Color32::from_rgb(229, 231, 235);
 
+/// Drag multiplier for knobs and sliders: 5x finer while Shift is held
+fn drag_sensitivity(ui: &Ui) -> f32 {
+    if ui.input(|i| i.modifiers.shift) { FINE_ADJUST } else { 1.0 }
+}
+
 pub struct Knob<'a> {
     value: &'a mut f32,
     min: f32,
@@ -61,7 +68,7 @@ impl<'a> Widget for Knob<'a> {
         let (rect, mut response) = ui.allocate_exact_size(desired_size, Sense::drag());
 
         if response.dragged() {
-            let delta = response.drag_delta();
+            let delta = response.drag_delta() * drag_sensitivity(ui);
             let delta_value = -delta.y * (self.max - self.min) / 100.0;
             *self.value = (*self.value + delta_value).clamp(self.min, self.max);
             response.mark_changed();
@@ -384,11 +391,13 @@ impl<'a> Widget for VuMeter<'a> {
         let desired_size = Vec2::new(self.width + 10.0, self.height + 30.0);
         let (rect, response) = ui.allocate_exact_size(desired_size, Sense::hover());
 
-        // Update peak level
-        if *self.level > *self.peak_level {
+        // Update peak level, falling at the canvas meters' rate whatever the frame rate
+        if *self.level >= *self.peak_level {
             *self.peak_level = *self.level;
         } else {
-            *self.peak_level = (*self.peak_level - 0.5).max(0.0);
+            let dt = ui.input(|i| i.unstable_dt);
+            *self.peak_level = (*self.peak_level - PEAK_FALL_RATE * dt).max(*self.level);
+            ui.ctx().request_repaint();
         }
 
         if ui.is_rect_visible(rect) {
@@ -526,7 +535,7 @@ impl<'a> Widget for Slider<'a> {
         let (rect, mut response) = ui.allocate_exact_size(desired_size, Sense::drag());
 
         if response.dragged() {
-            let delta = response.drag_delta();
+            let delta = response.drag_delta() * drag_sensitivity(ui);
             let delta_value = if self.vertical {
                 -delta.y * (self.max - self.min) / self.size.y
             } else {
@@ -0,0 +1,51 @@
+//! Color and layout constants for the drag-and-drop canvas
+
+use egui::Color32;
+
+/// Application version displayed in the palette and window title
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Layout constants
+pub const PANEL_MARGIN: f32 = 10.0;
+pub const PANEL_TITLE_HEIGHT: f32 = 40.0;
+pub const CANVAS_MARGIN: f32 = 20.0;
+pub const GRID_SPACING: f32 = 120.0;
+pub const PALETTE_WIDTH: f32 = 220.0;
+pub const DOCK_DISTANCE: f32 = 24.0; // How close to a canvas edge a dragged settings panel docks to it
+
+// Animation
+pub const FLASH_DURATION: f64 = 0.6; // Seconds a host-driven change stays outlined
+pub const RUBBER_BAND_LIMIT: f32 = 40.0; // Furthest a widget can be pulled past its panel's edge
+pub const SPRING_STIFFNESS: f32 = 14.0; // Spring-back covers 1 - e^-stiffness of the distance per second
+
+// Interaction
+pub const FINE_ADJUST: f32 = 0.2; // Drag sensitivity multiplier for knobs and sliders while Shift is held
+
+// View
+pub const MIN_ZOOM: f32 = 0.25;
+pub const MAX_ZOOM: f32 = 4.0;
+
+// History
+pub const UNDO_LIMIT: usize = 50; // Snapshots kept for undo()
+pub const EVENT_LIMIT: usize = 1024; // Queued CanvasEvents kept for a host that doesn't poll take_events()
+pub const NUDGE_BURST_GAP: f64 = 1.0; // Seconds between arrow-key nudges that still undo as one step
+
+// Metering
+pub const METER_FLOOR_DB: f32 = -60.0; // Level shown as an empty bar on dB meters
+pub const PEAK_FALL_RATE: f32 = 30.0; // Percent of full scale a held meter peak drops per second
+pub const PULSE_PERIOD: f64 = 1.6; // Seconds per breath of the status bar's online pulse
+pub const PULSE_FRAME_RATE: f32 = 20.0; // Repaints per second driving the pulse, so an idle console doesn't redraw every vsync
+
+// Color constants matching the React app palette
+pub const BLACK: Color32 = Color32::from_rgb(0, 0, 0);
+pub const CYAN: Color32 = Color32::from_rgb(6, 182, 212);
+pub const PINK: Color32 = Color32::from_rgb(236, 72, 153);
+pub const GREEN: Color32 = Color32::from_rgb(16, 185, 129);
+pub const YELLOW: Color32 = Color32::from_rgb(245, 158, 11);
+pub const RED: Color32 = Color32::from_rgb(239, 68, 68);
+pub const GRAY_900: Color32 = Color32::from_rgb(17, 24, 39);
+pub const GRAY_800: Color32 = Color32::from_rgb(31, 41, 55);
+pub const GRAY_700: Color32 = Color32::from_rgb(55, 65, 81);
+pub const GRAY_600: Color32 = Color32::from_rgb(75, 85, 99);
+pub const GRAY_400: Color32 = Color32::from_rgb(156, 163, 175);
+pub const WHITE: Color32 = Color32::WHITE;
@@ -0,0 +1,298 @@
+//! Offscreen rendering of the canvas to PNG
+//!
+//! egui only tessellates shapes into triangle meshes; turning those into pixels
+//! is normally the GPU backend's job. For image export the canvas draws its
+//! widgets in a separate `egui::Context`, and this module rasterizes the
+//! resulting meshes in software, the same way egui's own painters shade them:
+//! vertex color times texture color, blended with premultiplied alpha in gamma
+//! space. The result is encoded as PNG with the `image` crate.
+
+use std::collections::HashMap;
+
+use egui::epaint::{ClippedPrimitive, ImageData, ImageDelta, Mesh, Primitive, Vertex};
+use egui::{Color32, ColorImage, Pos2, Rect, TextureId};
+use image::ImageEncoder;
+
+/// Textures referenced by the meshes, built up from the frame's texture deltas
+struct Textures(HashMap<TextureId, ColorImage>);
+
+impl Textures {
+    fn from_deltas(deltas: &[(TextureId, ImageDelta)]) -> Self {
+        let mut textures: HashMap<TextureId, ColorImage> = HashMap::new();
+        for (id, delta) in deltas {
+            let patch = match &delta.image {
+                ImageData::Color(image) => image.as_ref().clone(),
+                ImageData::Font(font) => ColorImage { size: font.size, pixels: font.srgba_pixels(None).collect() },
+            };
+            match delta.pos {
+                None => {
+                    textures.insert(*id, patch);
+                }
+                Some([x, y]) => {
+                    // A patch only ever lands in a texture set earlier in the same frame
+                    if let Some(texture) = textures.get_mut(id) {
+                        for row in 0..patch.size[1] {
+                            for col in 0..patch.size[0] {
+                                if x + col < texture.size[0] && y + row < texture.size[1] {
+                                    texture[(x + col, y + row)] = patch[(col, row)];
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Self(textures)
+    }
+
+    /// Nearest texel at normalized `uv`; textures the frame didn't upload sample as white
+    fn sample(&self, id: TextureId, uv: Pos2) -> Color32 {
+        let Some(texture) = self.0.get(&id) else {
+            return Color32::WHITE;
+        };
+        let [width, height] = texture.size;
+        if width == 0 || height == 0 {
+            return Color32::WHITE;
+        }
+        let x = ((uv.x * width as f32) as usize).min(width - 1);
+        let y = ((uv.y * height as f32) as usize).min(height - 1);
+        texture[(x, y)]
+    }
+}
+
+/// Rasterize tessellated shapes into an image of `size` pixels
+///
+/// `origin` is the point drawn at the top-left pixel; positions and clip
+/// rects are in points and scaled by `pixels_per_point`. The image starts
+/// filled with `background`.
+pub fn rasterize(
+    primitives: &[ClippedPrimitive],
+    textures: &[(TextureId, ImageDelta)],
+    origin: Pos2,
+    pixels_per_point: f32,
+    size: [usize; 2],
+    background: Color32,
+) -> ColorImage {
+    let textures = Textures::from_deltas(textures);
+    let mut image = ColorImage::new(size, background);
+    let to_pixels = |pos: Pos2| ((pos - origin) * pixels_per_point).to_pos2();
+
+    for primitive in primitives {
+        let Primitive::Mesh(mesh) = &primitive.primitive else {
+            continue; // Paint callbacks need the GPU backend
+        };
+        let clip = Rect::from_min_max(to_pixels(primitive.clip_rect.min), to_pixels(primitive.clip_rect.max))
+            .intersect(Rect::from_min_size(Pos2::ZERO, egui::vec2(size[0] as f32, size[1] as f32)));
+        if clip.is_positive() {
+            rasterize_mesh(&mut image, mesh, &textures, clip, to_pixels);
+        }
+    }
+    image
+}
+
+fn rasterize_mesh(image: &mut ColorImage, mesh: &Mesh, textures: &Textures, clip: Rect, to_pixels: impl Fn(Pos2) -> Pos2) {
+    for triangle in mesh.indices.chunks_exact(3) {
+        let vertices = [0, 1, 2].map(|i| {
+            let vertex = mesh.vertices[triangle[i] as usize];
+            Vertex { pos: to_pixels(vertex.pos), ..vertex }
+        });
+        rasterize_triangle(image, &vertices, mesh.texture_id, textures, clip);
+    }
+}
+
+fn rasterize_triangle(image: &mut ColorImage, vertices: &[Vertex; 3], texture_id: TextureId, textures: &Textures, clip: Rect) {
+    let [a, b, c] = vertices.map(|v| v.pos);
+    let area = (b - a).x * (c - a).y - (b - a).y * (c - a).x;
+    if area.abs() < f32::EPSILON {
+        return;
+    }
+
+    // Edge function of the edge from `p` to `q`, positive inside whichever the winding
+    let edge = |p: Pos2, q: Pos2, point: Pos2| ((q - p).x * (point - p).y - (q - p).y * (point - p).x) / area;
+    // Pixels exactly on an edge shared by two triangles belong to one of them (top-left rule).
+    // With y pointing down, a top edge runs rightwards and a left edge upwards.
+    let owns_edge = |p: Pos2, q: Pos2| {
+        let d = (q - p) * area.signum();
+        (d.y == 0.0 && d.x > 0.0) || d.y < 0.0
+    };
+    let owned = [owns_edge(b, c), owns_edge(c, a), owns_edge(a, b)];
+
+    let bounds = Rect::from_points(&[a, b, c]).intersect(clip);
+    if !bounds.is_positive() {
+        return;
+    }
+    for y in bounds.min.y.floor() as usize..bounds.max.y.ceil() as usize {
+        for x in bounds.min.x.floor() as usize..bounds.max.x.ceil() as usize {
+            let center = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+            if !clip.contains(center) {
+                continue;
+            }
+            let weights = [edge(b, c, center), edge(c, a, center), edge(a, b, center)];
+            let inside = weights.iter().zip(owned).all(|(&w, owns)| w > 0.0 || (w == 0.0 && owns));
+            if !inside {
+                continue;
+            }
+
+            let interpolate = |value: fn(&Vertex) -> [f32; 4]| {
+                let mut out = [0.0; 4];
+                for (vertex, weight) in vertices.iter().zip(weights) {
+                    for (o, v) in out.iter_mut().zip(value(vertex)) {
+                        *o += v * weight;
+                    }
+                }
+                out
+            };
+            let color = interpolate(|v| v.color.to_array().map(f32::from));
+            let [u, v, ..] = interpolate(|v| [v.uv.x, v.uv.y, 0.0, 0.0]);
+            let texel = textures.sample(texture_id, Pos2::new(u, v)).to_array();
+
+            // Premultiplied source over destination, both in gamma space
+            let source: [f32; 4] = std::array::from_fn(|i| color[i] / 255.0 * f32::from(texel[i]) / 255.0);
+            let target = &mut image[(x, y)];
+            let dest = target.to_array();
+            let blended: [u8; 4] = std::array::from_fn(|i| {
+                let value = source[i] + f32::from(dest[i]) / 255.0 * (1.0 - source[3]);
+                (value * 255.0).round().clamp(0.0, 255.0) as u8
+            });
+            *target = Color32::from_rgba_premultiplied(blended[0], blended[1], blended[2], blended[3]);
+        }
+    }
+}
+
+/// Encode an image as PNG, unmultiplying its alpha
+///
+/// The alpha is divided out in gamma space, where the rasterizer multiplied it in;
+/// `Color32::to_srgba_unmultiplied` goes through linear space and would darken
+/// translucent pixels.
+pub fn encode_png(image: &ColorImage) -> Result<Vec<u8>, image::ImageError> {
+    let rgba: Vec<u8> = image.pixels.iter().flat_map(|pixel| unmultiply(*pixel)).collect();
+    let mut png = Vec::new();
+    image::codecs::png::PngEncoder::new(&mut png).write_image(
+        &rgba,
+        image.size[0] as u32,
+        image.size[1] as u32,
+        image::ExtendedColorType::Rgba8,
+    )?;
+    Ok(png)
+}
+
+fn unmultiply(pixel: Color32) -> [u8; 4] {
+    let [r, g, b, a] = pixel.to_array();
+    if a == 0 || a == 255 {
+        return [r, g, b, a];
+    }
+    let channel = |c: u8| (f32::from(c) * 255.0 / f32::from(a)).round().min(255.0) as u8;
+    [channel(r), channel(g), channel(b), a]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::TextureOptions;
+
+    const BACKGROUND: Color32 = Color32::from_rgb(0, 0, 200);
+    // Half-covering red, drawn twice it would come out visibly redder
+    const HALF_RED: Color32 = Color32::from_rgba_premultiplied(128, 0, 0, 128);
+
+    /// The default (font atlas) texture as a single white texel, like egui uploads it
+    fn white_texture() -> Vec<(TextureId, ImageDelta)> {
+        vec![(TextureId::default(), ImageDelta::full(ColorImage::new([1, 1], Color32::WHITE), TextureOptions::NEAREST))]
+    }
+
+    fn rect_primitive(rect: Rect, color: Color32) -> ClippedPrimitive {
+        let mut mesh = Mesh::default();
+        mesh.add_colored_rect(rect, color);
+        ClippedPrimitive { clip_rect: Rect::EVERYTHING, primitive: Primitive::Mesh(mesh) }
+    }
+
+    /// Pixels of a 4×4 image that differ from the background
+    fn covered(image: &ColorImage) -> Vec<(usize, usize)> {
+        (0..4).flat_map(|y| (0..4).map(move |x| (x, y)))
+            .filter(|&(x, y)| image[(x, y)] != BACKGROUND)
+            .collect()
+    }
+
+    #[test]
+    fn solid_rect_covers_its_pixels_once_and_blends_over_the_background() {
+        let primitives = [rect_primitive(Rect::from_min_max(Pos2::new(1.0, 1.0), Pos2::new(3.0, 3.0)), HALF_RED)];
+        let image = rasterize(&primitives, &white_texture(), Pos2::ZERO, 1.0, [4, 4], BACKGROUND);
+
+        assert_eq!(covered(&image), vec![(1, 1), (2, 1), (1, 2), (2, 2)]);
+        // (1, 1) and (2, 2) sit on the diagonal both triangles share; still blended only once
+        let blended = Color32::from_rgba_premultiplied(128, 0, 100, 255);
+        for pixel in covered(&image) {
+            assert_eq!(image[pixel], blended, "pixel {pixel:?}");
+        }
+    }
+
+    #[test]
+    fn pixel_centers_on_an_edge_go_to_the_top_and_left_edges_only() {
+        // Every edge runs through pixel centers: the top and left rows are in, the bottom and right ones out
+        let primitives = [rect_primitive(Rect::from_min_max(Pos2::new(0.5, 0.5), Pos2::new(2.5, 2.5)), HALF_RED)];
+        let image = rasterize(&primitives, &white_texture(), Pos2::ZERO, 1.0, [4, 4], BACKGROUND);
+
+        assert_eq!(covered(&image), vec![(0, 0), (1, 0), (0, 1), (1, 1)]);
+        assert!(covered(&image).iter().all(|&pixel| image[pixel] == Color32::from_rgba_premultiplied(128, 0, 100, 255)));
+    }
+
+    #[test]
+    fn origin_and_pixels_per_point_map_points_to_pixels() {
+        let primitives = [rect_primitive(Rect::from_min_size(Pos2::new(11.0, 20.0), egui::vec2(1.0, 1.0)), Color32::RED)];
+        let image = rasterize(&primitives, &white_texture(), Pos2::new(10.0, 20.0), 2.0, [4, 4], BACKGROUND);
+
+        assert_eq!(covered(&image), vec![(2, 0), (3, 0), (2, 1), (3, 1)]);
+        assert_eq!(image[(2, 0)], Color32::RED);
+    }
+
+    #[test]
+    fn texture_patches_land_in_the_texture_set_earlier() {
+        let id = TextureId::Managed(7);
+        let deltas = [
+            (id, ImageDelta::full(ColorImage::new([2, 2], Color32::WHITE), TextureOptions::NEAREST)),
+            (id, ImageDelta::partial([1, 0], ColorImage::new([1, 1], Color32::GREEN), TextureOptions::NEAREST)),
+        ];
+        let textures = Textures::from_deltas(&deltas);
+
+        assert_eq!(textures.sample(id, Pos2::new(0.25, 0.25)), Color32::WHITE);
+        assert_eq!(textures.sample(id, Pos2::new(0.75, 0.25)), Color32::GREEN);
+        assert_eq!(textures.sample(id, Pos2::new(1.0, 1.0)), Color32::WHITE);
+        assert_eq!(textures.sample(TextureId::Managed(8), Pos2::ZERO), Color32::WHITE);
+    }
+
+    #[test]
+    fn encoded_png_decodes_with_unmultiplied_alpha() {
+        let mut image = ColorImage::new([2, 1], Color32::BLACK);
+        image[(1, 0)] = Color32::from_rgba_premultiplied(64, 0, 0, 128);
+
+        let decoded = image::load_from_memory(&encode_png(&image).unwrap()).unwrap().to_rgba8();
+
+        assert_eq!(decoded.dimensions(), (2, 1));
+        assert_eq!(decoded.get_pixel(0, 0).0, [0, 0, 0, 255]);
+        assert_eq!(decoded.get_pixel(1, 0).0, [128, 0, 0, 128]);
+    }
+
+    #[test]
+    fn exported_canvas_decodes_at_canvas_size_on_the_theme_background() {
+        use crate::canvas::widgets::types::{WidgetColor, WidgetType};
+        use crate::drag_drop_canvas::DragDropCanvas;
+
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |_| {}); // Fonts exist from the first frame on
+        let mut canvas = DragDropCanvas::new();
+        canvas.canvas_rect = Rect::from_min_size(Pos2::new(40.0, 30.0), egui::vec2(240.0, 180.0));
+        canvas.add_widget_at(
+            WidgetType::Knob { value: 50.0, min: 0.0, max: 100.0, label: "GAIN".to_string(), color: WidgetColor::Cyan, default_value: None },
+            Pos2::new(40.0, 30.0),
+        );
+
+        let png = canvas.export_png(&ctx).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+
+        assert_eq!(decoded.dimensions(), (240, 180));
+        let [r, g, b, _] = canvas.settings.theme.background_color().to_srgba_unmultiplied();
+        assert_eq!(decoded.get_pixel(239, 179).0, [r, g, b, 255]);
+        assert!(decoded.pixels().any(|pixel| pixel.0 != [r, g, b, 255]), "the knob was not drawn");
+
+        assert!(DragDropCanvas::new().export_png(&ctx).is_err(), "exported before the first layout");
+    }
+}
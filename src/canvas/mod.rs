@@ -0,0 +1,12 @@
+//! Canvas module organization
+//!
+//! Shared building blocks for the drag-and-drop canvas: constants, panel
+//! containment helpers, canvas settings, the React layout importer, PNG
+//! export, and widget type/rendering definitions.
+
+pub mod constants;
+pub mod export;
+pub mod panels;
+pub mod react_import;
+pub mod settings;
+pub mod widgets;
@@ -0,0 +1,190 @@
+//! Panel containment management
+//!
+//! Helpers for tracking which widgets live inside which panels. Panels and
+//! settings panels store the IDs of their children in `contained_widgets`.
+
+use std::collections::HashSet;
+
+use egui::Pos2;
+
+use super::widgets::types::{DraggableWidget, WidgetType};
+
+pub struct PanelManager;
+
+impl PanelManager {
+    /// Check if a panel can accept widgets (not collapsed/minimized)
+    pub fn is_panel_accepting_widgets(panel_widget: &DraggableWidget) -> bool {
+        match &panel_widget.widget_type {
+            WidgetType::Panel { collapsed, .. } => !collapsed,
+            WidgetType::Settings { minimized, .. } => !minimized,
+            _ => false,
+        }
+    }
+
+    /// Add a widget to a panel's contained widgets list
+    pub fn add_widget_to_panel(widgets: &mut [DraggableWidget], panel_idx: usize, widget_id: usize) -> bool {
+        if let Some(panel) = widgets.get_mut(panel_idx) {
+            match &mut panel.widget_type {
+                WidgetType::Panel { contained_widgets, .. } |
+                WidgetType::Settings { contained_widgets, .. } if !contained_widgets.contains(&widget_id) => {
+                    contained_widgets.push(widget_id);
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Remove a widget ID from every panel that contains it
+    pub fn remove_widget_from_containers(widgets: &mut [DraggableWidget], widget_id: usize) {
+        for widget in widgets.iter_mut() {
+            match &mut widget.widget_type {
+                WidgetType::Panel { contained_widgets, .. } |
+                WidgetType::Settings { contained_widgets, .. } => {
+                    contained_widgets.retain(|&id| id != widget_id);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Find the index of the panel containing the widget at `widget_idx`
+    pub fn find_widget_container_panel(widgets: &[DraggableWidget], widget_idx: usize) -> Option<usize> {
+        let widget_id = widgets.get(widget_idx)?.id;
+
+        widgets.iter().position(|panel| match &panel.widget_type {
+            WidgetType::Panel { contained_widgets, .. } |
+            WidgetType::Settings { contained_widgets, .. } => contained_widgets.contains(&widget_id),
+            _ => false,
+        })
+    }
+
+    /// Find the ID of the panel containing the widget with `widget_id`
+    pub fn find_widget_container_panel_id(widgets: &[DraggableWidget], widget_id: usize) -> Option<usize> {
+        widgets.iter()
+            .find(|panel| match &panel.widget_type {
+                WidgetType::Panel { contained_widgets, .. } |
+                WidgetType::Settings { contained_widgets, .. } => contained_widgets.contains(&widget_id),
+                _ => false,
+            })
+            .map(|panel| panel.id)
+    }
+
+    /// True if putting `panel_id` inside `target_panel_id` would make a panel its own ancestor
+    ///
+    /// That is the case when the target is the panel itself or anything nested
+    /// inside it. Containment that is already cyclic also counts, so a corrupt
+    /// layout is never made worse.
+    pub fn would_create_cycle(widgets: &[DraggableWidget], panel_id: usize, target_panel_id: usize) -> bool {
+        let mut visited = HashSet::new();
+        let mut current = Some(target_panel_id);
+        while let Some(id) = current {
+            if id == panel_id || !visited.insert(id) {
+                return true;
+            }
+            current = Self::find_widget_container_panel_id(widgets, id);
+        }
+        false
+    }
+
+    /// Panels from the canvas down to and including `panel_id`; a top-level panel is at depth 1
+    pub fn nesting_depth(widgets: &[DraggableWidget], panel_id: usize) -> usize {
+        let mut visited = HashSet::new();
+        let mut current = Some(panel_id);
+        while let Some(id) = current.filter(|&id| visited.insert(id)) {
+            current = Self::find_widget_container_panel_id(widgets, id);
+        }
+        visited.len()
+    }
+
+    /// Levels of panels in the subtree rooted at a widget: 0 for a plain widget, 1 for an empty panel
+    pub fn nested_levels(widgets: &[DraggableWidget], widget_id: usize) -> usize {
+        Self::nested_levels_recursive(widgets, widget_id, &mut HashSet::new())
+    }
+
+    fn nested_levels_recursive(widgets: &[DraggableWidget], widget_id: usize, visited: &mut HashSet<usize>) -> usize {
+        if !visited.insert(widget_id) {
+            return 0;
+        }
+        let children = match widgets.iter().find(|w| w.id == widget_id).map(|w| &w.widget_type) {
+            Some(WidgetType::Panel { contained_widgets, .. }) |
+            Some(WidgetType::Settings { contained_widgets, .. }) => contained_widgets,
+            _ => return 0,
+        };
+        1 + children.iter()
+            .map(|&child| Self::nested_levels_recursive(widgets, child, visited))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Check if a widget may be placed inside a panel without a cycle or more than `max_depth` levels of panels
+    pub fn can_nest(widgets: &[DraggableWidget], widget_id: usize, target_panel_id: usize, max_depth: usize) -> bool {
+        !Self::would_create_cycle(widgets, widget_id, target_panel_id)
+            && Self::nesting_depth(widgets, target_panel_id) + Self::nested_levels(widgets, widget_id) <= max_depth
+    }
+
+    /// Find the ID of the first accepting panel under a screen position
+    pub fn find_panel_under_position(widgets: &[DraggableWidget], pos: Pos2) -> Option<usize> {
+        widgets.iter()
+            .find(|widget| Self::is_panel_accepting_widgets(widget) && widget.get_rect().contains(pos))
+            .map(|widget| widget.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::widgets::types::WidgetColor;
+
+    fn panel(id: usize, contained_widgets: Vec<usize>) -> DraggableWidget {
+        let widget_type = WidgetType::Panel {
+            title: format!("PANEL {id}"),
+            color: WidgetColor::Cyan,
+            width: 300.0,
+            height: 200.0,
+            collapsed: false,
+            contained_widgets,
+            minimize_to_settings_icon: false,
+            grid_layout: false,
+        };
+        DraggableWidget::new(id, widget_type, Pos2::ZERO)
+    }
+
+    /// Grandparent 0 holds parent 1, which holds grandchild 2; panel 3 is on the canvas
+    fn three_levels() -> Vec<DraggableWidget> {
+        vec![panel(0, vec![1]), panel(1, vec![2]), panel(2, Vec::new()), panel(3, Vec::new())]
+    }
+
+    #[test]
+    fn panel_cannot_nest_in_itself() {
+        let widgets = three_levels();
+        assert!(PanelManager::would_create_cycle(&widgets, 3, 3));
+        assert!(PanelManager::would_create_cycle(&widgets, 0, 0));
+        assert!(!PanelManager::can_nest(&widgets, 3, 3, 8));
+    }
+
+    #[test]
+    fn grandparent_cannot_nest_in_its_grandchild() {
+        let widgets = three_levels();
+        assert!(PanelManager::would_create_cycle(&widgets, 0, 2));
+        assert!(PanelManager::would_create_cycle(&widgets, 0, 1));
+        assert!(!PanelManager::can_nest(&widgets, 0, 2, 8));
+
+        // Moving down the tree the other way is fine
+        assert!(!PanelManager::would_create_cycle(&widgets, 2, 0));
+        assert!(PanelManager::can_nest(&widgets, 3, 2, 8));
+    }
+
+    #[test]
+    fn nesting_depth_counts_panels_down_from_the_canvas() {
+        let widgets = three_levels();
+        assert_eq!(PanelManager::nesting_depth(&widgets, 0), 1);
+        assert_eq!(PanelManager::nesting_depth(&widgets, 2), 3);
+        assert_eq!(PanelManager::nested_levels(&widgets, 0), 3);
+
+        // Panel 3 inside the grandchild would be the fourth level
+        assert!(!PanelManager::can_nest(&widgets, 3, 2, 3));
+        assert!(PanelManager::can_nest(&widgets, 3, 2, 4));
+    }
+}
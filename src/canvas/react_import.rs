@@ -0,0 +1,204 @@
+//! Importer for layouts exported from the React version of the control surface
+//!
+//! The React app builds its console from `KnobControl`, `Slider`, `ToggleSwitch`,
+//! `PushButton` and `VUMeter` components inside titled cards. An exported layout
+//! lists those components with their props and a position:
+//!
+//! ```json
+//! { "widgets": [
+//!     { "type": "panel",    "title": "MASTER CONTROL", "color": "cyan", "x": 50, "y": 50, "width": 220, "height": 180 },
+//!     { "type": "knob",     "label": "VOLUME", "value": 75, "color": "cyan", "x": 80, "y": 100 },
+//!     { "type": "slider",   "label": "CH1", "value": 60, "vertical": true, "x": 50, "y": 350 },
+//!     { "type": "toggle",   "label": "REVERB", "isOn": true, "color": "pink", "x": 320, "y": 220 },
+//!     { "type": "button",   "label": "POWER", "icon": "Power", "isActive": true, "x": 80, "y": 250 },
+//!     { "type": "vu-meter", "label": "L", "level": 75, "color": "green", "x": 580, "y": 100 },
+//!     { "type": "label",    "text": "AUDIO CONTROL MATRIX", "size": 24, "x": 300, "y": 20 }
+//! ] }
+//! ```
+//!
+//! Positions are relative to the top-left of the layout and values use the
+//! React 0..100 range. Prop names follow the React components (`isOn`,
+//! `isActive`, `vertical`). Unknown widget types are skipped with a logged
+//! warning, and unknown colors fall back to the component's default color, as
+//! they do in the React app.
+
+use egui::Pos2;
+use serde::Deserialize;
+
+use super::panels::PanelManager;
+use super::widgets::types::{DraggableWidget, WidgetColor, WidgetType};
+
+/// Top-level shape of a React layout export
+#[derive(Debug, Deserialize)]
+pub struct ReactLayout {
+    pub widgets: Vec<ReactWidget>,
+}
+
+/// One React component with its props; props a component doesn't use are ignored
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ReactWidget {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub x: f32,
+    pub y: f32,
+    pub label: String,
+    pub color: Option<String>,
+    pub value: f32,
+    pub vertical: bool,    // Slider orientation
+    pub is_on: bool,       // ToggleSwitch state
+    pub is_active: bool,   // PushButton state
+    pub icon: String,      // Lucide icon name, e.g. "Power"
+    pub level: f32,        // VUMeter level
+    pub title: String,     // Panel heading
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+    pub text: String,      // Heading text
+    pub size: Option<f32>, // Heading font size
+}
+
+/// Map a React color class name (`"cyan"`, `"pink"`, ...) or a `"#rrggbb"` hex color to a widget color
+pub fn color_from_name(name: &str) -> Option<WidgetColor> {
+    match name.to_ascii_lowercase().as_str() {
+        "cyan" => Some(WidgetColor::Cyan),
+        "pink" => Some(WidgetColor::Pink),
+        "green" => Some(WidgetColor::Green),
+        "yellow" => Some(WidgetColor::Yellow),
+        "red" => Some(WidgetColor::Red),
+        hex => {
+            let digits = hex.strip_prefix('#').filter(|digits| digits.len() == 6)?;
+            let channel = |at: usize| u8::from_str_radix(digits.get(at..at + 2)?, 16).ok();
+            Some(WidgetColor::Custom(channel(0)?, channel(2)?, channel(4)?))
+        }
+    }
+}
+
+/// Glyph drawn for a Lucide icon name on an imported push button
+fn icon_glyph(name: &str) -> String {
+    match name.to_ascii_lowercase().as_str() {
+        "power" | "zap" => "⚡".to_string(),
+        "play" => "▶".to_string(),
+        "pause" => "⏸".to_string(),
+        "skipback" | "skip-back" => "⏮".to_string(),
+        "skipforward" | "skip-forward" => "⏭".to_string(),
+        "settings" => "⚙".to_string(),
+        "mic" => "🎤".to_string(),
+        "volume" | "volume2" => "🔊".to_string(),
+        _ => "●".to_string(),
+    }
+}
+
+impl ReactWidget {
+    fn color_or(&self, default: WidgetColor) -> WidgetColor {
+        match self.color.as_deref() {
+            Some(name) => color_from_name(name).unwrap_or_else(|| {
+                log::warn!("Unknown React color {:?} on {:?}, using the default", name, self.kind);
+                default
+            }),
+            None => default,
+        }
+    }
+
+    /// The matching widget type, or None for component types we don't know
+    pub fn to_widget_type(&self) -> Option<WidgetType> {
+        let widget_type = match self.kind.to_ascii_lowercase().as_str() {
+            "knob" | "knobcontrol" => WidgetType::Knob {
+                value: self.value.clamp(0.0, 100.0),
+                min: 0.0,
+                max: 100.0,
+                label: self.label.clone(),
+                color: self.color_or(WidgetColor::Cyan),
+                default_value: None,
+            },
+            "slider" if self.vertical => WidgetType::VerticalSlider {
+                value: self.value.clamp(0.0, 100.0),
+                min: 0.0,
+                max: 100.0,
+                label: self.label.clone(),
+                color: self.color_or(WidgetColor::Cyan),
+                default_value: None,
+            },
+            "slider" => WidgetType::HorizontalSlider {
+                value: self.value.clamp(0.0, 100.0),
+                min: 0.0,
+                max: 100.0,
+                label: self.label.clone(),
+                color: self.color_or(WidgetColor::Cyan),
+                default_value: None,
+            },
+            "toggle" | "toggleswitch" => WidgetType::ToggleSwitch {
+                on: self.is_on,
+                label: self.label.clone(),
+                color: self.color_or(WidgetColor::Cyan),
+                glow: true,
+            },
+            "button" | "pushbutton" => WidgetType::PushButton {
+                active: self.is_active,
+                icon: icon_glyph(&self.icon),
+                label: self.label.clone(),
+                color: self.color_or(WidgetColor::Cyan),
+                size: self.size.unwrap_or(48.0),
+                momentary: false,
+            },
+            "vu-meter" | "vumeter" => WidgetType::VuMeter {
+                level: self.level.clamp(0.0, 100.0),
+                peak_level: self.level.clamp(0.0, 100.0),
+                label: self.label.clone(),
+                color: self.color_or(WidgetColor::Green),
+            },
+            "panel" => WidgetType::Panel {
+                title: self.title.clone(),
+                color: self.color_or(WidgetColor::Cyan),
+                width: self.width.unwrap_or(220.0),
+                height: self.height.unwrap_or(200.0),
+                collapsed: false,
+                contained_widgets: Vec::new(),
+                minimize_to_settings_icon: false,
+                grid_layout: false,
+            },
+            "label" | "heading" => WidgetType::TextLabel {
+                text: self.text.clone(),
+                size: self.size.unwrap_or(16.0),
+                color: self.color_or(WidgetColor::Cyan),
+            },
+            _ => return None,
+        };
+        Some(widget_type)
+    }
+}
+
+/// Parse a React layout export into widgets with ids `0..n` and layout-relative positions
+///
+/// Widgets whose center falls inside an imported panel are placed in that
+/// panel, mirroring the card nesting of the React app.
+pub fn parse_react_layout(json: &str) -> Result<Vec<DraggableWidget>, serde_json::Error> {
+    let layout: ReactLayout = serde_json::from_str(json)?;
+
+    let mut widgets: Vec<DraggableWidget> = Vec::new();
+    for react_widget in &layout.widgets {
+        match react_widget.to_widget_type() {
+            Some(widget_type) => {
+                let id = widgets.len();
+                widgets.push(DraggableWidget::new(id, widget_type, Pos2::new(react_widget.x, react_widget.y)));
+            }
+            None => log::warn!("Skipping React widget with unknown type {:?}", react_widget.kind),
+        }
+    }
+
+    // Put each widget into the topmost panel under its center
+    for idx in 0..widgets.len() {
+        if matches!(widgets[idx].widget_type, WidgetType::Panel { .. }) {
+            continue;
+        }
+        let center = widgets[idx].get_rect().center();
+        let container = widgets.iter().rposition(|panel| {
+            matches!(panel.widget_type, WidgetType::Panel { .. }) && panel.get_rect().contains(center)
+        });
+        if let Some(panel_idx) = container {
+            let widget_id = widgets[idx].id;
+            PanelManager::add_widget_to_panel(&mut widgets, panel_idx, widget_id);
+        }
+    }
+
+    Ok(widgets)
+}
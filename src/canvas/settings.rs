@@ -0,0 +1,177 @@
+//! Canvas-wide configuration edited from the settings popup
+//!
+//! Holds the user-facing options that apply to the whole canvas rather than
+//! to a single widget: snapping, grid spacing and margins, theme and accent,
+//! tooltips, palette width, placement mode, knob drag sensitivity, link
+//! display, flick inertia, change flashes, widget spawn hotkeys, the
+//! fixed design size, how widgets follow a canvas resize and how deeply
+//! panels may nest.
+
+use egui::{Color32, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::constants::*;
+use crate::canvas::widgets::types::{WidgetColor, WidgetType};
+
+/// Background themes for the canvas area
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CanvasTheme {
+    Dark,  // Pure black, matching the React app
+    Slate, // Dark gray-blue
+}
+
+impl CanvasTheme {
+    pub fn background_color(self) -> Color32 {
+        match self {
+            CanvasTheme::Dark => BLACK,
+            CanvasTheme::Slate => GRAY_900,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CanvasTheme::Dark => "Dark",
+            CanvasTheme::Slate => "Slate",
+        }
+    }
+}
+
+/// How top-level widgets follow a change of canvas size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResizePolicy {
+    Regrid,       // Rebuild the right-to-left auto-layout grid, discarding manual placement
+    Proportional, // Keep each widget's center at the same fraction of the canvas width and height
+    Fixed,        // Leave widgets where they are, only pulling them back inside the canvas
+}
+
+impl ResizePolicy {
+    pub fn name(self) -> &'static str {
+        match self {
+            ResizePolicy::Regrid => "Regrid",
+            ResizePolicy::Proportional => "Proportional",
+            ResizePolicy::Fixed => "Fixed",
+        }
+    }
+}
+
+/// Widgets that can be spawned at the cursor with a single key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpawnKind {
+    Knob,
+    Toggle,
+    Button,
+    VuMeter,
+    HorizontalSlider,
+    VerticalSlider,
+    Label,
+    Panel,
+}
+
+impl SpawnKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            SpawnKind::Knob => "Knob",
+            SpawnKind::Toggle => "Toggle",
+            SpawnKind::Button => "Button",
+            SpawnKind::VuMeter => "VU Meter",
+            SpawnKind::HorizontalSlider => "H.Slider",
+            SpawnKind::VerticalSlider => "V.Slider",
+            SpawnKind::Label => "Label",
+            SpawnKind::Panel => "Panel",
+        }
+    }
+    
+    /// The widget its palette button spawns, in the given accent color
+    pub fn widget_type(self, color: WidgetColor) -> WidgetType {
+        match self {
+            SpawnKind::Knob => WidgetType::Knob { value: 50.0, min: 0.0, max: 100.0, label: "KNOB".to_string(), color, default_value: None },
+            SpawnKind::Toggle => WidgetType::ToggleSwitch { on: false, label: "TOGGLE".to_string(), color, glow: true },
+            SpawnKind::Button => WidgetType::PushButton { active: false, icon: "▶".to_string(), label: "PLAY".to_string(), color, size: 48.0, momentary: false },
+            SpawnKind::VuMeter => WidgetType::VuMeter { level: 75.0, peak_level: 80.0, label: "VU".to_string(), color },
+            SpawnKind::HorizontalSlider => WidgetType::HorizontalSlider { value: 60.0, min: 0.0, max: 100.0, label: "LEVEL".to_string(), color, default_value: None },
+            SpawnKind::VerticalSlider => WidgetType::VerticalSlider { value: 75.0, min: 0.0, max: 100.0, label: "CH1".to_string(), color, default_value: None },
+            SpawnKind::Label => WidgetType::TextLabel { text: "LABEL".to_string(), size: 16.0, color },
+            SpawnKind::Panel => WidgetType::Panel {
+                title: "CONTROL PANEL".to_string(),
+                color,
+                width: 200.0,
+                height: 150.0,
+                collapsed: false,
+                contained_widgets: Vec::new(),
+                minimize_to_settings_icon: true,
+                grid_layout: false,
+            },
+        }
+    }
+}
+
+/// Default single-key bindings for spawning widgets
+fn default_spawn_keys() -> Vec<(char, SpawnKind)> {
+    vec![
+        ('k', SpawnKind::Knob),
+        ('t', SpawnKind::Toggle),
+        ('b', SpawnKind::Button),
+        ('v', SpawnKind::VuMeter),
+        ('h', SpawnKind::HorizontalSlider),
+        ('f', SpawnKind::VerticalSlider),
+        ('l', SpawnKind::Label),
+        ('p', SpawnKind::Panel),
+    ]
+}
+
+/// User-configurable canvas options
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CanvasSettings {
+    pub snapping_enabled: bool,             // Snap to alignment guides while dragging
+    pub snap_to_grid: bool,                 // Snap dragged widgets to a grid instead (takes priority over guides)
+    pub snap_grid_size: f32,                // Cell size of the snap grid, measured from the canvas origin
+    pub grid_size: f32,                     // Spacing of the auto-layout grid
+    pub canvas_margin: f32,                 // Gap kept between auto-placed widgets and the canvas edge (0 = full bleed)
+    pub theme: CanvasTheme,
+    pub default_accent: WidgetColor,        // Color given to widgets spawned from the palette
+    pub show_tooltips: bool,                // Show a tooltip for the widget under the pointer
+    pub palette_width: f32,                 // Width of the widget palette side panel
+    pub free_placement: bool,               // Place widgets at the drop position instead of the auto-grid
+    pub right_drag_fine_tune: bool,         // Right-drag on knobs/sliders scrubs the value; right-click still opens the menu
+    pub distance_sensitivity: bool,         // Scale knob drag sensitivity by pointer distance from center
+    pub distance_sensitivity_radius: f32,   // Pointer distance at which knob sensitivity is 1x
+    pub show_links: bool,                   // Draw a curve between each pair of linked widgets
+    pub flick_inertia: bool,                // A widget released mid-drag keeps gliding and settles with friction
+    pub flash_external_changes: bool,       // Briefly outline widgets whose value the host changed
+    pub spawn_keys: Vec<(char, SpawnKind)>, // Key that spawns each widget kind at the cursor (case-insensitive)
+    pub design_size: Option<Vec2>,          // Fixed canvas resolution, scaled uniformly and letterboxed to fit
+    pub resize_policy: ResizePolicy,        // How top-level widgets move when the canvas changes size
+    pub recycle_ids: bool,                  // Give new widgets the IDs of deleted ones instead of always counting up
+    pub delete_panel_cascade: bool,         // Deleting a panel deletes its contents instead of releasing them to the canvas
+    pub max_nesting_depth: usize,           // Most levels of panels inside one another; deeper drops are refused
+}
+
+impl Default for CanvasSettings {
+    fn default() -> Self {
+        Self {
+            snapping_enabled: true,
+            snap_to_grid: false,
+            snap_grid_size: 20.0,
+            grid_size: GRID_SPACING,
+            canvas_margin: CANVAS_MARGIN,
+            theme: CanvasTheme::Dark,
+            default_accent: WidgetColor::Cyan,
+            show_tooltips: true,
+            palette_width: PALETTE_WIDTH,
+            free_placement: false,
+            right_drag_fine_tune: false,
+            distance_sensitivity: false,
+            distance_sensitivity_radius: 80.0,
+            show_links: false,
+            flick_inertia: false,
+            flash_external_changes: false,
+            spawn_keys: default_spawn_keys(),
+            design_size: None,
+            resize_policy: ResizePolicy::Proportional,
+            recycle_ids: false,
+            delete_panel_cascade: false,
+            max_nesting_depth: 4,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+//! Widget definitions and rendering for the drag-and-drop canvas
+
+pub mod properties;
+pub mod rendering;
+pub mod types;
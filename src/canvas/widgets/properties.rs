@@ -0,0 +1,144 @@
+//! Uniform access to the properties shared by many widget types
+//!
+//! Generic code (batch editing, host setters, value resets) goes through these
+//! traits instead of matching on `WidgetType` itself. Every match below names
+//! all variants, with no catch-all arm, so adding a variant fails to compile
+//! until it has been decided whether it has a color, a label and a value.
+
+use super::types::{WidgetColor, WidgetType};
+
+/// Widgets drawn in one of the accent colors
+pub trait HasColor {
+    fn color(&self) -> Option<WidgetColor>;
+    fn color_mut(&mut self) -> Option<&mut WidgetColor>;
+}
+
+/// Widgets with caption text: a label, a text label's text, or a panel's title
+pub trait HasLabel {
+    fn label(&self) -> Option<&str>;
+    fn label_mut(&mut self) -> Option<&mut String>;
+}
+
+/// Widgets with a numeric value, and for user-adjustable ones its (min, max) range
+pub trait HasValue {
+    fn value(&self) -> Option<f32>;
+    fn value_mut(&mut self) -> Option<&mut f32>;
+    fn range(&self) -> Option<(f32, f32)>;
+    fn range_mut(&mut self) -> Option<(&mut f32, &mut f32)>;
+}
+
+impl HasColor for WidgetType {
+    fn color(&self) -> Option<WidgetColor> {
+        match self {
+            WidgetType::Knob { color, .. } | WidgetType::ToggleSwitch { color, .. } | WidgetType::PushButton { color, .. } |
+            WidgetType::VuMeter { color, .. } | WidgetType::HorizontalSlider { color, .. } | WidgetType::VerticalSlider { color, .. } |
+            WidgetType::TextLabel { color, .. } | WidgetType::Panel { color, .. } | WidgetType::IconButton { color, .. } |
+            WidgetType::Settings { color, .. } | WidgetType::Readout { color, .. } | WidgetType::MeterWithReadout { color, .. } |
+            WidgetType::TimeDisplay { color, .. } | WidgetType::RegionSelector { color, .. } | WidgetType::XyPad { color, .. } |
+            WidgetType::RotaryEncoder { color, .. } | WidgetType::SpectrumAnalyzer { color, .. } => Some(*color),
+            WidgetType::LevelIndicator { .. } | WidgetType::StatusBar { .. } | WidgetType::Custom { .. } => None,
+        }
+    }
+
+    fn color_mut(&mut self) -> Option<&mut WidgetColor> {
+        match self {
+            WidgetType::Knob { color, .. } | WidgetType::ToggleSwitch { color, .. } | WidgetType::PushButton { color, .. } |
+            WidgetType::VuMeter { color, .. } | WidgetType::HorizontalSlider { color, .. } | WidgetType::VerticalSlider { color, .. } |
+            WidgetType::TextLabel { color, .. } | WidgetType::Panel { color, .. } | WidgetType::IconButton { color, .. } |
+            WidgetType::Settings { color, .. } | WidgetType::Readout { color, .. } | WidgetType::MeterWithReadout { color, .. } |
+            WidgetType::TimeDisplay { color, .. } | WidgetType::RegionSelector { color, .. } | WidgetType::XyPad { color, .. } |
+            WidgetType::RotaryEncoder { color, .. } | WidgetType::SpectrumAnalyzer { color, .. } => Some(color),
+            WidgetType::LevelIndicator { .. } | WidgetType::StatusBar { .. } | WidgetType::Custom { .. } => None,
+        }
+    }
+}
+
+impl HasLabel for WidgetType {
+    fn label(&self) -> Option<&str> {
+        match self {
+            WidgetType::Knob { label, .. } | WidgetType::ToggleSwitch { label, .. } | WidgetType::PushButton { label, .. } |
+            WidgetType::VuMeter { label, .. } | WidgetType::HorizontalSlider { label, .. } | WidgetType::VerticalSlider { label, .. } |
+            WidgetType::LevelIndicator { label, .. } | WidgetType::IconButton { label, .. } | WidgetType::Settings { label, .. } |
+            WidgetType::Readout { label, .. } => Some(label),
+            WidgetType::TextLabel { text, .. } => Some(text),
+            WidgetType::Panel { title, .. } => Some(title),
+            WidgetType::StatusBar { .. } | WidgetType::MeterWithReadout { .. } | WidgetType::TimeDisplay { .. } |
+            WidgetType::RegionSelector { .. } | WidgetType::XyPad { .. } | WidgetType::RotaryEncoder { .. } |
+            WidgetType::SpectrumAnalyzer { .. } | WidgetType::Custom { .. } => None,
+        }
+    }
+
+    fn label_mut(&mut self) -> Option<&mut String> {
+        match self {
+            WidgetType::Knob { label, .. } | WidgetType::ToggleSwitch { label, .. } | WidgetType::PushButton { label, .. } |
+            WidgetType::VuMeter { label, .. } | WidgetType::HorizontalSlider { label, .. } | WidgetType::VerticalSlider { label, .. } |
+            WidgetType::LevelIndicator { label, .. } | WidgetType::IconButton { label, .. } | WidgetType::Settings { label, .. } |
+            WidgetType::Readout { label, .. } => Some(label),
+            WidgetType::TextLabel { text, .. } => Some(text),
+            WidgetType::Panel { title, .. } => Some(title),
+            WidgetType::StatusBar { .. } | WidgetType::MeterWithReadout { .. } | WidgetType::TimeDisplay { .. } |
+            WidgetType::RegionSelector { .. } | WidgetType::XyPad { .. } | WidgetType::RotaryEncoder { .. } |
+            WidgetType::SpectrumAnalyzer { .. } | WidgetType::Custom { .. } => None,
+        }
+    }
+}
+
+/// Meters, spectrum analyzers, time displays and region selectors carry levels and times the host
+/// feeds through dedicated setters, so they are not treated as values here.
+/// XY pads hold two values and rotary encoders a step index rather than one
+/// continuous value, so they are left out as well.
+impl HasValue for WidgetType {
+    fn value(&self) -> Option<f32> {
+        match self {
+            WidgetType::Knob { value, .. } | WidgetType::HorizontalSlider { value, .. } | WidgetType::VerticalSlider { value, .. } |
+            WidgetType::Readout { value, .. } => Some(*value),
+            WidgetType::ToggleSwitch { .. } | WidgetType::PushButton { .. } | WidgetType::VuMeter { .. } |
+            WidgetType::LevelIndicator { .. } | WidgetType::TextLabel { .. } | WidgetType::Panel { .. } |
+            WidgetType::StatusBar { .. } | WidgetType::IconButton { .. } | WidgetType::Settings { .. } |
+            WidgetType::MeterWithReadout { .. } | WidgetType::TimeDisplay { .. } | WidgetType::RegionSelector { .. } |
+            WidgetType::XyPad { .. } | WidgetType::RotaryEncoder { .. } |
+            WidgetType::SpectrumAnalyzer { .. } | WidgetType::Custom { .. } => None,
+        }
+    }
+
+    fn value_mut(&mut self) -> Option<&mut f32> {
+        match self {
+            WidgetType::Knob { value, .. } | WidgetType::HorizontalSlider { value, .. } | WidgetType::VerticalSlider { value, .. } |
+            WidgetType::Readout { value, .. } => Some(value),
+            WidgetType::ToggleSwitch { .. } | WidgetType::PushButton { .. } | WidgetType::VuMeter { .. } |
+            WidgetType::LevelIndicator { .. } | WidgetType::TextLabel { .. } | WidgetType::Panel { .. } |
+            WidgetType::StatusBar { .. } | WidgetType::IconButton { .. } | WidgetType::Settings { .. } |
+            WidgetType::MeterWithReadout { .. } | WidgetType::TimeDisplay { .. } | WidgetType::RegionSelector { .. } |
+            WidgetType::XyPad { .. } | WidgetType::RotaryEncoder { .. } |
+            WidgetType::SpectrumAnalyzer { .. } | WidgetType::Custom { .. } => None,
+        }
+    }
+
+    fn range(&self) -> Option<(f32, f32)> {
+        match self {
+            WidgetType::Knob { min, max, .. } | WidgetType::HorizontalSlider { min, max, .. } | WidgetType::VerticalSlider { min, max, .. } => {
+                Some((*min, *max))
+            }
+            WidgetType::ToggleSwitch { .. } | WidgetType::PushButton { .. } | WidgetType::VuMeter { .. } |
+            WidgetType::LevelIndicator { .. } | WidgetType::TextLabel { .. } | WidgetType::Panel { .. } |
+            WidgetType::StatusBar { .. } | WidgetType::IconButton { .. } | WidgetType::Settings { .. } |
+            WidgetType::Readout { .. } | WidgetType::MeterWithReadout { .. } | WidgetType::TimeDisplay { .. } |
+            WidgetType::RegionSelector { .. } | WidgetType::XyPad { .. } | WidgetType::RotaryEncoder { .. } |
+            WidgetType::SpectrumAnalyzer { .. } | WidgetType::Custom { .. } => None,
+        }
+    }
+
+    fn range_mut(&mut self) -> Option<(&mut f32, &mut f32)> {
+        match self {
+            WidgetType::Knob { min, max, .. } | WidgetType::HorizontalSlider { min, max, .. } | WidgetType::VerticalSlider { min, max, .. } => {
+                Some((min, max))
+            }
+            WidgetType::ToggleSwitch { .. } | WidgetType::PushButton { .. } | WidgetType::VuMeter { .. } |
+            WidgetType::LevelIndicator { .. } | WidgetType::TextLabel { .. } | WidgetType::Panel { .. } |
+            WidgetType::StatusBar { .. } | WidgetType::IconButton { .. } | WidgetType::Settings { .. } |
+            WidgetType::Readout { .. } | WidgetType::MeterWithReadout { .. } | WidgetType::TimeDisplay { .. } |
+            WidgetType::RegionSelector { .. } | WidgetType::XyPad { .. } | WidgetType::RotaryEncoder { .. } |
+            WidgetType::SpectrumAnalyzer { .. } | WidgetType::Custom { .. } => None,
+        }
+    }
+}
@@ -8,15 +8,58 @@ use egui::{Color32, Pos2, Rect, Vec2, FontId, Align2, Stroke};
 use std::f32::consts::PI;
 
 use crate::canvas::constants::*;
-use super::types::{WidgetColor, IconType, CanvasEdge};
+use super::types::{WidgetColor, IconType, CanvasEdge, TimeMode, ValueFormat, StatusPrecision};
 
-pub fn render_knob(painter: &egui::Painter, rect: Rect, value: &mut f32, min: f32, max: f32, label: &str, color: WidgetColor) {
-    let knob_rect = Rect::from_center_size(
-        Pos2::new(rect.center().x, rect.top() + 37.0),
-        Vec2::splat(64.0),
-    );
+/// Bounding box of a knob's dial: a 64pt circle centered 37pt below the widget top
+pub fn knob_dial_rect(rect: Rect) -> Rect {
+    Rect::from_center_size(Pos2::new(rect.center().x, rect.top() + 37.0), Vec2::splat(64.0))
+}
+
+/// Track of a horizontal slider, shifted right of center to leave room for the label
+pub fn horizontal_slider_track_rect(rect: Rect) -> Rect {
+    Rect::from_center_size(Pos2::new(rect.center().x + 10.0, rect.center().y), Vec2::new(96.0, 8.0))
+}
+
+/// Track of a vertical slider, shifted up to leave room for the value
+pub fn vertical_slider_track_rect(rect: Rect) -> Rect {
+    Rect::from_center_size(Pos2::new(rect.center().x, rect.center().y - 10.0), Vec2::new(8.0, 96.0))
+}
+
+/// Bottom-right grip used to resize widgets
+pub fn resize_handle_rect(rect: Rect) -> Rect {
+    let handle_size = 12.0;
+    Rect::from_min_size(rect.max - Vec2::splat(handle_size), Vec2::splat(handle_size))
+}
+
+/// Three diagonal lines marking a resize grip
+pub fn render_resize_grip(painter: &egui::Painter, handle_rect: Rect, color: Color32) {
+    for i in 0..3 {
+        let offset = i as f32 * 3.0;
+        painter.line_segment(
+            [
+                Pos2::new(handle_rect.min.x + offset, handle_rect.max.y - 2.0),
+                Pos2::new(handle_rect.max.x - 2.0, handle_rect.min.y + offset),
+            ],
+            Stroke::new(1.0, color),
+        );
+    }
+}
+
+/// Value text color: the accent while a control is hovered, selected or being
+/// adjusted, dimmed otherwise so a dense canvas stays quiet
+fn value_text_color(color: WidgetColor, active: bool) -> Color32 {
+    if active {
+        color.to_color32()
+    } else {
+        color.to_color32().gamma_multiply(0.45)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn render_knob(painter: &egui::Painter, rect: Rect, value: &mut f32, min: f32, max: f32, label: &str, color: WidgetColor, format: Option<ValueFormat>, unit: &str, active: bool) {
+    let knob_rect = knob_dial_rect(rect);
     let center = knob_rect.center();
-    let radius = 32.0;
+    let radius = knob_rect.width() / 2.0;
     let normalized = (*value - min) / (max - min);
     let angle = normalized * 270.0 * PI / 180.0 - 135.0 * PI / 180.0;
 
@@ -68,9 +111,56 @@ pub fn render_knob(painter: &egui::Painter, rect: Rect, value: &mut f32, min: f3
     painter.text(
         Pos2::new(center.x, rect.bottom() - 15.0),
         Align2::CENTER_CENTER,
-        format!("{:.1}", value),
+        ValueFormat::with_unit(format, format.map_or_else(|| format!("{:.1}", value), |f| f.format(*value, min, max)), unit),
         FontId::monospace(10.0),
-        color.to_color32(),
+        value_text_color(color, active),
+    );
+}
+
+/// Stepped dial: one tick per step over the knob's 270° sweep, the current step lit
+pub fn render_rotary_encoder(painter: &egui::Painter, rect: Rect, value: usize, steps: usize, labels: &[String], color: WidgetColor, active: bool) {
+    let dial_rect = knob_dial_rect(rect);
+    let center = dial_rect.center();
+    let radius = dial_rect.width() / 2.0;
+    let angle_at = |step: usize| {
+        let normalized = if steps > 1 { step as f32 / (steps - 1) as f32 } else { 0.5 };
+        normalized * 270.0 * PI / 180.0 - 135.0 * PI / 180.0
+    };
+
+    // Draw outer ring
+    painter.circle_filled(center, radius, GRAY_900);
+    painter.circle_stroke(center, radius, Stroke::new(4.0, GRAY_700));
+
+    // Draw step ticks
+    for step in 0..steps {
+        let a = angle_at(step);
+        let direction = Vec2::new(a.cos(), a.sin());
+        let (inner_radius, stroke) = if step == value {
+            (radius - 12.0, Stroke::new(3.0, color.to_color32()))
+        } else {
+            (radius - 8.0, Stroke::new(2.0, GRAY_600))
+        };
+        painter.line_segment([center + direction * inner_radius, center + direction * (radius - 3.0)], stroke);
+    }
+
+    // Draw inner circle
+    painter.circle_filled(center, radius - 14.0, GRAY_800);
+
+    // Draw indicator line
+    let a = angle_at(value);
+    painter.line_segment([center, center + Vec2::new(a.cos(), a.sin()) * (radius - 18.0)], Stroke::new(4.0, color.to_color32()));
+
+    // Draw center dot
+    painter.circle_filled(center, 4.0, color.to_color32());
+
+    // Draw step label, or the step number if it has none
+    let step_label = labels.get(value).filter(|label| !label.is_empty()).cloned().unwrap_or_else(|| (value + 1).to_string());
+    painter.text(
+        Pos2::new(center.x, rect.bottom() - 15.0),
+        Align2::CENTER_CENTER,
+        step_label,
+        FontId::monospace(10.0),
+        value_text_color(color, active),
     );
 }
 
@@ -139,11 +229,13 @@ pub fn render_toggle_switch(painter: &egui::Painter, rect: Rect, on: &mut bool,
     }
 }
 
+/// Face of a push or icon button of the given size, the part that presses
+pub fn button_face_rect(rect: Rect, size: f32) -> Rect {
+    Rect::from_center_size(Pos2::new(rect.center().x, rect.top() + size / 2.0 + 5.0), Vec2::splat(size))
+}
+
 pub fn render_push_button(painter: &egui::Painter, rect: Rect, active: &mut bool, icon: &str, label: &str, color: WidgetColor, size: f32) {
-    let button_rect = Rect::from_center_size(
-        Pos2::new(rect.center().x, rect.top() + size / 2.0 + 5.0),
-        Vec2::splat(size),
-    );
+    let button_rect = button_face_rect(rect, size);
 
     let (fill_color, _stroke_color) = if *active {
         (Color32::from_rgba_unmultiplied(color.to_color32().r(), color.to_color32().g(), color.to_color32().b(), 60), color.to_color32())
@@ -176,17 +268,18 @@ pub fn render_push_button(painter: &egui::Painter, rect: Rect, active: &mut bool
     );
 }
 
-pub fn render_vu_meter(painter: &egui::Painter, rect: Rect, level: f32, peak_level: &mut f32, label: &str, color: WidgetColor) {
+pub fn render_vu_meter(painter: &egui::Painter, rect: Rect, level: f32, peak_level: &mut f32, label: &str, color: WidgetColor, dt: f32) {
     let meter_rect = Rect::from_center_size(
         Pos2::new(rect.center().x, rect.top() + 69.0),
         Vec2::new(16.0, 128.0),
     );
 
-    // Update peak level
-    if level > *peak_level {
+    // Update peak level; the fall is per second so it looks the same at any frame rate
+    if level >= *peak_level {
         *peak_level = level;
-    } else {
-        *peak_level = (*peak_level - 0.5).max(0.0);
+    } else if dt > 0.0 {
+        *peak_level = (*peak_level - PEAK_FALL_RATE * dt).max(level);
+        painter.ctx().request_repaint(); // Keep falling while the host is idle
     }
 
     // Draw background
@@ -242,7 +335,8 @@ pub fn render_vu_meter(painter: &egui::Painter, rect: Rect, level: f32, peak_lev
     );
 }
 
-pub fn render_horizontal_slider(painter: &egui::Painter, rect: Rect, value: &mut f32, min: f32, max: f32, label: &str, color: WidgetColor) {
+#[allow(clippy::too_many_arguments)]
+pub fn render_horizontal_slider(painter: &egui::Painter, rect: Rect, value: &mut f32, min: f32, max: f32, label: &str, color: WidgetColor, format: Option<ValueFormat>, unit: &str, active: bool) {
     let normalized = (*value - min) / (max - min);
 
     // Draw label
@@ -254,10 +348,7 @@ pub fn render_horizontal_slider(painter: &egui::Painter, rect: Rect, value: &mut
         GRAY_400,
     );
 
-    let slider_rect = Rect::from_center_size(
-        Pos2::new(rect.center().x + 10.0, rect.center().y),
-        Vec2::new(96.0, 8.0),
-    );
+    let slider_rect = horizontal_slider_track_rect(rect);
 
     // Draw background
     painter.rect_filled(slider_rect, 4.0, GRAY_700);
@@ -274,19 +365,17 @@ pub fn render_horizontal_slider(painter: &egui::Painter, rect: Rect, value: &mut
     painter.text(
         Pos2::new(rect.right() - 15.0, rect.center().y),
         Align2::CENTER_CENTER,
-        format!("{:.0}", value),
+        ValueFormat::with_unit(format, format.map_or_else(|| format!("{:.0}", value), |f| f.format(*value, min, max)), unit),
         FontId::monospace(10.0),
-        color.to_color32(),
+        value_text_color(color, active),
     );
 }
 
-pub fn render_vertical_slider(painter: &egui::Painter, rect: Rect, value: &mut f32, min: f32, max: f32, _label: &str, color: WidgetColor) {
+#[allow(clippy::too_many_arguments)]
+pub fn render_vertical_slider(painter: &egui::Painter, rect: Rect, value: &mut f32, min: f32, max: f32, _label: &str, color: WidgetColor, format: Option<ValueFormat>, unit: &str, active: bool) {
     let normalized = (*value - min) / (max - min);
 
-    let slider_rect = Rect::from_center_size(
-        Pos2::new(rect.center().x, rect.center().y - 10.0),
-        Vec2::new(8.0, 96.0),
-    );
+    let slider_rect = vertical_slider_track_rect(rect);
 
     // Draw background
     painter.rect_filled(slider_rect, 4.0, GRAY_700);
@@ -303,14 +392,14 @@ pub fn render_vertical_slider(painter: &egui::Painter, rect: Rect, value: &mut f
     painter.text(
         Pos2::new(rect.center().x, rect.bottom() - 15.0),
         Align2::CENTER_CENTER,
-        format!("{:.0}", value),
+        ValueFormat::with_unit(format, format.map_or_else(|| format!("{:.0}", value), |f| f.format(*value, min, max)), unit),
         FontId::monospace(8.0),
-        color.to_color32(),
+        value_text_color(color, active),
     );
 }
 
 pub fn render_level_indicator(painter: &egui::Painter, rect: Rect, level: f32, segments: usize, label: &str) {
-    let colors = vec![GREEN, GREEN, GREEN, GREEN, GREEN, YELLOW, YELLOW, RED];
+    let colors = [GREEN, GREEN, GREEN, GREEN, GREEN, YELLOW, YELLOW, RED];
     let indicator_rect = Rect::from_center_size(
         Pos2::new(rect.center().x, rect.center().y - 5.0),
         Vec2::new(rect.width() - 20.0, 20.0)
@@ -362,7 +451,255 @@ pub fn render_text_label(painter: &egui::Painter, rect: Rect, text: &str, size:
     );
 }
 
-pub fn render_panel(painter: &egui::Painter, rect: Rect, title: &str, color: WidgetColor, collapsed: bool, contained_widgets: &Vec<usize>, minimize_to_settings_icon: bool) {
+/// Numeric display; readouts have no range, so `ValueFormat::Percent` reads the value as 0..100
+pub fn render_readout(painter: &egui::Painter, rect: Rect, value: f32, unit: &str, format: ValueFormat, color: WidgetColor, label: &str) {
+    let display_rect = Rect::from_min_max(
+        rect.min + Vec2::splat(4.0),
+        Pos2::new(rect.right() - 4.0, rect.bottom() - 22.0),
+    );
+
+    // Draw display background
+    painter.rect_filled(display_rect, 4.0, GRAY_900);
+    painter.rect_stroke(display_rect, 4.0, Stroke::new(1.0, GRAY_700), egui::StrokeKind::Inside);
+
+    // Draw unit at the right edge, then the number right-aligned against it
+    let mut number_right = display_rect.right() - 8.0;
+    if !unit.is_empty() {
+        let unit_rect = painter.text(
+            Pos2::new(number_right, display_rect.center().y + 4.0),
+            Align2::RIGHT_CENTER,
+            unit,
+            FontId::monospace(12.0),
+            GRAY_400,
+        );
+        number_right = unit_rect.left() - 4.0;
+    }
+
+    painter.text(
+        Pos2::new(number_right, display_rect.center().y),
+        Align2::RIGHT_CENTER,
+        format.format(value, 0.0, 100.0),
+        FontId::monospace(26.0),
+        color.to_color32(),
+    );
+
+    // Draw caption
+    if !label.is_empty() {
+        painter.text(
+            Pos2::new(rect.center().x, rect.bottom() - 10.0),
+            Align2::CENTER_CENTER,
+            label,
+            FontId::monospace(10.0),
+            GRAY_400,
+        );
+    }
+}
+
+pub fn render_custom_placeholder(painter: &egui::Painter, rect: Rect, id: &str) {
+    painter.rect_filled(rect, 4.0, GRAY_900);
+    painter.rect_stroke(rect, 4.0, Stroke::new(1.0, GRAY_600), egui::StrokeKind::Inside);
+
+    painter.text(
+        rect.center(),
+        Align2::CENTER_CENTER,
+        format!("? {}", id),
+        FontId::monospace(10.0),
+        GRAY_400,
+    );
+}
+
+/// A VU bar with current and held-peak readouts beside it; levels are in dB
+pub fn render_meter_with_readout(painter: &egui::Painter, rect: Rect, level: f32, peak: f32, unit: &str, color: WidgetColor) {
+    let to_percent = |db: f32| ((db - METER_FLOOR_DB) / -METER_FLOOR_DB * 100.0).clamp(0.0, 100.0);
+
+    // Bar on the left; the held peak is a copy with no elapsed time, so the VU renderer's decay doesn't apply
+    let meter_rect = Rect::from_min_size(rect.min, Vec2::new(26.0, rect.height()));
+    let mut peak_percent = to_percent(peak);
+    render_vu_meter(painter, meter_rect, to_percent(level), &mut peak_percent, "", color, 0.0);
+
+    // Current and peak readouts stacked on the right
+    let readout_left = meter_rect.right() + 4.0;
+    let readout_height = rect.height() / 2.0;
+    let level_rect = Rect::from_min_max(
+        Pos2::new(readout_left, rect.top()),
+        Pos2::new(rect.right(), rect.top() + readout_height),
+    );
+    let peak_rect = Rect::from_min_max(
+        Pos2::new(readout_left, rect.top() + readout_height),
+        rect.max,
+    );
+    render_readout(painter, level_rect, level, unit, ValueFormat::Decimal { places: 1 }, color, "LEVEL");
+    render_readout(painter, peak_rect, peak, unit, ValueFormat::Decimal { places: 1 }, if peak >= 0.0 { WidgetColor::Red } else { color }, "PEAK");
+}
+
+/// One bar per band, scaled to the rect, with a held peak line over each
+///
+/// Bands are 0..1; out-of-range and non-finite values are clamped. Peaks fall back
+/// at the VU meter's rate and follow the band count.
+pub fn render_spectrum_analyzer(painter: &egui::Painter, rect: Rect, bands: &[f32], peaks: &mut Vec<f32>, color: WidgetColor, dt: f32) {
+    painter.rect_filled(rect, 4.0, GRAY_900);
+    peaks.resize(bands.len(), 0.0);
+    if bands.is_empty() {
+        return;
+    }
+
+    let area = rect.shrink(6.0);
+    let slot = area.width() / bands.len() as f32;
+    let gap = (slot * 0.2).min(2.0);
+    let height_at = |level: f32| area.bottom() - level * area.height();
+    let fall = PEAK_FALL_RATE / 100.0 * dt;
+    let mut falling = false;
+
+    for (i, (&band, peak)) in bands.iter().zip(peaks.iter_mut()).enumerate() {
+        let level = if band.is_finite() { band.clamp(0.0, 1.0) } else { 0.0 };
+        if level >= *peak {
+            *peak = level;
+        } else {
+            *peak = (*peak - fall).max(level);
+            falling = true;
+        }
+
+        // Colored like the VU meter's segments: accent, then yellow from 70%, red from 90%
+        let left = area.left() + i as f32 * slot;
+        let right = left + (slot - gap).max(1.0);
+        for (from, to, segment_color) in [(0.0, 0.7, color.to_color32()), (0.7, 0.9, YELLOW), (0.9, 1.0, RED)] {
+            if level > from {
+                let bar = Rect::from_min_max(Pos2::new(left, height_at(level.min(to))), Pos2::new(right, height_at(from)));
+                painter.rect_filled(bar, 0.0, segment_color);
+            }
+        }
+
+        if *peak > 0.0 {
+            painter.line_segment([Pos2::new(left, height_at(*peak)), Pos2::new(right, height_at(*peak))], Stroke::new(1.0, WHITE));
+        }
+    }
+
+    if falling && dt > 0.0 {
+        painter.ctx().request_repaint(); // Keep the peaks falling while the host is idle
+    }
+}
+
+pub fn render_time_display(painter: &egui::Painter, rect: Rect, seconds: f32, mode: TimeMode, color: WidgetColor) {
+    let display_rect = Rect::from_min_max(
+        rect.min + Vec2::splat(4.0),
+        Pos2::new(rect.right() - 4.0, rect.bottom() - 18.0),
+    );
+
+    // Draw display background
+    painter.rect_filled(display_rect, 4.0, GRAY_900);
+    painter.rect_stroke(display_rect, 4.0, Stroke::new(1.0, GRAY_700), egui::StrokeKind::Inside);
+
+    // Draw time
+    painter.text(
+        display_rect.center(),
+        Align2::CENTER_CENTER,
+        mode.format(seconds),
+        FontId::monospace(24.0),
+        color.to_color32(),
+    );
+
+    // Draw mode caption
+    let caption = match mode {
+        TimeMode::HhMmSs => "HH:MM:SS".to_string(),
+        TimeMode::BarsBeats { bpm } => format!("BARS {:.0} BPM", bpm),
+    };
+    painter.text(
+        Pos2::new(rect.center().x, rect.bottom() - 8.0),
+        Align2::CENTER_CENTER,
+        caption,
+        FontId::monospace(10.0),
+        GRAY_400,
+    );
+}
+
+/// Track of a region selector; handles sit on it at `start / duration` and `end / duration`
+pub fn region_track_rect(rect: Rect) -> Rect {
+    Rect::from_min_max(
+        Pos2::new(rect.left() + 12.0, rect.top() + 14.0),
+        Pos2::new(rect.right() - 12.0, rect.top() + 22.0),
+    )
+}
+
+pub fn render_region(painter: &egui::Painter, rect: Rect, start: f32, end: f32, duration: f32, color: WidgetColor) {
+    let track_rect = region_track_rect(rect);
+    let x_at = |time: f32| {
+        let normalized = if duration > 0.0 { (time / duration).clamp(0.0, 1.0) } else { 0.0 };
+        track_rect.left() + normalized * track_rect.width()
+    };
+    let start_x = x_at(start);
+    let end_x = x_at(end);
+
+    // Draw track
+    painter.rect_filled(track_rect, 4.0, GRAY_700);
+
+    // Draw highlighted region
+    let region_rect = Rect::from_min_max(
+        Pos2::new(start_x, track_rect.top()),
+        Pos2::new(end_x, track_rect.bottom()),
+    );
+    painter.rect_filled(region_rect, 0.0, color.to_color32().gamma_multiply(0.6));
+
+    // Draw handles
+    for x in [start_x, end_x] {
+        let handle_rect = Rect::from_center_size(
+            Pos2::new(x, track_rect.center().y),
+            Vec2::new(6.0, 20.0),
+        );
+        painter.rect_filled(handle_rect, 2.0, color.to_color32());
+    }
+
+    // Draw start/end times
+    painter.text(
+        Pos2::new(track_rect.left(), rect.bottom() - 10.0),
+        Align2::LEFT_CENTER,
+        format!("{:.1}s", start),
+        FontId::monospace(10.0),
+        GRAY_400,
+    );
+    painter.text(
+        Pos2::new(track_rect.right(), rect.bottom() - 10.0),
+        Align2::RIGHT_CENTER,
+        format!("{:.1}s", end),
+        FontId::monospace(10.0),
+        GRAY_400,
+    );
+}
+
+/// Square pad of an XY pad, centered above the readout strip
+pub fn xy_pad_rect(rect: Rect) -> Rect {
+    let side = (rect.width() - 16.0).min(rect.height() - 30.0).max(0.0);
+    Rect::from_center_size(Pos2::new(rect.center().x, rect.top() + 8.0 + side / 2.0), Vec2::splat(side))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn render_xy_pad(painter: &egui::Painter, rect: Rect, x: f32, y: f32, x_label: &str, y_label: &str, color: WidgetColor, active: bool) {
+    let pad_rect = xy_pad_rect(rect);
+    let cursor = Pos2::new(
+        pad_rect.left() + x.clamp(0.0, 1.0) * pad_rect.width(),
+        pad_rect.bottom() - y.clamp(0.0, 1.0) * pad_rect.height(),
+    );
+
+    // Draw pad
+    painter.rect_filled(pad_rect, 4.0, GRAY_800);
+    painter.rect_stroke(pad_rect, 4.0, Stroke::new(1.0, GRAY_700), egui::StrokeKind::Inside);
+
+    // Draw crosshair
+    let crosshair = Stroke::new(1.0, color.to_color32().gamma_multiply(0.5));
+    painter.line_segment([Pos2::new(pad_rect.left(), cursor.y), Pos2::new(pad_rect.right(), cursor.y)], crosshair);
+    painter.line_segment([Pos2::new(cursor.x, pad_rect.top()), Pos2::new(cursor.x, pad_rect.bottom())], crosshair);
+    painter.circle_filled(cursor, 5.0, color.to_color32());
+
+    // Draw both values
+    painter.text(
+        Pos2::new(rect.center().x, rect.bottom() - 11.0),
+        Align2::CENTER_CENTER,
+        format!("{} {:.1}  {} {:.1}", x_label, x, y_label, y),
+        FontId::monospace(10.0),
+        value_text_color(color, active),
+    );
+}
+
+pub fn render_panel(painter: &egui::Painter, rect: Rect, title: &str, color: WidgetColor, collapsed: bool, contained_widgets: &[usize], minimize_to_settings_icon: bool) {
     if collapsed && minimize_to_settings_icon {
         // Show only settings icon when collapsed AND minimize_to_settings_icon is enabled
         // No background, just the icon at top-left corner
@@ -407,7 +744,7 @@ pub fn render_panel(painter: &egui::Painter, rect: Rect, title: &str, color: Wid
             painter.text(
                 Pos2::new(rect.right() - 60.0, rect.top() + 20.0),
                 Align2::CENTER_CENTER,
-                &format!("({})", contained_widgets.len()),
+                format!("({})", contained_widgets.len()),
                 FontId::monospace(10.0),
                 GRAY_400,
             );
@@ -415,28 +752,13 @@ pub fn render_panel(painter: &egui::Painter, rect: Rect, title: &str, color: Wid
         
         // Only draw resize handle if not collapsed
         if !collapsed {
-            let handle_size = 12.0;
-            let handle_rect = Rect::from_min_size(
-                Pos2::new(rect.max.x - handle_size, rect.max.y - handle_size),
-                Vec2::splat(handle_size),
-            );
-            
-            // Draw resize handle lines
-            for i in 0..3 {
-                let offset = i as f32 * 3.0;
-                painter.line_segment(
-                    [
-                        Pos2::new(handle_rect.min.x + offset, handle_rect.max.y - 2.0),
-                        Pos2::new(handle_rect.max.x - 2.0, handle_rect.min.y + offset),
-                    ],
-                    Stroke::new(1.0, GRAY_600),
-                );
-            }
+            render_resize_grip(painter, resize_handle_rect(rect), GRAY_600);
         }
     }
 }
 
-pub fn render_status_bar(painter: &egui::Painter, rect: Rect, cpu: f32, ram: f32, latency: f32, online: bool) {
+#[allow(clippy::too_many_arguments)]
+pub fn render_status_bar(painter: &egui::Painter, rect: Rect, cpu: f32, ram: f32, latency: f32, online: bool, precision: StatusPrecision, time: f64) {
     // Background
     painter.rect_filled(rect, 8.0, GRAY_900);
     
@@ -447,19 +769,21 @@ pub fn render_status_bar(painter: &egui::Painter, rect: Rect, cpu: f32, ram: f32
     let indicator_color = if online { GREEN } else { RED };
     painter.circle_filled(indicator_pos, 4.0, indicator_color);
     
-    // Pulsing effect for online
+    // Pulsing effect for online, breathing on elapsed time rather than frame count
     if online {
+        let phase = ((time * std::f64::consts::TAU / PULSE_PERIOD).sin() * 0.5 + 0.5) as f32;
         let pulse_color = Color32::from_rgba_unmultiplied(
             indicator_color.r(),
             indicator_color.g(),
             indicator_color.b(),
-            50
+            (20.0 + 80.0 * phase) as u8
         );
         painter.circle_filled(indicator_pos, 6.0, pulse_color);
+        painter.ctx().request_repaint_after(std::time::Duration::from_secs_f32(1.0 / PULSE_FRAME_RATE));
     }
     
     // Status text
-    painter.text(
+    let status_rect = painter.text(
         Pos2::new(rect.left() + 30.0, rect.center().y),
         Align2::LEFT_CENTER,
         if online { "SYSTEM ONLINE" } else { "SYSTEM OFFLINE" },
@@ -467,64 +791,32 @@ pub fn render_status_bar(painter: &egui::Painter, rect: Rect, cpu: f32, ram: f32
         indicator_color,
     );
     
-    // System stats
-    painter.text(
-        Pos2::new(rect.center().x - 50.0, rect.center().y),
-        Align2::CENTER_CENTER,
-        "48kHz / 24-bit",
-        FontId::monospace(10.0),
-        CYAN,
-    );
-    
-    painter.text(
-        Pos2::new(rect.center().x + 50.0, rect.center().y),
-        Align2::CENTER_CENTER,
-        format!("LATENCY: {:.1}ms", latency),
-        FontId::monospace(10.0),
-        PINK,
-    );
-    
-    painter.text(
-        Pos2::new(rect.right() - 120.0, rect.center().y),
-        Align2::CENTER_CENTER,
-        format!("CPU: {:.0}%", cpu),
-        FontId::monospace(10.0),
-        YELLOW,
-    );
-    
-    painter.text(
-        Pos2::new(rect.right() - 50.0, rect.center().y),
-        Align2::CENTER_CENTER,
-        format!("RAM: {:.1}GB", ram),
-        FontId::monospace(10.0),
-        GREEN,
-    );
+    // System stats, packed right to left from measured widths (clear of the resize handle).
+    // Fields that would run into the status text are dropped, the format label first.
+    let gap = 16.0;
+    let fields = [
+        (format!("RAM: {:.*}GB", precision.ram, ram), GREEN),
+        (format!("CPU: {:.*}%", precision.cpu, cpu), YELLOW),
+        (format!("LATENCY: {:.*}ms", precision.latency, latency), PINK),
+        ("48kHz / 24-bit".to_string(), CYAN),
+    ];
+    let mut right = rect.right() - 20.0;
+    for (text, color) in fields {
+        let galley = painter.layout_no_wrap(text, FontId::monospace(10.0), color);
+        let left = right - galley.size().x;
+        if left < status_rect.right() + gap {
+            break;
+        }
+        painter.galley(Pos2::new(left, rect.center().y - galley.size().y / 2.0), galley, color);
+        right = left - gap;
+    }
     
     // Draw resize handle in bottom-right corner
-    let handle_size = 12.0;
-    let handle_rect = Rect::from_min_size(
-        Pos2::new(rect.max.x - handle_size, rect.max.y - handle_size),
-        Vec2::splat(handle_size),
-    );
-    
-    // Draw resize handle lines
-    for i in 0..3 {
-        let offset = i as f32 * 3.0;
-        painter.line_segment(
-            [
-                Pos2::new(handle_rect.min.x + offset, handle_rect.max.y - 2.0),
-                Pos2::new(handle_rect.max.x - 2.0, handle_rect.min.y + offset),
-            ],
-            Stroke::new(1.0, GRAY_600),
-        );
-    }
+    render_resize_grip(painter, resize_handle_rect(rect), GRAY_600);
 }
 
 pub fn render_icon_button(painter: &egui::Painter, rect: Rect, icon: IconType, label: &str, active: &mut bool, color: WidgetColor, size: f32) {
-    let button_rect = Rect::from_center_size(
-        Pos2::new(rect.center().x, rect.top() + size / 2.0 + 5.0),
-        Vec2::splat(size),
-    );
+    let button_rect = button_face_rect(rect, size);
 
     // All icon buttons have transparent background
     let icon_color = if *active {
@@ -588,7 +880,7 @@ pub fn render_icon_button(painter: &egui::Painter, rect: Rect, icon: IconType, l
     );
 }
 
-pub fn render_settings_panel(painter: &egui::Painter, rect: Rect, title: &str, color: WidgetColor, minimized: bool, edge: CanvasEdge, _contained_widgets: &Vec<usize>) {
+pub fn render_settings_panel(painter: &egui::Painter, rect: Rect, title: &str, color: WidgetColor, minimized: bool, edge: CanvasEdge, _contained_widgets: &[usize]) {
     if minimized {
         // Render minimized state - just a settings icon
         let icon_color = color.to_color32();
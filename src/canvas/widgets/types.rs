@@ -0,0 +1,816 @@
+//! Widget type definitions for the drag-and-drop canvas system
+//!
+//! Contains the widget enum with all supported widget configurations, the
+//! color and icon enums, and the `DraggableWidget` instance type.
+
+use egui::{Color32, Pos2, Rect, Ui, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::canvas::constants::*;
+use super::rendering;
+
+/// Color themes for widgets matching the React app palette
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WidgetColor {
+    Cyan,
+    Pink,
+    Green,
+    Yellow,
+    Red,
+    Custom(u8, u8, u8), // Exact sRGB, e.g. to match a brand color
+}
+
+impl WidgetColor {
+    pub fn to_color32(self) -> Color32 {
+        match self {
+            WidgetColor::Cyan => CYAN,
+            WidgetColor::Pink => PINK,
+            WidgetColor::Green => GREEN,
+            WidgetColor::Yellow => YELLOW,
+            WidgetColor::Red => RED,
+            WidgetColor::Custom(r, g, b) => Color32::from_rgb(r, g, b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum IconType {
+    Power,
+    Play,
+    Pause,
+    SkipBack,
+    SkipForward,
+    Volume,
+    Mic,
+    Settings,
+    Mute,
+    Zap,
+}
+
+/// How a time display formats its seconds value
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TimeMode {
+    HhMmSs,                 // Wall clock style, 01:02:03
+    BarsBeats { bpm: f32 }, // Musical position in 4/4 at the given tempo, 001.1.000
+}
+
+impl TimeMode {
+    pub fn format(self, seconds: f32) -> String {
+        let seconds = seconds.max(0.0);
+        match self {
+            TimeMode::HhMmSs => {
+                let total = seconds as u64;
+                format!("{:02}:{:02}:{:02}", total / 3600, total / 60 % 60, total % 60)
+            }
+            TimeMode::BarsBeats { bpm } => {
+                // Bars and beats count from 1; ticks at 960 per beat
+                let beats = seconds * bpm.max(1.0) / 60.0;
+                let whole_beats = beats as u64;
+                let ticks = ((beats - whole_beats as f32) * 960.0) as u64;
+                format!("{:03}.{}.{:03}", whole_beats / 4 + 1, whole_beats % 4 + 1, ticks)
+            }
+        }
+    }
+}
+
+/// How a knob, slider or readout turns its value into text
+///
+/// Widgets without a format keep their built-in display (one decimal on
+/// knobs, whole numbers on sliders, the readout's own decimals).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ValueFormat {
+    Decimal { places: usize }, // Fixed decimal places, 12.50
+    SiPrefix,                  // Scaled with k/M from a thousand up, 1.2k
+    Time,                      // Value in seconds as mm:ss
+    Percent,                   // Position within min..max, 75%
+    Db,                        // Value in decibels, +3.0 dB
+}
+
+impl ValueFormat {
+    /// Every format with default parameters, in the order shown in the edit window
+    pub const ALL: [ValueFormat; 5] = [
+        ValueFormat::Decimal { places: 1 },
+        ValueFormat::SiPrefix,
+        ValueFormat::Time,
+        ValueFormat::Percent,
+        ValueFormat::Db,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueFormat::Decimal { .. } => "Decimal",
+            ValueFormat::SiPrefix => "SI prefix",
+            ValueFormat::Time => "Time",
+            ValueFormat::Percent => "Percent",
+            ValueFormat::Db => "dB",
+        }
+    }
+
+    /// Format `value`; `min..max` is only used by `Percent`
+    pub fn format(self, value: f32, min: f32, max: f32) -> String {
+        match self {
+            ValueFormat::Decimal { places } => format!("{:.*}", places, value),
+            ValueFormat::SiPrefix => {
+                let (scaled, prefix) = if value.abs() >= 1_000_000.0 {
+                    (value / 1_000_000.0, "M")
+                } else if value.abs() >= 1_000.0 {
+                    (value / 1_000.0, "k")
+                } else {
+                    (value, "")
+                };
+                if scaled.abs() >= 100.0 {
+                    format!("{:.0}{}", scaled, prefix)
+                } else {
+                    format!("{:.1}{}", scaled, prefix)
+                }
+            }
+            ValueFormat::Time => {
+                let sign = if value < 0.0 { "-" } else { "" };
+                let total = value.abs().round() as u64;
+                format!("{}{:02}:{:02}", sign, total / 60, total % 60)
+            }
+            ValueFormat::Percent => {
+                let fraction = if max > min { (value - min) / (max - min) } else { 0.0 };
+                format!("{:.0}%", fraction * 100.0)
+            }
+            ValueFormat::Db if value <= METER_FLOOR_DB => "-inf dB".to_string(),
+            ValueFormat::Db => format!("{:+.1} dB", value),
+        }
+    }
+
+    /// Whether a custom unit goes after this format; Time, Percent and dB already carry their own
+    pub fn shows_unit(self) -> bool {
+        matches!(self, ValueFormat::Decimal { .. } | ValueFormat::SiPrefix)
+    }
+
+    /// Append a unit to text formatted with `format` (`None` for a widget's default),
+    /// "440 Hz"; an SI prefix moves onto the unit, "4.4 kHz"
+    ///
+    /// Formats that carry their own unit are returned unchanged, see `shows_unit`.
+    pub fn with_unit(format: Option<ValueFormat>, text: String, unit: &str) -> String {
+        if unit.is_empty() || !format.map_or(true, ValueFormat::shows_unit) {
+            return text;
+        }
+        let number = text.trim_end_matches(['k', 'M']);
+        format!("{} {}{}", number, &text[number.len()..], unit)
+    }
+}
+
+/// Decimal places shown for each status bar field
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusPrecision {
+    pub cpu: usize,
+    pub ram: usize,
+    pub latency: usize,
+}
+
+impl Default for StatusPrecision {
+    fn default() -> Self {
+        Self { cpu: 0, ram: 1, latency: 1 }
+    }
+}
+
+/// Edge snapping positions for settings panels
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum CanvasEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    #[default]
+    None, // For when not snapped to any edge
+}
+
+/// All supported widget types with their configuration parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WidgetType {
+    Knob {
+        value: f32,
+        min: f32,
+        max: f32,
+        label: String,
+        color: WidgetColor,
+        #[serde(default)]
+        default_value: Option<f32>, // Double-click target; None means the middle of the range
+    },
+    ToggleSwitch { on: bool, label: String, color: WidgetColor, glow: bool },
+    PushButton {
+        active: bool,
+        icon: String,
+        label: String,
+        color: WidgetColor,
+        size: f32,
+        #[serde(default)]
+        momentary: bool, // Active only while held down, instead of latching on each click
+    },
+    VuMeter { level: f32, peak_level: f32, label: String, color: WidgetColor },
+    HorizontalSlider {
+        value: f32,
+        min: f32,
+        max: f32,
+        label: String,
+        color: WidgetColor,
+        #[serde(default)]
+        default_value: Option<f32>, // Double-click target; None means the middle of the range
+    },
+    VerticalSlider {
+        value: f32,
+        min: f32,
+        max: f32,
+        label: String,
+        color: WidgetColor,
+        #[serde(default)]
+        default_value: Option<f32>, // Double-click target; None means the middle of the range
+    },
+    LevelIndicator { level: f32, segments: usize, label: String },
+    TextLabel { text: String, size: f32, color: WidgetColor },
+    Panel {
+        title: String,
+        color: WidgetColor,
+        width: f32,
+        height: f32,
+        collapsed: bool,
+        contained_widgets: Vec<usize>,
+        minimize_to_settings_icon: bool,
+        #[serde(default)]
+        grid_layout: bool, // Children snap to a regular grid of equal cells, like an icon tray
+    },
+    StatusBar {
+        cpu: f32,
+        ram: f32,
+        latency: f32,
+        online: bool,
+        #[serde(default)]
+        precision: StatusPrecision,
+    },
+    IconButton {
+        icon: IconType,
+        label: String,
+        active: bool,
+        color: WidgetColor,
+        size: f32,
+        #[serde(default)]
+        momentary: bool, // Active only while held down, instead of latching on each click
+    },
+    Settings {
+        label: String,
+        color: WidgetColor,
+        minimized: bool,
+        contained_widgets: Vec<usize>,
+        #[serde(default)]
+        edge: CanvasEdge, // Canvas edge the panel is docked to, stretched along it; None when floating
+    },
+    Readout { value: f32, unit: String, decimals: usize, color: WidgetColor, label: String },
+    MeterWithReadout { level: f32, peak: f32, unit: String, color: WidgetColor }, // Level and held peak in dB
+    TimeDisplay { seconds: f32, mode: TimeMode, color: WidgetColor },
+    RegionSelector { start: f32, end: f32, duration: f32, color: WidgetColor }, // Loop region over 0..duration
+    XyPad { x: f32, y: f32, x_label: String, y_label: String, color: WidgetColor }, // Two parameters, both 0..1
+    RotaryEncoder { value: usize, steps: usize, labels: Vec<String>, wrap: bool, color: WidgetColor }, // Step index 0..steps, optionally named
+    SpectrumAnalyzer {
+        bands: Vec<f32>, // Host-fed magnitudes, 0..1, one bar each
+        color: WidgetColor,
+        #[serde(default)]
+        peaks: Vec<f32>, // Held peak per band, falling back like the VU meter's
+    },
+    Custom { id: String }, // Drawn by a renderer registered on the canvas under this id
+}
+
+/// Sub-rects of a widget, shared by its renderer and the canvas hit-tests
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WidgetGeometry {
+    pub rect: Rect,                  // Whole widget, same as get_rect()
+    pub control: Option<Rect>,       // Knob or encoder dial, slider track, region track or XY pad
+    pub resize_handle: Option<Rect>, // Bottom-right grip on every resizable widget except collapsed panels
+}
+
+/// A widget instance with position, size, and type information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraggableWidget {
+    pub id: usize,
+    pub widget_type: WidgetType,
+    pub position: Pos2,
+    pub size: Vec2,
+    #[serde(default)]
+    pub linked_to: Vec<usize>, // IDs of widgets the host linked this one to; only drawn, values stay independent
+    #[serde(default)]
+    pub format: Option<ValueFormat>, // Value display for knobs, sliders and readouts
+    #[serde(default)]
+    pub unit: String, // Shown after knob and slider values, e.g. "Hz"; readouts have their own
+    #[serde(default)]
+    pub exclusive_group: Option<String>, // Toggles and buttons sharing a group act like radio buttons
+    #[serde(default)]
+    pub always_on_top: bool, // Drawn and hit-tested above every unlocked widget
+    #[serde(default)]
+    pub always_on_bottom: bool, // Drawn and hit-tested below every unlocked widget, even while dragged
+    #[serde(default)]
+    pub locked: bool, // Pinned in place: can't be dragged or resized, but still plays like in performance mode
+    #[serde(default)]
+    pub name: Option<String>, // Unique name for scripting and host lookups, e.g. "master_volume"
+}
+
+impl WidgetType {
+    /// Short human-readable name for the widget type
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            WidgetType::Knob { .. } => "Knob",
+            WidgetType::ToggleSwitch { .. } => "Toggle",
+            WidgetType::PushButton { .. } => "Button",
+            WidgetType::VuMeter { .. } => "VU Meter",
+            WidgetType::HorizontalSlider { .. } => "H Slider",
+            WidgetType::VerticalSlider { .. } => "V Slider",
+            WidgetType::LevelIndicator { .. } => "Level",
+            WidgetType::TextLabel { .. } => "Text",
+            WidgetType::Panel { .. } => "Panel",
+            WidgetType::StatusBar { .. } => "Status",
+            WidgetType::IconButton { .. } => "Icon",
+            WidgetType::Settings { .. } => "Settings",
+            WidgetType::Readout { .. } => "Readout",
+            WidgetType::MeterWithReadout { .. } => "dB Meter",
+            WidgetType::TimeDisplay { .. } => "Time",
+            WidgetType::RegionSelector { .. } => "Region",
+            WidgetType::XyPad { .. } => "XY Pad",
+            WidgetType::RotaryEncoder { .. } => "Encoder",
+            WidgetType::SpectrumAnalyzer { .. } => "Spectrum",
+            WidgetType::Custom { .. } => "Custom",
+        }
+    }
+    
+    /// Accessible role, name and value for screen readers, e.g. "VOLUME knob, 75 percent"
+    pub fn accessibility_info(&self) -> egui::WidgetInfo {
+        fn percent(value: f32, min: f32, max: f32) -> f32 {
+            if max > min { (value - min) / (max - min) * 100.0 } else { 0.0 }
+        }
+        
+        match self {
+            WidgetType::Knob { value, min, max, label, .. } => egui::WidgetInfo::slider(
+                true,
+                *value as f64,
+                format!("{} knob, {:.0} percent", label, percent(*value, *min, *max)),
+            ),
+            WidgetType::HorizontalSlider { value, min, max, label, .. } |
+            WidgetType::VerticalSlider { value, min, max, label, .. } => egui::WidgetInfo::slider(
+                true,
+                *value as f64,
+                format!("{} slider, {:.0} percent", label, percent(*value, *min, *max)),
+            ),
+            WidgetType::ToggleSwitch { on, label, .. } => {
+                egui::WidgetInfo::selected(egui::WidgetType::Checkbox, true, *on, format!("{} switch", label))
+            }
+            WidgetType::PushButton { active, label, .. } |
+            WidgetType::IconButton { active, label, .. } => {
+                egui::WidgetInfo::selected(egui::WidgetType::Button, true, *active, label)
+            }
+            WidgetType::VuMeter { level, label, .. } => egui::WidgetInfo {
+                value: Some(*level as f64),
+                ..egui::WidgetInfo::labeled(egui::WidgetType::ProgressIndicator, true, format!("{} meter, {:.0} percent", label, level))
+            },
+            WidgetType::LevelIndicator { level, label, .. } => egui::WidgetInfo {
+                value: Some(*level as f64),
+                ..egui::WidgetInfo::labeled(egui::WidgetType::ProgressIndicator, true, format!("{} level, {:.0} percent", label, level))
+            },
+            WidgetType::TextLabel { text, .. } => egui::WidgetInfo::labeled(egui::WidgetType::Label, true, text),
+            WidgetType::Panel { title, collapsed, .. } => egui::WidgetInfo::labeled(
+                egui::WidgetType::CollapsingHeader,
+                true,
+                format!("{} panel{}", title, if *collapsed { ", collapsed" } else { "" }),
+            ),
+            WidgetType::Settings { label, minimized, .. } => egui::WidgetInfo::labeled(
+                egui::WidgetType::CollapsingHeader,
+                true,
+                format!("{} settings panel{}", label, if *minimized { ", minimized" } else { "" }),
+            ),
+            WidgetType::StatusBar { cpu, ram, latency, online, precision } => egui::WidgetInfo::labeled(
+                egui::WidgetType::Label,
+                true,
+                format!(
+                    "Status: CPU {:.*} percent, RAM {:.*} gigabytes, latency {:.*} milliseconds, {}",
+                    precision.cpu, cpu, precision.ram, ram, precision.latency, latency, if *online { "online" } else { "offline" }
+                ),
+            ),
+            WidgetType::Readout { value, unit, decimals, label, .. } => egui::WidgetInfo::labeled(
+                egui::WidgetType::Label,
+                true,
+                format!("{} readout, {:.*} {}", label, *decimals, value, unit),
+            ),
+            WidgetType::MeterWithReadout { level, peak, unit, .. } => egui::WidgetInfo {
+                value: Some(*level as f64),
+                ..egui::WidgetInfo::labeled(
+                    egui::WidgetType::ProgressIndicator,
+                    true,
+                    format!("Meter, {:.1} {}, peak {:.1} {}", level, unit, peak, unit),
+                )
+            },
+            WidgetType::TimeDisplay { seconds, mode, .. } => egui::WidgetInfo::labeled(
+                egui::WidgetType::Label,
+                true,
+                format!("Time, {}", mode.format(*seconds)),
+            ),
+            WidgetType::RegionSelector { start, end, duration, .. } => egui::WidgetInfo::labeled(
+                egui::WidgetType::Slider,
+                true,
+                format!("Loop region, {:.1} to {:.1} of {:.1} seconds", start, end, duration),
+            ),
+            WidgetType::XyPad { x, y, x_label, y_label, .. } => egui::WidgetInfo::labeled(
+                egui::WidgetType::Slider,
+                true,
+                format!("XY pad, {} {:.1}, {} {:.1}", x_label, x, y_label, y),
+            ),
+            WidgetType::RotaryEncoder { value, steps, labels, .. } => egui::WidgetInfo::labeled(
+                egui::WidgetType::Slider,
+                true,
+                match labels.get(*value).filter(|label| !label.is_empty()) {
+                    Some(label) => format!("Encoder, {}, step {} of {}", label, value + 1, steps),
+                    None => format!("Encoder, step {} of {}", value + 1, steps),
+                },
+            ),
+            WidgetType::SpectrumAnalyzer { bands, .. } => egui::WidgetInfo::labeled(
+                egui::WidgetType::ProgressIndicator,
+                true,
+                format!("Spectrum analyzer, {} bands, loudest {:.0} percent", bands.len(), bands.iter().fold(0.0f32, |a, &b| a.max(b)) * 100.0),
+            ),
+            WidgetType::Custom { id } => egui::WidgetInfo::labeled(egui::WidgetType::Other, true, id),
+        }
+    }
+    
+    /// Height of the label strip under the square control of aspect-locked widgets
+    ///
+    /// Push and icon buttons draw a square control above their label, sized from
+    /// their own `size` field, so their size is kept at `width == height - label strip`.
+    /// Returns `None` for other widgets.
+    pub fn aspect_lock_label_height(&self) -> Option<f32> {
+        match self {
+            WidgetType::PushButton { .. } | WidgetType::IconButton { .. } => Some(20.0),
+            _ => None,
+        }
+    }
+    
+    /// Value a knob or slider returns to on double-click or reset: its
+    /// `default_value` if set, otherwise the middle of its range
+    pub fn default_value(&self) -> Option<f32> {
+        match self {
+            WidgetType::Knob { min, max, default_value, .. } |
+            WidgetType::HorizontalSlider { min, max, default_value, .. } |
+            WidgetType::VerticalSlider { min, max, default_value, .. } => {
+                Some(default_value.unwrap_or((*min + *max) / 2.0).clamp(min.min(*max), max.max(*min)))
+            }
+            _ => None,
+        }
+    }
+    
+    /// On/active state of toggles, push buttons and icon buttons
+    pub fn switch_state(&self) -> Option<bool> {
+        match self {
+            WidgetType::ToggleSwitch { on, .. } => Some(*on),
+            WidgetType::PushButton { active, .. } | WidgetType::IconButton { active, .. } => Some(*active),
+            _ => None,
+        }
+    }
+    
+    pub fn switch_state_mut(&mut self) -> Option<&mut bool> {
+        match self {
+            WidgetType::ToggleSwitch { on, .. } => Some(on),
+            WidgetType::PushButton { active, .. } | WidgetType::IconButton { active, .. } => Some(active),
+            _ => None,
+        }
+    }
+    
+    /// Whether this is a push or icon button that is active only while held
+    pub fn is_momentary(&self) -> bool {
+        matches!(self, WidgetType::PushButton { momentary: true, .. } | WidgetType::IconButton { momentary: true, .. })
+    }
+    
+    /// Whether this widget displays a value that a `ValueFormat` applies to
+    pub fn has_formatted_value(&self) -> bool {
+        matches!(
+            self,
+            WidgetType::Knob { .. } | WidgetType::HorizontalSlider { .. } | WidgetType::VerticalSlider { .. } | WidgetType::Readout { .. }
+        )
+    }
+    
+    /// Smallest and largest scale of widgets drawn as a uniformly scaled copy of their natural size
+    ///
+    /// Returns `None` for widgets that lay themselves out at any size (panels, status
+    /// bars, buttons, text labels) or can't be resized (the settings panel).
+    pub fn scale_range(&self) -> Option<(f32, f32)> {
+        match self {
+            WidgetType::Knob { .. } | WidgetType::RotaryEncoder { .. } => Some((0.4, 3.0)),
+            WidgetType::XyPad { .. } | WidgetType::SpectrumAnalyzer { .. } | WidgetType::Custom { .. } => Some((0.5, 4.0)),
+            WidgetType::ToggleSwitch { .. }
+            | WidgetType::VuMeter { .. }
+            | WidgetType::HorizontalSlider { .. }
+            | WidgetType::VerticalSlider { .. }
+            | WidgetType::LevelIndicator { .. }
+            | WidgetType::Readout { .. }
+            | WidgetType::MeterWithReadout { .. }
+            | WidgetType::TimeDisplay { .. }
+            | WidgetType::RegionSelector { .. } => Some((0.5, 3.0)),
+            WidgetType::PushButton { .. }
+            | WidgetType::IconButton { .. }
+            | WidgetType::TextLabel { .. }
+            | WidgetType::Panel { .. }
+            | WidgetType::StatusBar { .. }
+            | WidgetType::Settings { .. } => None,
+        }
+    }
+    
+    /// Whether the user can change this widget's size (resize handle or edit window)
+    pub fn is_resizable(&self) -> bool {
+        matches!(self, WidgetType::Panel { .. } | WidgetType::StatusBar { .. } | WidgetType::TextLabel { .. })
+            || self.aspect_lock_label_height().is_some()
+            || self.scale_range().is_some()
+    }
+}
+
+impl DraggableWidget {
+    pub fn new(id: usize, widget_type: WidgetType, position: Pos2) -> Self {
+        let size = Self::calculate_size(&widget_type);
+        Self {
+            id,
+            widget_type,
+            position,
+            size,
+            linked_to: Vec::new(),
+            format: None,
+            unit: String::new(),
+            exclusive_group: None,
+            always_on_top: false,
+            always_on_bottom: false,
+            locked: false,
+            name: None,
+        }
+    }
+
+    pub fn calculate_size(widget_type: &WidgetType) -> Vec2 {
+        match widget_type {
+            WidgetType::Knob { .. } => Vec2::new(104.0, 124.0),
+            WidgetType::ToggleSwitch { .. } => Vec2::new(68.0, 49.0),
+            WidgetType::PushButton { size, .. } => Vec2::new(size + 10.0, size + 30.0),
+            WidgetType::VuMeter { .. } => Vec2::new(26.0, 158.0),
+            WidgetType::HorizontalSlider { .. } => Vec2::new(176.0, 28.0),
+            WidgetType::VerticalSlider { .. } => Vec2::new(28.0, 146.0),
+            WidgetType::LevelIndicator { .. } => Vec2::new(120.0, 40.0),
+            WidgetType::TextLabel { size, .. } => Vec2::new(size * 8.0, size * 1.5),
+            WidgetType::Panel { width, height, collapsed, minimize_to_settings_icon, .. } => {
+                if *collapsed {
+                    if *minimize_to_settings_icon {
+                        Vec2::new(40.0, 40.0) // Settings icon size when minimized with special setting
+                    } else {
+                        Vec2::new(*width, 40.0) // Just title bar height when collapsed normally
+                    }
+                } else {
+                    Vec2::new(*width, *height)
+                }
+            },
+            WidgetType::StatusBar { .. } => Vec2::new(400.0, 60.0),
+            WidgetType::IconButton { size, .. } => Vec2::new(size + 10.0, size + 30.0),
+            WidgetType::Settings { minimized, .. } => {
+                if *minimized {
+                    Vec2::new(40.0, 40.0)
+                } else {
+                    Vec2::new(250.0, 300.0)
+                }
+            }
+            WidgetType::Readout { .. } => Vec2::new(140.0, 70.0),
+            WidgetType::MeterWithReadout { .. } => Vec2::new(180.0, 160.0),
+            WidgetType::TimeDisplay { .. } => Vec2::new(180.0, 60.0),
+            WidgetType::RegionSelector { .. } => Vec2::new(240.0, 50.0),
+            WidgetType::XyPad { .. } => Vec2::new(120.0, 120.0),
+            WidgetType::RotaryEncoder { .. } => Vec2::new(104.0, 124.0),
+            WidgetType::SpectrumAnalyzer { .. } => Vec2::new(240.0, 120.0),
+            WidgetType::Custom { .. } => Vec2::new(100.0, 100.0),
+        }
+    }
+
+    /// Resize the widget, keeping the control of aspect-locked widgets square
+    /// and the proportions of scaled widgets (see `scale_range`)
+    ///
+    /// The larger of the requested width and control height (or the larger scale) wins.
+    /// Text labels take the height as their line height and pick the font size to match.
+    pub fn set_size(&mut self, size: Vec2) {
+        if let WidgetType::TextLabel { size: font_size, .. } = &mut self.widget_type {
+            *font_size = size.y / 1.5;
+            self.size = Self::calculate_size(&self.widget_type);
+            return;
+        }
+        
+        self.size = match self.widget_type.aspect_lock_label_height() {
+            Some(label_height) => {
+                let side = size.x.max(size.y - label_height);
+                Vec2::new(side, side + label_height)
+            }
+            None if self.widget_type.scale_range().is_some() => {
+                let natural = Self::calculate_size(&self.widget_type);
+                natural * (size.x / natural.x).max(size.y / natural.y)
+            }
+            None => size,
+        };
+        
+        // Buttons draw from their own size field (rect is size + 10 wide)
+        if let WidgetType::PushButton { size, .. } | WidgetType::IconButton { size, .. } = &mut self.widget_type {
+            *size = self.size.x - 10.0;
+        }
+    }
+
+    /// Render layer from the z-lock flags: -1 always on bottom, 1 always on top, 0 otherwise
+    pub fn z_layer(&self) -> i8 {
+        if self.always_on_top {
+            1
+        } else if self.always_on_bottom {
+            -1
+        } else {
+            0
+        }
+    }
+
+    pub fn get_rect(&self) -> Rect {
+        Rect::from_min_size(self.position, self.size)
+    }
+
+    /// How much larger than its natural size (`calculate_size`) the widget is drawn
+    ///
+    /// Always 1.0 for widgets without a `scale_range`, which lay themselves out at their size.
+    pub fn scale(&self) -> f32 {
+        if self.widget_type.scale_range().is_none() {
+            return 1.0;
+        }
+        let natural = Self::calculate_size(&self.widget_type);
+        if natural.x > 0.0 { self.size.x / natural.x } else { 1.0 }
+    }
+
+    /// Rect the widget is laid out in before scaling: its natural size at its position
+    pub fn natural_rect(&self) -> Rect {
+        Rect::from_min_size(self.position, self.size / self.scale())
+    }
+
+    /// Map a point laid out in `natural_rect()` to where it is drawn after scaling
+    pub fn scale_transform(&self) -> egui::emath::TSTransform {
+        let scale = self.scale();
+        egui::emath::TSTransform::new(self.position.to_vec2() * (1.0 - scale), scale)
+    }
+
+    /// Current value as text with its format and unit, e.g. "-6.0 dB", for tooltips
+    ///
+    /// Knobs and sliders without a format show two decimals, more than their face
+    /// has room for. Returns `None` for widgets without a value (labels, panels, custom).
+    pub fn value_text(&self) -> Option<String> {
+        let text = match &self.widget_type {
+            WidgetType::Knob { value, min, max, .. } |
+            WidgetType::HorizontalSlider { value, min, max, .. } |
+            WidgetType::VerticalSlider { value, min, max, .. } => {
+                let text = self.format.map_or_else(|| format!("{:.2}", value), |f| f.format(*value, *min, *max));
+                ValueFormat::with_unit(self.format, text, &self.unit)
+            }
+            WidgetType::ToggleSwitch { on, .. } => if *on { "On" } else { "Off" }.to_string(),
+            WidgetType::PushButton { active, .. } | WidgetType::IconButton { active, .. } => {
+                if *active { "Active" } else { "Inactive" }.to_string()
+            }
+            WidgetType::VuMeter { level, .. } | WidgetType::LevelIndicator { level, .. } => format!("{:.0}%", level),
+            WidgetType::Readout { value, unit, decimals, .. } => {
+                let format = self.format.unwrap_or(ValueFormat::Decimal { places: *decimals });
+                format!("{} {}", format.format(*value, 0.0, 100.0), unit).trim_end().to_string()
+            }
+            WidgetType::MeterWithReadout { level, peak, unit, .. } => format!("{:.1} {}, peak {:.1} {}", level, unit, peak, unit),
+            WidgetType::StatusBar { cpu, ram, latency, online, precision } => format!(
+                "CPU {:.*}%, RAM {:.*} GB, {:.*} ms, {}",
+                precision.cpu, cpu, precision.ram, ram, precision.latency, latency, if *online { "online" } else { "offline" }
+            ),
+            WidgetType::TimeDisplay { seconds, mode, .. } => mode.format(*seconds),
+            WidgetType::RegionSelector { start, end, .. } => format!("{:.2} s – {:.2} s", start, end),
+            WidgetType::XyPad { x, y, x_label, y_label, .. } => format!("{} {:.2}, {} {:.2}", x_label, x, y_label, y),
+            WidgetType::RotaryEncoder { value, steps, labels, .. } => match labels.get(*value).filter(|label| !label.is_empty()) {
+                Some(label) => format!("{} (step {} of {})", label, value + 1, steps),
+                None => format!("Step {} of {}", value + 1, steps),
+            },
+            WidgetType::SpectrumAnalyzer { bands, .. } => {
+                format!("{} bands, loudest {:.0}%", bands.len(), bands.iter().fold(0.0f32, |a, &b| a.max(b)) * 100.0)
+            }
+            WidgetType::TextLabel { .. } | WidgetType::Panel { .. } | WidgetType::Settings { .. } | WidgetType::Custom { .. } => return None,
+        };
+        Some(text)
+    }
+
+    /// Where the renderer draws this widget's interactive parts, without rendering
+    pub fn render_rects(&self) -> WidgetGeometry {
+        let rect = self.get_rect();
+        // Controls are laid out at the natural size, then scaled along with the drawing
+        let natural = self.natural_rect();
+        let control = match self.widget_type {
+            WidgetType::Knob { .. } | WidgetType::RotaryEncoder { .. } => Some(rendering::knob_dial_rect(natural)),
+            WidgetType::HorizontalSlider { .. } => Some(rendering::horizontal_slider_track_rect(natural)),
+            WidgetType::VerticalSlider { .. } => Some(rendering::vertical_slider_track_rect(natural)),
+            WidgetType::RegionSelector { .. } => Some(rendering::region_track_rect(natural)),
+            WidgetType::XyPad { .. } => Some(rendering::xy_pad_rect(natural)),
+            WidgetType::PushButton { size, .. } | WidgetType::IconButton { size, .. } => Some(rendering::button_face_rect(natural, size)),
+            _ => None,
+        }.map(|control| self.scale_transform().mul_rect(control));
+        let resize_handle = match self.widget_type {
+            WidgetType::Panel { collapsed: true, .. } => None,
+            _ if self.widget_type.is_resizable() => Some(rendering::resize_handle_rect(rect)),
+            _ => None,
+        };
+        WidgetGeometry { rect, control, resize_handle }
+    }
+
+    /// Draw the widget at its natural size; `active` (hovered, selected or being adjusted)
+    /// brightens knob and slider values
+    ///
+    /// The canvas scales the shapes by `scale_transform()` afterwards.
+    pub fn render(&mut self, ui: &mut Ui, active: bool) {
+        let rect = self.natural_rect();
+        let (dt, time) = ui.ctx().input(|i| (i.unstable_dt, i.time));
+        let painter = ui.painter();
+
+        match &mut self.widget_type {
+            WidgetType::Knob { value, min, max, label, color, .. } => {
+                rendering::render_knob(painter, rect, value, *min, *max, label, *color, self.format, &self.unit, active);
+            }
+            WidgetType::ToggleSwitch { on, label, color, glow } => {
+                rendering::render_toggle_switch(painter, rect, on, label, *color, *glow);
+            }
+            WidgetType::PushButton { active, icon, label, color, size, .. } => {
+                rendering::render_push_button(painter, rect, active, icon, label, *color, *size);
+            }
+            WidgetType::VuMeter { level, peak_level, label, color } => {
+                rendering::render_vu_meter(painter, rect, *level, peak_level, label, *color, dt);
+            }
+            WidgetType::HorizontalSlider { value, min, max, label, color, .. } => {
+                rendering::render_horizontal_slider(painter, rect, value, *min, *max, label, *color, self.format, &self.unit, active);
+            }
+            WidgetType::VerticalSlider { value, min, max, label, color, .. } => {
+                rendering::render_vertical_slider(painter, rect, value, *min, *max, label, *color, self.format, &self.unit, active);
+            }
+            WidgetType::LevelIndicator { level, segments, label } => {
+                rendering::render_level_indicator(painter, rect, *level, *segments, label);
+            }
+            WidgetType::TextLabel { text, size, color } => {
+                rendering::render_text_label(painter, rect, text, *size, *color);
+            }
+            WidgetType::Panel { title, color, collapsed, contained_widgets, minimize_to_settings_icon, .. } => {
+                rendering::render_panel(painter, rect, title, *color, *collapsed, contained_widgets, *minimize_to_settings_icon);
+            }
+            WidgetType::StatusBar { cpu, ram, latency, online, precision } => {
+                rendering::render_status_bar(painter, rect, *cpu, *ram, *latency, *online, *precision, time);
+            }
+            WidgetType::IconButton { icon, label, active, color, size, .. } => {
+                rendering::render_icon_button(painter, rect, *icon, label, active, *color, *size);
+            }
+            WidgetType::Settings { label, color, minimized, contained_widgets, edge } => {
+                rendering::render_settings_panel(painter, rect, label, *color, *minimized, *edge, contained_widgets);
+            }
+            WidgetType::Readout { value, unit, decimals, color, label } => {
+                let format = self.format.unwrap_or(ValueFormat::Decimal { places: *decimals });
+                rendering::render_readout(painter, rect, *value, unit, format, *color, label);
+            }
+            WidgetType::MeterWithReadout { level, peak, unit, color } => {
+                rendering::render_meter_with_readout(painter, rect, *level, *peak, unit, *color);
+            }
+            WidgetType::TimeDisplay { seconds, mode, color } => {
+                rendering::render_time_display(painter, rect, *seconds, *mode, *color);
+            }
+            WidgetType::RegionSelector { start, end, duration, color } => {
+                rendering::render_region(painter, rect, *start, *end, *duration, *color);
+            }
+            WidgetType::XyPad { x, y, x_label, y_label, color } => {
+                rendering::render_xy_pad(painter, rect, *x, *y, x_label, y_label, *color, active);
+            }
+            WidgetType::RotaryEncoder { value, steps, labels, color, .. } => {
+                rendering::render_rotary_encoder(painter, rect, *value, *steps, labels, *color, active);
+            }
+            WidgetType::SpectrumAnalyzer { bands, color, peaks } => {
+                rendering::render_spectrum_analyzer(painter, rect, bands, peaks, *color, dt);
+            }
+            WidgetType::Custom { id } => {
+                // Canvas dispatches registered custom renderers itself; this is the fallback
+                rendering::render_custom_placeholder(painter, rect, id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatted(format: Option<ValueFormat>, value: f32, unit: &str) -> String {
+        let text = format.map_or_else(|| format!("{:.0}", value), |f| f.format(value, 0.0, 100.0));
+        ValueFormat::with_unit(format, text, unit)
+    }
+
+    #[test]
+    fn unit_follows_the_number_and_takes_over_the_si_prefix() {
+        assert_eq!(formatted(None, 440.0, "Hz"), "440 Hz");
+        assert_eq!(formatted(Some(ValueFormat::Decimal { places: 1 }), 440.0, "Hz"), "440.0 Hz");
+        assert_eq!(formatted(Some(ValueFormat::SiPrefix), 440.0, "Hz"), "440 Hz");
+        assert_eq!(formatted(Some(ValueFormat::SiPrefix), 4_400.0, "Hz"), "4.4 kHz");
+        assert_eq!(formatted(Some(ValueFormat::SiPrefix), 2_500_000.0, "Hz"), "2.5 MHz");
+        assert_eq!(formatted(Some(ValueFormat::SiPrefix), 4_400.0, ""), "4.4k");
+    }
+
+    #[test]
+    fn formats_with_their_own_unit_ignore_the_custom_one() {
+        assert_eq!(formatted(Some(ValueFormat::Db), 1.0, "dB"), "+1.0 dB");
+        assert_eq!(formatted(Some(ValueFormat::Percent), 50.0, "Hz"), "50%");
+        assert_eq!(formatted(Some(ValueFormat::Time), 83.0, "Hz"), "01:23");
+    }
+}
@@ -0,0 +1,300 @@
+//! # Canvas Tabs
+//!
+//! Several independent canvases ("pages") shown one at a time, e.g. one per
+//! console scene. Each page is a full `DragDropCanvas` with its own widgets,
+//! undo history and view; only the active page is rendered and takes input.
+//!
+//! The canvas's own clipboard (`copy_widget`/`paste_widget`, Ctrl+C/Ctrl+V)
+//! travels with the active page, so widgets copied on one page paste on
+//! another.
+//!
+//! ## Example Usage
+//!
+//! ```ignore
+//! let mut tabs = CanvasTabs::new();
+//! let mixer = tabs.add_tab("Mixer");
+//! tabs.switch_tab(mixer);
+//!
+//! // Tab bar above the active page
+//! tabs.show_tab_bar(ui);
+//! tabs.active_mut().render(ui);
+//! ```
+
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+
+use crate::drag_drop_canvas::DragDropCanvas;
+
+/// Characters a page name can't contain, since it doubles as a layout/export file name
+const RESERVED_NAME_CHARS: [char; 3] = ['/', '\\', '.'];
+
+/// The page name as a file stem, or None if it is blank or could point outside the target folder
+pub fn file_name_for(name: &str) -> Option<&str> {
+    (!name.trim().is_empty() && !name.contains(RESERVED_NAME_CHARS)).then_some(name)
+}
+
+/// Named canvases with one of them active
+pub struct CanvasTabs {
+    tabs: Vec<(String, DragDropCanvas)>, // Page name and canvas, in tab bar order; never empty
+    active: usize, // Index of the page being shown
+}
+
+/// All pages as written by `CanvasTabs::to_json`
+#[derive(Serialize, Deserialize)]
+struct TabsLayout {
+    active: usize,
+    tabs: Vec<TabLayout>,
+}
+
+/// One page: its name and the canvas layout exactly as `DragDropCanvas::to_json` writes it
+#[derive(Serialize, Deserialize)]
+struct TabLayout {
+    name: String,
+    layout: serde_json::Value,
+}
+
+impl Default for CanvasTabs {
+    fn default() -> Self {
+        Self {
+            tabs: vec![("Page 1".to_string(), DragDropCanvas::new())],
+            active: 0,
+        }
+    }
+}
+
+impl CanvasTabs {
+    /// A single empty page named "Page 1"
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The page being shown
+    pub fn active(&self) -> &DragDropCanvas {
+        &self.tabs[self.active].1
+    }
+
+    /// The page being shown, for rendering and editing
+    pub fn active_mut(&mut self) -> &mut DragDropCanvas {
+        &mut self.tabs[self.active].1
+    }
+
+    /// Index of the page being shown
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    /// Name of the page being shown
+    pub fn active_name(&self) -> &str {
+        &self.tabs[self.active].0
+    }
+
+    /// Number of pages, always at least one
+    pub fn tab_count(&self) -> usize {
+        self.tabs.len()
+    }
+
+    /// Page names in tab bar order
+    pub fn tab_names(&self) -> impl Iterator<Item = &str> {
+        self.tabs.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Every page with its name, e.g. to feed meters on pages that aren't shown
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut DragDropCanvas)> {
+        self.tabs.iter_mut().map(|(name, canvas)| (name.as_str(), canvas))
+    }
+
+    /// Add an empty page at the end of the tab bar and return its index
+    ///
+    /// The new page starts with the active page's canvas settings. It is not
+    /// switched to; call switch_tab() for that.
+    pub fn add_tab(&mut self, name: impl Into<String>) -> usize {
+        let canvas = self.new_canvas();
+        self.tabs.push((name.into(), canvas));
+        self.tabs.len() - 1
+    }
+
+    /// Remove a page and everything on it
+    ///
+    /// Removing the active page shows the one that took its place (or the new
+    /// last page), carrying the clipboard over. Returns false if `index` is out
+    /// of range or it is the only page.
+    pub fn remove_tab(&mut self, index: usize) -> bool {
+        if index >= self.tabs.len() || self.tabs.len() == 1 {
+            return false;
+        }
+        let (_, mut removed) = self.tabs.remove(index);
+        if index < self.active {
+            self.active -= 1;
+        } else if index == self.active {
+            self.active = self.active.min(self.tabs.len() - 1);
+            let clip = removed.take_clipboard();
+            self.active_mut().set_clipboard(clip);
+        }
+        true
+    }
+
+    /// Rename a page
+    ///
+    /// Returns false if `index` is out of range or the name contains a path
+    /// separator or '.'. A blank name is accepted so the field can be cleared
+    /// while typing; it just can't be saved (see `file_name_for`).
+    pub fn rename_tab(&mut self, index: usize, name: impl Into<String>) -> bool {
+        let name = name.into();
+        if name.contains(RESERVED_NAME_CHARS) {
+            return false;
+        }
+        match self.tabs.get_mut(index) {
+            Some((tab_name, _)) => {
+                *tab_name = name;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Show another page, moving the clipboard to it; returns false if `index` is out of range
+    pub fn switch_tab(&mut self, index: usize) -> bool {
+        if index >= self.tabs.len() {
+            return false;
+        }
+        if index != self.active {
+            let clip = self.active_mut().take_clipboard();
+            self.active = index;
+            self.active_mut().set_clipboard(clip);
+        }
+        true
+    }
+
+    /// Tab bar for picking the active page, with a button to add one
+    ///
+    /// Right-clicking a tab renames or closes it; the last page can't be closed.
+    pub fn show_tab_bar(&mut self, ui: &mut Ui) {
+        let mut switch_to = None;
+        let mut close = None;
+        let mut rename = None;
+        let mut add = false;
+        let active = self.active;
+        let can_close = self.tabs.len() > 1;
+
+        ui.horizontal_wrapped(|ui| {
+            for (index, (name, canvas)) in self.tabs.iter_mut().enumerate() {
+                let tab = ui.selectable_label(index == active, name.as_str());
+                if tab.clicked() {
+                    switch_to = Some(index);
+                }
+                tab.context_menu(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Name");
+                        let mut edited = name.clone();
+                        if ui.text_edit_singleline(&mut edited).changed() {
+                            rename = Some((index, edited));
+                        }
+                    });
+                    let label = if canvas.is_dirty() { "✖ Close page (discard changes)" } else { "✖ Close page" };
+                    if ui.add_enabled(can_close, egui::Button::new(label)).clicked() {
+                        close = Some(index);
+                        ui.close_menu();
+                    }
+                });
+            }
+            add = ui.button("➕").on_hover_text("Add a page").clicked();
+        });
+
+        if let Some(index) = switch_to {
+            self.switch_tab(index);
+        }
+        if let Some((index, name)) = rename {
+            self.rename_tab(index, name);
+        }
+        if let Some(index) = close {
+            self.remove_tab(index);
+        }
+        if add {
+            let index = self.add_tab(self.unused_name());
+            self.switch_tab(index);
+        }
+    }
+
+    /// Serialize every page to JSON, each with its name and its own canvas layout
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        let tabs = self.tabs.iter()
+            .map(|(name, canvas)| {
+                Ok(TabLayout { name: name.clone(), layout: serde_json::from_str(&canvas.to_json()?)? })
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+        serde_json::to_string_pretty(&TabsLayout { active: self.active, tabs })
+    }
+
+    /// Replace all pages with ones saved by to_json()
+    ///
+    /// A layout saved from a single canvas is accepted too and becomes one page
+    /// under the active page's name. Pages keep the active page's canvas
+    /// settings and the clipboard. On error the pages are left unchanged.
+    pub fn from_json(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let (tabs, active) = match serde_json::from_str::<TabsLayout>(json) {
+            Ok(saved) => {
+                let mut tabs = Vec::with_capacity(saved.tabs.len());
+                for tab in saved.tabs {
+                    let mut canvas = self.new_canvas();
+                    canvas.from_json(&tab.layout.to_string())?;
+                    tabs.push((tab.name, canvas));
+                }
+                if tabs.is_empty() {
+                    return Err(serde::de::Error::custom("layout has no pages"));
+                }
+                (tabs, saved.active)
+            }
+            Err(err) => {
+                let mut canvas = self.new_canvas();
+                canvas.from_json(json).map_err(|_| err)?;
+                (vec![(self.active_name().to_string(), canvas)], 0)
+            }
+        };
+
+        let clip = self.active_mut().take_clipboard();
+        self.tabs = tabs;
+        self.active = active.min(self.tabs.len() - 1);
+        self.active_mut().set_clipboard(clip);
+        Ok(())
+    }
+
+    /// Empty canvas with the active page's settings and size
+    ///
+    /// Taking over the size lets layouts load at the right offset before the
+    /// page is first shown.
+    fn new_canvas(&self) -> DragDropCanvas {
+        let mut canvas = DragDropCanvas::new();
+        canvas.settings = self.active().settings.clone();
+        canvas.canvas_rect = self.active().canvas_rect;
+        canvas
+    }
+
+    /// "Page N" with the lowest N not already taken
+    fn unused_name(&self) -> String {
+        (1..)
+            .map(|n| format!("Page {n}"))
+            .find(|name| self.tabs.iter().all(|(taken, _)| taken != name))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_names_that_would_leave_the_folder_are_refused() {
+        let mut tabs = CanvasTabs::new();
+
+        for bad in ["../escape", "a/b", "a\\b", "v1.2"] {
+            assert!(!tabs.rename_tab(0, bad), "{bad:?} was accepted");
+            assert_eq!(tabs.active_name(), "Page 1");
+            assert_eq!(file_name_for(bad), None);
+        }
+
+        assert!(tabs.rename_tab(0, ""));
+        assert_eq!(file_name_for(tabs.active_name()), None);
+        assert!(tabs.rename_tab(0, "Live set"));
+        assert_eq!(file_name_for(tabs.active_name()), Some("Live set"));
+    }
+}
@@ -13,17 +13,19 @@
 //! 
 //! ## Example Usage
 //! 
-//! ```rust
+//! ```ignore
 //! let mut canvas = DragDropCanvas::new();
 //! 
-//! // Add a panel
-//! canvas.add_widget(WidgetType::Panel {
+//! // Add a panel, keeping its ID to look it up later with get_widget()
+//! let master_id = canvas.add_widget(WidgetType::Panel {
 //!     title: "MASTER".to_string(),
 //!     color: WidgetColor::Cyan,
 //!     width: 200.0,
 //!     height: 150.0,
 //!     collapsed: false,
 //!     contained_widgets: Vec::new(),
+//!     minimize_to_settings_icon: false,
+//!     grid_layout: false,
 //! }, Pos2::new(50.0, 50.0));
 //! 
 //! // Render the canvas
@@ -31,9 +33,16 @@
 //! ```
 
 use egui::{Color32, Pos2, Rect, Ui, Vec2, FontId, Align2, RichText, Stroke};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
 use std::f32::consts::PI;
 use crate::canvas::constants::*;
+use crate::canvas::export;
 use crate::canvas::panels::PanelManager;
+use crate::canvas::react_import;
+use crate::canvas::settings::{CanvasSettings, CanvasTheme, ResizePolicy};
+use crate::canvas::widgets::properties::{HasColor, HasLabel, HasValue};
+use crate::canvas::widgets::rendering;
 use crate::canvas::widgets::types::*;
 
 
@@ -43,13 +52,21 @@ use crate::canvas::widgets::types::*;
 /// Supports nested panels, smart positioning, and visual feedback.
 pub struct DragDropCanvas {
     pub widgets: Vec<DraggableWidget>,
+    pub connections: Vec<(usize, usize)>, // Signal routing cables as (source ID, destination ID), drawn under the widgets
     pub next_id: usize,
+    free_ids: Vec<usize>, // IDs of deleted widgets, reused when settings.recycle_ids is on
     pub canvas_rect: Rect,
-    pub editing_widget: Option<usize>, // Index of widget being edited
+    pub editing_widget: Option<usize>, // ID of widget being edited
     pub show_edit_window: bool,
+    pub context_menu: Option<(usize, Pos2)>, // Widget ID and canvas position of the open right-click menu
     
     // Panel selection state
     pub selected_panel: Option<usize>, // ID of currently selected panel for widget placement
+    pub selected_widget: Option<usize>, // ID of the last clicked widget
+    pub selected_widgets: Vec<usize>, // IDs in the Shift+click or marquee multi-selection, empty when only one widget is selected
+    selection_cycle: Option<(Pos2, usize)>, // Last Alt+click position and depth into the widget stack there
+    marquee: Option<(Pos2, Pos2)>, // Start and current corner of a selection drag on empty canvas
+    connecting: Option<usize>, // Source widget ID of a Shift+press that may become a cable to another widget
     
     // Drag and drop state (cleaned up but kept compatible)
     pub dragging_widget: Option<usize>, // Index of currently dragging widget
@@ -58,13 +75,197 @@ pub struct DragDropCanvas {
     pub last_mouse_pos: Option<Pos2>,
     pub resizing_widget: Option<usize>, // Index of widget being resized
     pub resize_start_size: Vec2, // Original size when resize started
+    resize_start_pointer: Pos2, // Pointer position when resize started
+    resize_start_position: Pos2, // Widget position when resize started
     pub palette_dragging: Option<WidgetType>, // Widget type being dragged from palette
     pub palette_drag_pos: Option<Pos2>, // Current position of palette drag
+    pub fine_tuning_widget: Option<usize>, // Index of widget being scrubbed with a right-drag
+    fine_tune_press_pos: Option<Pos2>, // Right-press position, cleared once the press turns into a drag
+    fine_tune_last_pos: Option<Pos2>,
+    region_handle: Option<RegionHandle>, // Handle grabbed when interacting_widget is a region selector
+    encoder_travel: f32, // Steps' worth of drag not yet applied to the rotary encoder being turned
+    glide: Option<(usize, Vec2)>, // Index and velocity (points/s) of a widget coasting after a flick
+    spring_back: Option<(usize, Pos2)>, // Index and resting position of a widget released past its panel's edge
     
     // Visual feedback
     pub alignment_guides: Vec<AlignmentGuide>,
     pub drag_hover_panel: Option<usize>, // Panel being hovered over during drag
+    rejected_drop: Option<(usize, f64)>, // ID of the widget whose move into a panel was refused, and when
     pub needs_repositioning: bool, // Whether canvas widgets need to be repositioned
+    
+    // Layout loading
+    pub resolve_overlaps_on_load: bool, // Run resolve_overlaps after from_json
+    pub needs_overlap_resolution: bool, // Overlap repair deferred until canvas size is known
+    needs_origin_offset: bool, // Layout loaded before the canvas size was known, still placed at a zero origin
+    
+    // Canvas settings
+    pub settings: CanvasSettings,
+    pub show_settings_popup: bool, // Settings popup opened from the gear icon
+    pub show_shortcuts: bool, // Keyboard shortcut overlay, toggled with '?'
+    pub confirm_clear: bool, // Clear Canvas was pressed; the canvas clears only once the prompt is confirmed
+    pub performance_mode: bool, // Locked layout: widgets can't be moved or resized, only played
+    pub interaction_mode: Mode, // Live: performance mode plus no palette, edit window, deletion, paste, undo or spawn hotkeys
+    
+    // View state
+    pub pan_offset: Vec2, // How far the view has been scrolled with the mouse wheel or a middle-drag
+    pub zoom: f32, // View magnification (MIN_ZOOM..=MAX_ZOOM), changed with Ctrl+scroll around the cursor
+    zoom_offset: Vec2, // Translation of the zoomed view, relative to the canvas corner
+    design_transform: Option<egui::emath::TSTransform>, // Canvas -> screen scaling while a design size is set
+    
+    // Pointer state
+    hovered_widget_id: Option<usize>, // ID of the widget under the pointer, see hovered_widget()
+    changed_widgets: BTreeSet<usize>, // IDs whose value changed since the last take_changed()
+    events: Vec<CanvasEvent>, // User actions since the last take_events(), oldest first
+    flashes: HashMap<usize, f64>, // Widget ID -> time its value was last set by the host
+    frame_time: f64, // egui time of the latest render, used to stamp flashes
+    exported_png: Option<Vec<u8>>, // Image from the Export PNG button, waiting for take_exported_png()
+    
+    // History
+    undo_stack: Vec<CanvasSnapshot>, // Oldest first, capped at UNDO_LIMIT
+    nudge_burst: Option<(Vec<usize>, f64)>, // Selection and time of the last nudge, see nudge_selected()
+    clean_layout: Option<String>, // Fingerprint of the layout when last saved or loaded, see is_dirty()
+    
+    // Clipboard
+    clipboard: Option<CanvasSnapshot>, // Widgets taken by the last copy_widget(), canvas-relative like copy_selected()
+    
+    // Extensions
+    custom_renderers: HashMap<String, CustomRenderer>, // Keyed by WidgetType::Custom id
+}
+
+/// Host-provided drawing routine for `WidgetType::Custom` widgets
+pub type CustomRenderer = Box<dyn Fn(&egui::Painter, Rect)>;
+
+/// Minimal canvas state used for presets, undo, autosave and layout files
+///
+/// Snapshots taken with `snapshot()` hold absolute screen positions. When a
+/// snapshot is written with `to_json`, widget positions are stored relative to
+/// the canvas origin (`canvas_rect.min`) so a saved layout lands in the same
+/// place regardless of window size, palette width, or header height.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CanvasSnapshot {
+    pub widgets: Vec<DraggableWidget>,
+    pub next_id: usize,
+    #[serde(default)]
+    pub view_state: Option<ViewState>,
+    #[serde(default)]
+    pub connections: Vec<(usize, usize)>,
+}
+
+/// Selection and view state saved alongside a layout so reopening it resumes where the user left off
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewState {
+    pub selected_panel: Option<usize>,
+    pub zoom: f32,         // View zoom factor, 1.0 = unzoomed
+    pub zoom_offset: Vec2, // Zoomed view translation, see DragDropCanvas::zoom_offset
+    pub pan_offset: Vec2,  // View scroll offset, see DragDropCanvas::pan_offset
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        Self {
+            selected_panel: None,
+            zoom: 1.0,
+            zoom_offset: Vec2::ZERO,
+            pan_offset: Vec2::ZERO,
+        }
+    }
+}
+
+/// Something the user did on the canvas, reported through `take_events()`
+///
+/// Each event carries the widget's new state, so a host can map widget IDs
+/// to its own parameters without re-reading every widget.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CanvasEvent {
+    ValueChanged { id: usize, value: f32 }, // Knob, slider or (trimmed) VU meter
+    Toggled { id: usize, on: bool },        // Toggle switch
+    ButtonPressed { id: usize, active: bool }, // Push or icon button, with its state after the press; momentary buttons also report the release
+    StepChanged { id: usize, step: usize }, // Rotary encoder, as a step index
+    XyChanged { id: usize, x: f32, y: f32 }, // XY pad, both 0..1
+    RegionChanged { id: usize, start: f32, end: f32 }, // Region selector, in seconds
+    WidgetAdded { id: usize },
+    WidgetDeleted { id: usize },
+}
+
+impl CanvasEvent {
+    /// The event describing a playable widget's current state; None for widgets without one
+    fn for_state(widget: &DraggableWidget) -> Option<Self> {
+        let id = widget.id;
+        match widget.widget_type {
+            WidgetType::Knob { value, .. } | WidgetType::HorizontalSlider { value, .. } | WidgetType::VerticalSlider { value, .. } => {
+                Some(Self::ValueChanged { id, value })
+            }
+            WidgetType::VuMeter { level, .. } => Some(Self::ValueChanged { id, value: level }),
+            WidgetType::ToggleSwitch { on, .. } => Some(Self::Toggled { id, on }),
+            WidgetType::PushButton { active, .. } | WidgetType::IconButton { active, .. } => Some(Self::ButtonPressed { id, active }),
+            WidgetType::RotaryEncoder { value, .. } => Some(Self::StepChanged { id, step: value }),
+            WidgetType::XyPad { x, y, .. } => Some(Self::XyChanged { id, x, y }),
+            WidgetType::RegionSelector { start, end, .. } => Some(Self::RegionChanged { id, start, end }),
+            _ => None,
+        }
+    }
+    
+    /// Queue an event for a widget the user just changed
+    ///
+    /// A continuous change to the widget reported last replaces that event, so
+    /// dragging a knob queues one event per poll rather than one per frame.
+    /// Past EVENT_LIMIT the oldest events are dropped.
+    fn queue_change(events: &mut Vec<Self>, widget: &DraggableWidget) {
+        let Some(event) = Self::for_state(widget) else {
+            return;
+        };
+        let continuous = |event: &Self| matches!(event, Self::ValueChanged { .. } | Self::XyChanged { .. } | Self::RegionChanged { .. });
+        match events.last_mut() {
+            Some(last) if continuous(last) && std::mem::discriminant(last) == std::mem::discriminant(&event) && last.id() == widget.id => {
+                *last = event;
+            }
+            _ => Self::queue(events, event),
+        }
+    }
+    
+    /// Queue an event, dropping the oldest past EVENT_LIMIT
+    fn queue(events: &mut Vec<Self>, event: Self) {
+        if events.len() >= EVENT_LIMIT {
+            events.remove(0);
+        }
+        events.push(event);
+    }
+    
+    /// ID of the widget the event is about
+    pub fn id(&self) -> usize {
+        match *self {
+            Self::ValueChanged { id, .. } | Self::Toggled { id, .. } | Self::ButtonPressed { id, .. } |
+            Self::StepChanged { id, .. } | Self::XyChanged { id, .. } | Self::RegionChanged { id, .. } |
+            Self::WidgetAdded { id } | Self::WidgetDeleted { id } => id,
+        }
+    }
+}
+
+/// Whether the canvas is being built or played, see `DragDropCanvas::set_mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    #[default]
+    Design, // Full editing
+    Live,   // Finished console: controls stay playable, the layout can't be changed
+}
+
+/// Entry picked from the right-click menu of a widget
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ContextAction {
+    Edit,
+    Duplicate,
+    Delete,
+    BringToFront,
+    SendToBack,
+    ToggleLock,
+}
+
+/// Which end of a `WidgetType::RegionSelector` is being dragged
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RegionHandle {
+    Start,
+    End,
 }
 
 #[derive(Debug, Clone)]
@@ -76,32 +277,76 @@ pub struct AlignmentGuide {
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AlignmentType {
-    CenterHorizontal, // Pink - centered on canvas
-    CenterVertical,   // Pink - centered on canvas
+    CenterHorizontal, // Pink - centered on canvas (or on the containing panel)
+    CenterVertical,   // Pink - centered on canvas (or on the containing panel)
     WidgetAlignHorizontal, // Yellow - aligned with other widget
     WidgetAlignVertical,   // Yellow - aligned with other widget
+    PanelEdgeHorizontal, // Cyan - against the left or right content edge of the containing panel
+    PanelEdgeVertical,   // Cyan - just under the containing panel's title bar
 }
 
 impl Default for DragDropCanvas {
     fn default() -> Self {
         Self {
             widgets: Vec::new(),
+            connections: Vec::new(),
             next_id: 0,
+            free_ids: Vec::new(),
             canvas_rect: Rect::NOTHING,
             editing_widget: None,
+            context_menu: None,
             show_edit_window: false,
             selected_panel: None,
+            selected_widget: None,
+            selected_widgets: Vec::new(),
+            connecting: None,
+            marquee: None,
+            selection_cycle: None,
             dragging_widget: None,
             drag_offset: Vec2::ZERO,
             interacting_widget: None,
             last_mouse_pos: None,
             resizing_widget: None,
             resize_start_size: Vec2::ZERO,
+            resize_start_pointer: Pos2::ZERO,
+            resize_start_position: Pos2::ZERO,
             palette_dragging: None,
             palette_drag_pos: None,
+            fine_tuning_widget: None,
+            fine_tune_press_pos: None,
+            fine_tune_last_pos: None,
+            region_handle: None,
+            encoder_travel: 0.0,
+            glide: None,
+            spring_back: None,
             alignment_guides: Vec::new(),
             drag_hover_panel: None,
+            rejected_drop: None,
             needs_repositioning: false,
+            resolve_overlaps_on_load: true,
+            needs_overlap_resolution: false,
+            needs_origin_offset: false,
+            settings: CanvasSettings::default(),
+            show_settings_popup: false,
+            show_shortcuts: false,
+            confirm_clear: false,
+            performance_mode: false,
+            interaction_mode: Mode::Design,
+            pan_offset: Vec2::ZERO,
+            zoom: 1.0,
+            zoom_offset: Vec2::ZERO,
+            design_transform: None,
+            hovered_widget_id: None,
+            changed_widgets: BTreeSet::new(),
+            events: Vec::new(),
+            flashes: HashMap::new(),
+            frame_time: 0.0,
+            exported_png: None,
+            undo_stack: Vec::new(),
+            nudge_burst: None,
+            clean_layout: None,
+            clipboard: None,
+            custom_renderers: HashMap::new(),
         }
     }
 }
@@ -123,6 +368,134 @@ impl DragDropCanvas {
         })
     }
     
+    /// ID of the panel or settings panel directly containing a widget, None on the canvas
+    fn container_of(&self, widget_id: usize) -> Option<usize> {
+        self.widgets.iter()
+            .find(|panel| match &panel.widget_type {
+                WidgetType::Panel { contained_widgets, .. } |
+                WidgetType::Settings { contained_widgets, .. } => contained_widgets.contains(&widget_id),
+                _ => false,
+            })
+            .map(|panel| panel.id)
+    }
+    
+    /// ID of the topmost visible panel under `pos` that takes drops, ignoring the widgets being dragged
+    ///
+    /// Settings panels dock to the canvas edges instead, so dragging one never has a target.
+    fn drop_target(&self, pos: Pos2, dragged: &[usize]) -> Option<usize> {
+        let dragging_settings = self.widgets.iter()
+            .any(|w| dragged.contains(&w.id) && matches!(w.widget_type, WidgetType::Settings { .. }));
+        if dragging_settings {
+            return None;
+        }
+        self.render_order().into_iter().rev()
+            .map(|idx| &self.widgets[idx])
+            .find(|panel| {
+                !dragged.contains(&panel.id)
+                    && PanelManager::is_panel_accepting_widgets(panel)
+                    && panel.get_rect().contains(pos)
+                    && !self.is_widget_in_minimized_panel(panel.id)
+            })
+            .map(|panel| panel.id)
+    }
+    
+    /// Check if a widget may move into a panel without a containment cycle or nesting past max_nesting_depth
+    fn may_adopt(&self, widget_id: usize, panel_id: usize) -> bool {
+        PanelManager::can_nest(&self.widgets, widget_id, panel_id, self.settings.max_nesting_depth)
+    }
+    
+    /// Move a widget out of its panel into another, restacked above it so it stays visible
+    ///
+    /// Anything inside the widget comes along. Not undoable on its own.
+    fn adopt_into_panel(&mut self, widget_id: usize, panel_id: usize) {
+        PanelManager::remove_widget_from_containers(&mut self.widgets, widget_id);
+        let idx = self.widgets.iter().position(|w| w.id == widget_id);
+        let panel_idx = self.widgets.iter().position(|w| w.id == panel_id);
+        let (Some(idx), Some(panel_idx)) = (idx, panel_idx) else {
+            return;
+        };
+        PanelManager::add_widget_to_panel(&mut self.widgets, panel_idx, widget_id);
+        if self.stack_block(idx).first().is_some_and(|&bottom| bottom < panel_idx) {
+            if let Some(order) = self.stack_order(idx, panel_idx + 1) {
+                self.reorder(&order);
+            }
+        }
+    }
+    
+    /// Snap the children of expanded grid-layout panels into equal cells
+    ///
+    /// Cells are the size of the largest child plus PANEL_MARGIN and fill the
+    /// content area left to right, top to bottom, each child centered in its
+    /// cell. Children keep their reading order (the row their center falls in,
+    /// then x), so a widget dropped between two others slots in there. While a
+    /// child is dragged the others close up around it; a child that is gliding
+    /// or springing back eases into its cell instead of jumping.
+    fn reflow_grid_panels(&mut self) {
+        let dragged_id = self.dragging_widget.and_then(|idx| self.widgets.get(idx)).map(|w| w.id);
+        let grid_panels: Vec<(usize, Vec<usize>)> = self.widgets.iter()
+            .enumerate()
+            .filter_map(|(idx, w)| match &w.widget_type {
+                WidgetType::Panel { grid_layout: true, collapsed: false, contained_widgets, .. } => Some((idx, contained_widgets.clone())),
+                _ => None,
+            })
+            .collect();
+        
+        for (panel_idx, contained) in grid_panels {
+            let panel_rect = self.widgets[panel_idx].get_rect();
+            let content = Rect::from_min_max(
+                panel_rect.min + Vec2::new(PANEL_MARGIN, PANEL_TITLE_HEIGHT),
+                panel_rect.max - Vec2::splat(PANEL_MARGIN),
+            );
+            let mut children: Vec<usize> = contained.iter()
+                .filter(|&&id| Some(id) != dragged_id)
+                .filter_map(|&id| self.widgets.iter().position(|w| w.id == id))
+                .collect();
+            if children.is_empty() {
+                continue;
+            }
+            
+            let largest = children.iter().fold(Vec2::ZERO, |largest, &idx| largest.max(self.widgets[idx].size));
+            let cell = largest + Vec2::splat(PANEL_MARGIN);
+            let columns = (((content.width() + PANEL_MARGIN) / cell.x).floor() as usize).max(1);
+            let reading_order = |idx: usize| {
+                let center = self.widgets[idx].get_rect().center();
+                (((center.y - content.top()) / cell.y).floor() as i32, center.x)
+            };
+            children.sort_by(|&a, &b| {
+                let ((row_a, x_a), (row_b, x_b)) = (reading_order(a), reading_order(b));
+                row_a.cmp(&row_b).then(x_a.total_cmp(&x_b))
+            });
+            
+            for (n, idx) in children.into_iter().enumerate() {
+                let cell_min = content.min + Vec2::new((n % columns) as f32 * cell.x, (n / columns) as f32 * cell.y);
+                let target = cell_min + (largest - self.widgets[idx].size) / 2.0;
+                if self.glide.is_some_and(|(glide_idx, _)| glide_idx == idx) || self.spring_back.is_some_and(|(spring_idx, _)| spring_idx == idx) {
+                    self.glide = None;
+                    self.spring_back = Some((idx, target));
+                } else if self.widgets[idx].position != target {
+                    let delta = target - self.widgets[idx].position;
+                    self.move_widget_with_contents(idx, delta);
+                }
+            }
+        }
+    }
+    
+    /// Indices of the widgets a dragged widget may align and snap to
+    ///
+    /// Only widgets in the same container qualify: siblings in the same panel,
+    /// or other top-level widgets when it sits on the canvas.
+    fn snap_targets(&self, dragging_idx: usize) -> Vec<usize> {
+        let Some(dragging) = self.widgets.get(dragging_idx) else {
+            return Vec::new();
+        };
+        let scope = self.container_of(dragging.id);
+        self.widgets.iter()
+            .enumerate()
+            .filter(|(idx, other)| *idx != dragging_idx && self.container_of(other.id) == scope)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+    
     /// Get the list of widgets not contained in any panel (canvas widgets)
     fn get_canvas_widgets(&self) -> Vec<usize> {
         self.widgets.iter()
@@ -139,7 +512,7 @@ impl DragDropCanvas {
     
     /// Simple, reliable right-to-left grid positioning
     fn find_next_canvas_position(&self, widget_size: Vec2) -> Pos2 {
-        let margin = 20.0;
+        let margin = self.settings.canvas_margin;
         let spacing = 0.5;
         
         // Define the grid area
@@ -238,6 +611,218 @@ impl DragDropCanvas {
         }
     }
     
+    /// Indices of widgets an in-progress pointer interaction refers to
+    fn held_widget_indices(&self) -> Vec<usize> {
+        [
+            self.dragging_widget,
+            self.resizing_widget,
+            self.interacting_widget,
+            self.fine_tuning_widget,
+            self.glide.map(|(idx, _)| idx),
+            self.spring_back.map(|(idx, _)| idx),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+    
+    /// Move a flicked widget one frame further, slowing it with friction
+    ///
+    /// The glide respects the same bounds and panel containment as a drag, stops
+    /// on any new press, and applies alignment snapping once it settles.
+    fn advance_glide(&mut self, ui: &Ui) {
+        let Some((idx, velocity)) = self.glide else {
+            return;
+        };
+        let Some(widget) = self.widgets.get(idx) else {
+            self.glide = None;
+            return;
+        };
+        if ui.ctx().input(|i| i.pointer.any_pressed()) {
+            self.glide = None;
+            return;
+        }
+        
+        let friction = 6.0; // Velocity decays by e^-friction per second
+        let settle_speed = 40.0; // points per second
+        let dt = ui.ctx().input(|i| i.stable_dt).min(0.1);
+        
+        let widget_size = widget.size;
+        let target = widget.position + velocity * dt;
+        let mut final_pos = self.constrain_drag_position(idx, target, widget_size);
+        
+        // Hitting an edge kills the motion along that axis
+        let mut velocity = velocity * (-friction * dt).exp();
+        if final_pos.x != target.x {
+            velocity.x = 0.0;
+        }
+        if final_pos.y != target.y {
+            velocity.y = 0.0;
+        }
+        
+        if velocity.length() < settle_speed {
+            if self.settings.snap_to_grid {
+                final_pos = self.constrain_drag_position(idx, self.snap_to_grid(final_pos), widget_size);
+            } else if self.settings.snapping_enabled {
+                final_pos = self.apply_snapping(idx, final_pos, widget_size);
+            }
+            self.glide = None;
+        } else {
+            self.glide = Some((idx, velocity));
+            ui.ctx().request_repaint();
+        }
+        
+        self.widgets[idx].position = final_pos;
+    }
+    
+    /// Ease a widget released past its panel's edge back to its resting position
+    ///
+    /// A new press finishes the spring immediately, so nothing is ever grabbed
+    /// while it still hangs outside its panel.
+    fn advance_spring_back(&mut self, ui: &Ui) {
+        let Some((idx, target)) = self.spring_back else {
+            return;
+        };
+        let Some(widget) = self.widgets.get_mut(idx) else {
+            self.spring_back = None;
+            return;
+        };
+        
+        let dt = ui.ctx().input(|i| i.stable_dt).min(0.1);
+        let remaining = target - widget.position;
+        if remaining.length() < 0.5 || ui.ctx().input(|i| i.pointer.any_pressed()) {
+            widget.position = target;
+            self.spring_back = None;
+        } else {
+            widget.position += remaining * (1.0 - (-SPRING_STIFFNESS * dt).exp());
+            ui.ctx().request_repaint();
+        }
+    }
+    
+    /// Resistance past a panel edge: follows the pointer at first, then lags more
+    /// and more, never exceeding RUBBER_BAND_LIMIT
+    fn rubber_band(overshoot: Vec2) -> Vec2 {
+        let band = |d: f32| d.signum() * RUBBER_BAND_LIMIT * (1.0 - 1.0 / (d.abs() / RUBBER_BAND_LIMIT + 1.0));
+        Vec2::new(band(overshoot.x), band(overshoot.y))
+    }
+    
+    /// Resize a widget within its type's limits
+    ///
+    /// Panels and status bars take both dimensions; aspect-locked widgets take the
+    /// width as their side; scaled widgets keep their proportions, clamped to their
+    /// `scale_range`; text labels take the height as their line height. Returns false
+    /// for widgets that can't be resized.
+    fn resize_widget(&mut self, widget_idx: usize, size: Vec2) -> bool {
+        let Some(widget) = self.widgets.get_mut(widget_idx) else {
+            return false;
+        };
+        match &mut widget.widget_type {
+            WidgetType::Panel { width, height, collapsed, .. } => {
+                *width = size.x.clamp(100.0, 500.0);
+                *height = size.y.clamp(100.0, 400.0);
+                
+                // A collapsed panel keeps its title-bar height until expanded
+                widget.size = if *collapsed { Vec2::new(*width, widget.size.y) } else { Vec2::new(*width, *height) };
+            }
+            WidgetType::StatusBar { .. } => {
+                widget.size = Vec2::new(size.x.clamp(200.0, 800.0), size.y.clamp(40.0, 120.0));
+            }
+            WidgetType::PushButton { .. } | WidgetType::IconButton { .. } => {
+                widget.set_size(Vec2::splat(size.x.clamp(40.0, 300.0)));
+            }
+            WidgetType::TextLabel { .. } => {
+                widget.set_size(Vec2::new(size.x, size.y.clamp(12.0, 48.0))); // 8-32 pt, as in the edit window
+            }
+            widget_type => {
+                let Some((min_scale, max_scale)) = widget_type.scale_range() else {
+                    return false;
+                };
+                let natural = DraggableWidget::calculate_size(widget_type);
+                let scale = (size.x / natural.x).max(size.y / natural.y).clamp(min_scale, max_scale);
+                widget.set_size(natural * scale);
+            }
+        }
+        true
+    }
+    
+    /// Move a dragged settings panel, docking it to a canvas edge it comes within DOCK_DISTANCE of
+    ///
+    /// A docked panel sits flush against its edge and stretches along it (full height on
+    /// the left and right, full width at the top and bottom). Dragging it away undocks it
+    /// back to its natural size, under the pointer.
+    fn drag_settings_panel(&mut self, idx: usize, pointer: Pos2) {
+        let natural = DraggableWidget::calculate_size(&self.widgets[idx].widget_type);
+        // Keep the grab point inside the natural size, which a stretched panel can exceed
+        self.drag_offset = self.drag_offset.min(natural - Vec2::splat(PANEL_MARGIN)).max(Vec2::ZERO);
+        let floating = Rect::from_min_size(self.constrain_drag_position(idx, pointer - self.drag_offset, natural), natural);
+        
+        let canvas = self.canvas_rect;
+        let edge = [
+            (CanvasEdge::Left, floating.left() - canvas.left()),
+            (CanvasEdge::Right, canvas.right() - floating.right()),
+            (CanvasEdge::Top, floating.top() - canvas.top()),
+            (CanvasEdge::Bottom, canvas.bottom() - floating.bottom()),
+        ]
+            .into_iter()
+            .filter(|(_, distance)| *distance <= DOCK_DISTANCE)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(CanvasEdge::None, |(edge, _)| edge);
+        
+        if edge != CanvasEdge::None {
+            self.alignment_guides.clear();
+        }
+        let rect = self.docked_rect(edge, natural).unwrap_or(floating);
+        let widget = &mut self.widgets[idx];
+        if let WidgetType::Settings { edge: docked, .. } = &mut widget.widget_type {
+            *docked = edge;
+        }
+        widget.position = rect.min;
+        widget.size = rect.size();
+    }
+    
+    /// Where a settings panel of the given natural size sits when docked to `edge`; None when floating
+    fn docked_rect(&self, edge: CanvasEdge, natural: Vec2) -> Option<Rect> {
+        let canvas = self.canvas_rect;
+        match edge {
+            CanvasEdge::Left => Some(Rect::from_min_size(canvas.left_top(), Vec2::new(natural.x, canvas.height()))),
+            CanvasEdge::Right => Some(Rect::from_min_size(Pos2::new(canvas.right() - natural.x, canvas.top()), Vec2::new(natural.x, canvas.height()))),
+            CanvasEdge::Top => Some(Rect::from_min_size(canvas.left_top(), Vec2::new(canvas.width(), natural.y))),
+            CanvasEdge::Bottom => Some(Rect::from_min_size(Pos2::new(canvas.left(), canvas.bottom() - natural.y), Vec2::new(canvas.width(), natural.y))),
+            CanvasEdge::None => None,
+        }
+    }
+    
+    /// Keep docked, expanded settings panels flush with their edge and stretched along it,
+    /// e.g. after the canvas is resized, the panel is expanded or a layout is loaded
+    fn keep_settings_docked(&mut self) {
+        for idx in 0..self.widgets.len() {
+            let WidgetType::Settings { minimized: false, edge, .. } = self.widgets[idx].widget_type else {
+                continue;
+            };
+            let natural = DraggableWidget::calculate_size(&self.widgets[idx].widget_type);
+            if let Some(rect) = self.docked_rect(edge, natural) {
+                self.widgets[idx].position = rect.min;
+                self.widgets[idx].size = rect.size();
+            }
+        }
+    }
+    
+    /// Keep a moved widget inside its container panel, or inside the canvas if it has none
+    fn constrain_drag_position(&self, widget_idx: usize, new_pos: Pos2, widget_size: Vec2) -> Pos2 {
+        // Check if widget is contained in any panel and constrain accordingly with 0.5px padding
+        if let Some(container_panel) = PanelManager::find_widget_container_panel(&self.widgets, widget_idx) {
+            self.constrain_widget_to_panel(new_pos, widget_size, self.widgets[container_panel].id)
+        } else {
+            // Constrain to canvas bounds (no padding needed for canvas)
+            let max_x = (self.canvas_rect.max.x - widget_size.x).max(self.canvas_rect.min.x);
+            let max_y = (self.canvas_rect.max.y - widget_size.y).max(self.canvas_rect.min.y);
+            Pos2::new(
+                new_pos.x.clamp(self.canvas_rect.min.x, max_x),
+                new_pos.y.clamp(self.canvas_rect.min.y, max_y),
+            )
+        }
+    }
+    
     /// Check if a rect conflicts with any existing widget (tight grid with 0.5px spacing)
     fn position_conflicts_with_widgets(&self, test_rect: Rect) -> bool {
         for widget in &self.widgets {
@@ -279,39 +864,106 @@ impl DragDropCanvas {
             WidgetType::StatusBar { .. } => Vec2::new(300.0, 40.0),
             WidgetType::IconButton { .. } => Vec2::new(60.0, 80.0),
             WidgetType::Settings { .. } => Vec2::new(250.0, 300.0),
+            WidgetType::Readout { .. } => Vec2::new(140.0, 70.0),
+            WidgetType::MeterWithReadout { .. } => Vec2::new(180.0, 160.0),
+            WidgetType::TimeDisplay { .. } => Vec2::new(180.0, 60.0),
+            WidgetType::RegionSelector { .. } => Vec2::new(240.0, 50.0),
+            WidgetType::XyPad { .. } => Vec2::new(120.0, 120.0),
+            WidgetType::RotaryEncoder { .. } => Vec2::new(90.0, 110.0),
+            WidgetType::SpectrumAnalyzer { .. } => Vec2::new(240.0, 120.0),
+            WidgetType::Custom { .. } => Vec2::new(100.0, 100.0),
         }
     }
     
-    /// Spawn widget directly (either on canvas or in selected panel)
-    fn spawn_widget_directly(&mut self, widget_type: WidgetType) {
+    /// Spawn widget directly (either on canvas or in selected panel) and return its ID
+    fn spawn_widget_directly(&mut self, widget_type: WidgetType) -> usize {
         let widget_size = Self::get_widget_default_size(&widget_type);
         
         if let Some(panel_id) = self.selected_panel {
             // Try to place in selected panel
             if let Some(pos) = self.find_next_panel_position(panel_id, widget_size) {
-                self.add_widget_to_selected_panel(widget_type, pos);
+                self.add_widget_to_selected_panel(widget_type, pos)
             } else {
                 // Panel is full, place on canvas instead
                 let pos = self.find_next_canvas_position(widget_size);
-                self.add_widget(widget_type, pos);
+                self.add_widget(widget_type, pos)
             }
         } else {
             // Place on canvas
             let pos = self.find_next_canvas_position(widget_size);
-            self.add_widget(widget_type, pos);
+            self.add_widget(widget_type, pos)
+        }
+    }
+    
+    /// Spawn a widget from a hotkey, centered on the cursor like a palette drop
+    ///
+    /// Placement follows the same rules as dropping from the palette: into the
+    /// selected panel if the cursor is over it, otherwise onto the canvas (at the
+    /// cursor in free placement mode, else the next grid slot). Without a cursor
+    /// over the canvas it behaves like clicking the palette button.
+    fn spawn_at_cursor(&mut self, widget_type: WidgetType, cursor: Option<Pos2>) -> usize {
+        let Some(pos) = cursor else {
+            return self.spawn_widget_directly(widget_type);
+        };
+        let at = pos - DraggableWidget::calculate_size(&widget_type) / 2.0;
+        let in_selected_panel = self.selected_panel
+            .and_then(|panel_id| self.widgets.iter().find(|w| w.id == panel_id))
+            .is_some_and(|panel| panel.get_rect().contains(pos));
+        if in_selected_panel {
+            self.add_widget_to_selected_panel(widget_type, at)
+        } else {
+            self.add_widget(widget_type, at)
+        }
+    }
+    
+    /// Move top-level widgets after the canvas changed size from `previous`, following the resize policy
+    ///
+    /// Panels carry their contents along, and widgets the pointer is holding
+    /// are left alone. Proportional and Fixed keep the view's scroll position.
+    fn reposition_canvas_widgets_for_resize(&mut self, previous: Rect) {
+        if self.settings.resize_policy == ResizePolicy::Regrid {
+            self.regrid_canvas_widgets();
+            return;
+        }
+        
+        // Both rects in widget coordinates, i.e. shifted by the scroll
+        let bounds = self.canvas_rect.translate(self.pan_offset);
+        let old_bounds = previous.translate(self.pan_offset);
+        let proportional = self.settings.resize_policy == ResizePolicy::Proportional && old_bounds.is_positive();
+        
+        let held = self.held_widget_indices();
+        for idx in self.get_canvas_widgets() {
+            if held.contains(&idx) {
+                continue;
+            }
+            let rect = self.widgets[idx].get_rect();
+            let mut min = rect.min;
+            if proportional {
+                let fraction = (rect.center() - old_bounds.min) / old_bounds.size();
+                min = bounds.min + fraction * bounds.size() - rect.size() / 2.0;
+            }
+            let clamped = Pos2::new(
+                min.x.clamp(bounds.min.x, (bounds.max.x - rect.width()).max(bounds.min.x)),
+                min.y.clamp(bounds.min.y, (bounds.max.y - rect.height()).max(bounds.min.y)),
+            );
+            if clamped != rect.min {
+                self.move_widget_with_contents(idx, clamped - rect.min);
+            }
         }
     }
     
     /// Simple grid reposition on canvas resize
-    fn reposition_canvas_widgets_for_resize(&mut self) {
-        let margin = 20.0;
+    fn regrid_canvas_widgets(&mut self) {
+        let margin = self.settings.canvas_margin;
         let spacing = 0.5;
         
-        // Get canvas widgets only (not in panels)
+        // Get canvas widgets only (not in panels), leaving alone any widget the pointer is
+        // holding so an in-progress drag or resize isn't yanked to a grid slot
+        let held = self.held_widget_indices();
         let mut canvas_widgets: Vec<usize> = self.widgets.iter()
             .enumerate()
             .filter_map(|(idx, widget)| {
-                if !self.is_widget_contained(widget.id) {
+                if !self.is_widget_contained(widget.id) && !held.contains(&idx) {
                     Some(idx)
                 } else {
                     None
@@ -360,6 +1012,9 @@ impl DragDropCanvas {
                 }
             }
         }
+        
+        // The grid is rebuilt from the canvas origin, so the view is no longer scrolled
+        self.pan_offset = Vec2::ZERO;
     }
     
 }
@@ -371,10 +1026,20 @@ impl DragDropCanvas {
     }
     
 
-    fn add_widget_to_selected_panel(&mut self, widget_type: WidgetType, click_pos: Pos2) {
+    /// Add a widget inside the selected panel near `click_pos`, or on the canvas if it can't go there
+    ///
+    /// Returns the new widget's ID.
+    fn add_widget_to_selected_panel(&mut self, widget_type: WidgetType, click_pos: Pos2) -> usize {
         if let Some(panel_id) = self.selected_panel {
             // Find the panel
             if let Some(panel_idx) = self.widgets.iter().position(|w| w.id == panel_id) {
+                // A new panel must not nest deeper than allowed; it goes on the canvas instead
+                let adds_level = matches!(widget_type, WidgetType::Panel { .. } | WidgetType::Settings { .. });
+                if adds_level && PanelManager::nesting_depth(&self.widgets, panel_id) >= self.settings.max_nesting_depth {
+                    self.rejected_drop = Some((panel_id, self.frame_time));
+                    return self.add_widget(widget_type, click_pos);
+                }
+                
                 // Check if panel can accept widgets (not collapsed/minimized)
                 if PanelManager::is_panel_accepting_widgets(&self.widgets[panel_idx]) {
                     let panel_rect = self.widgets[panel_idx].get_rect();
@@ -402,14 +1067,14 @@ impl DragDropCanvas {
                     
                     final_pos = self.find_non_overlapping_position(final_pos, widget_size, &panel_widget_ids, panel_rect);
                     
-                    let widget = DraggableWidget::new(self.next_id, widget_type, final_pos);
+                    let widget = DraggableWidget::new(self.allocate_id(), widget_type, final_pos);
                     let widget_id = widget.id;
                     self.widgets.push(widget);
-                    self.next_id += 1;
+                    CanvasEvent::queue(&mut self.events, CanvasEvent::WidgetAdded { id: widget_id });
                     
                     // Add to panel's contained widgets
                     PanelManager::add_widget_to_panel(&mut self.widgets, panel_idx, widget_id);
-                    return; // Successfully placed in panel
+                    return widget_id; // Successfully placed in panel
                 } else {
                     // Panel is collapsed/minimized, clear selection and fall back to canvas
                     self.selected_panel = None;
@@ -421,7 +1086,7 @@ impl DragDropCanvas {
         }
         
         // Fallback: place on canvas if no valid selected panel
-        self.add_widget(widget_type, click_pos);
+        self.add_widget(widget_type, click_pos)
     }
     
     fn find_non_overlapping_position(&self, preferred_pos: Pos2, widget_size: Vec2, existing_widget_ids: &[usize], bounds: Rect) -> Pos2 {
@@ -487,9 +1152,21 @@ impl DragDropCanvas {
         Pos2::new(start_x, start_y)
     }
     
-    pub fn add_widget(&mut self, widget_type: WidgetType, _position: Pos2) {
-        // Calculate position using the new right-to-left logic
-        let position = if self.canvas_rect != Rect::NOTHING {
+    /// Add a widget to the main canvas and return its ID
+    ///
+    /// `position` is only honored in free placement mode; otherwise the widget
+    /// goes to the next free slot of the right-to-left grid. Use `add_widget_at`
+    /// to place a widget at an exact position.
+    pub fn add_widget(&mut self, widget_type: WidgetType, position: Pos2) -> usize {
+        let position = if self.settings.free_placement {
+            if self.canvas_rect != Rect::NOTHING {
+                let widget_size = DraggableWidget::calculate_size(&widget_type);
+                self.free_placement_position(position, widget_size)
+            } else {
+                // Canvas size unknown, keep the requested position as-is
+                position
+            }
+        } else if self.canvas_rect != Rect::NOTHING {
             // Canvas size is known, use new right-to-left positioning
             let widget_size = Self::get_widget_default_size(&widget_type);
             self.find_next_canvas_position(widget_size)
@@ -499,11 +1176,137 @@ impl DragDropCanvas {
             Pos2::new(50.0, 50.0)
         };
         
-        let widget = DraggableWidget::new(self.next_id, widget_type, position);
+        let widget = DraggableWidget::new(self.allocate_id(), widget_type, position);
+        let widget_id = widget.id;
+        CanvasEvent::queue(&mut self.events, CanvasEvent::WidgetAdded { id: widget_id });
         self.widgets.push(widget);
+        widget_id
+    }
+    
+    /// Add a widget to the main canvas at exactly `position` and return its ID
+    ///
+    /// Unlike `add_widget` there is no grid placement and no nudging off other
+    /// widgets: the position, in canvas coordinates like `DraggableWidget::position`,
+    /// is only clamped so the widget stays on the canvas. Before the canvas has
+    /// been laid out the position is kept as given.
+    pub fn add_widget_at(&mut self, widget_type: WidgetType, position: Pos2) -> usize {
+        let position = if self.canvas_rect != Rect::NOTHING {
+            self.clamp_to_canvas(position, DraggableWidget::calculate_size(&widget_type))
+        } else {
+            position
+        };
+        
+        let widget = DraggableWidget::new(self.allocate_id(), widget_type, position);
+        let widget_id = widget.id;
+        CanvasEvent::queue(&mut self.events, CanvasEvent::WidgetAdded { id: widget_id });
+        self.widgets.push(widget);
+        widget_id
+    }
+    
+    /// ID for a new widget: a recycled one if enabled and available, otherwise the next unused number
+    fn allocate_id(&mut self) -> usize {
+        if self.settings.recycle_ids {
+            while let Some(id) = self.free_ids.pop() {
+                // A restored or loaded layout may have brought the ID back into use
+                if !self.widgets.iter().any(|w| w.id == id) {
+                    return id;
+                }
+            }
+        }
+        let id = self.next_id;
         self.next_id += 1;
+        id
+    }
+    
+    /// Delete a widget by ID; returns false if there is no such widget
+    ///
+    /// Widgets inside a deleted panel are deleted too with
+    /// `settings.delete_panel_cascade`, otherwise they are released onto the
+    /// canvas. Any drag or edit in progress is cancelled, since it may refer
+    /// to a removed widget. Undoable.
+    pub fn delete_widget(&mut self, widget_id: usize) -> bool {
+        self.delete_widgets(&[widget_id])
+    }
+    
+    /// Delete several widgets as one undo step, following the same panel rule as delete_widget()
+    fn delete_widgets(&mut self, widget_ids: &[usize]) -> bool {
+        let mut doomed: Vec<usize> = widget_ids.iter().copied().filter(|id| self.widgets.iter().any(|w| w.id == *id)).collect();
+        if doomed.is_empty() {
+            return false;
+        }
+        if self.settings.delete_panel_cascade {
+            for id in doomed.clone() {
+                doomed.extend(self.contained_widgets_recursive(id));
+            }
+        }
+        
+        self.push_undo();
+        for id in doomed {
+            if let Some(idx) = self.widgets.iter().position(|w| w.id == id) {
+                self.remove_widget(idx);
+            }
+        }
+        self.reset_interaction_state();
+        true
+    }
+    
+    /// Delete a widget and drop every panel membership, link, connection and pending report that names it
+    ///
+    /// Its ID goes on the free list, so without this cleanup a recycled ID
+    /// would silently join the old panel or links, or report the old widget's
+    /// change or flash.
+    fn remove_widget(&mut self, idx: usize) {
+        let removed = self.widgets.remove(idx);
+        self.connections.retain(|&(from_id, to_id)| from_id != removed.id && to_id != removed.id);
+        for widget in &mut self.widgets {
+            widget.linked_to.retain(|id| *id != removed.id);
+            if let WidgetType::Panel { contained_widgets, .. } | WidgetType::Settings { contained_widgets, .. } = &mut widget.widget_type {
+                contained_widgets.retain(|id| *id != removed.id);
+            }
+        }
+        self.selected_widgets.retain(|id| *id != removed.id);
+        if self.selected_widget == Some(removed.id) {
+            self.selected_widget = None;
+        }
+        if self.selected_panel == Some(removed.id) {
+            self.selected_panel = None;
+        }
+        self.changed_widgets.remove(&removed.id);
+        self.flashes.remove(&removed.id);
+        if self.rejected_drop.is_some_and(|(id, _)| id == removed.id) {
+            self.rejected_drop = None;
+        }
+        self.free_ids.push(removed.id);
+        CanvasEvent::queue(&mut self.events, CanvasEvent::WidgetDeleted { id: removed.id });
+    }
+    
+    /// Clamp a widget position so a widget of `widget_size` stays on the canvas (top-left wins if it is too big)
+    fn clamp_to_canvas(&self, position: Pos2, widget_size: Vec2) -> Pos2 {
+        let bounds = self.canvas_rect;
+        Pos2::new(
+            position.x.clamp(bounds.min.x, (bounds.max.x - widget_size.x).max(bounds.min.x)),
+            position.y.clamp(bounds.min.y, (bounds.max.y - widget_size.y).max(bounds.min.y)),
+        )
     }
     
+    /// Clamp a requested position to the canvas and nudge it off a top-level widget it lands on
+    fn free_placement_position(&self, position: Pos2, widget_size: Vec2) -> Pos2 {
+        let bounds = self.canvas_rect;
+        let clamp_to_canvas = |pos: Pos2| self.clamp_to_canvas(pos, widget_size);
+        
+        let canvas_widget_ids: Vec<usize> = self.get_canvas_widgets().iter()
+            .map(|&idx| self.widgets[idx].id)
+            .collect();
+        
+        let pos = clamp_to_canvas(position);
+        if self.rect_overlaps_widgets(Rect::from_min_size(pos, widget_size), &canvas_widget_ids) {
+            clamp_to_canvas(self.find_non_overlapping_position(pos, widget_size, &canvas_widget_ids, bounds))
+        } else {
+            pos
+        }
+    }
+    
+    #[allow(dead_code)]
     fn count_canvas_widgets(&self) -> usize {
         // Count widgets that are on the main canvas (not in any panel)
         self.widgets.iter()
@@ -521,9 +1324,10 @@ impl DragDropCanvas {
     }
 
     pub fn render(&mut self, ui: &mut Ui) {
-        // Set canvas background to match React app (black)
-        ui.style_mut().visuals.extreme_bg_color = BLACK;
-        ui.style_mut().visuals.panel_fill = BLACK;
+        // Set canvas background from the theme (black matches the React app)
+        let background = self.settings.theme.background_color();
+        ui.style_mut().visuals.extreme_bg_color = background;
+        ui.style_mut().visuals.panel_fill = background;
         
         // Get the actual drawing area after UI elements
         let available_rect = ui.available_rect_before_wrap();
@@ -536,48 +1340,154 @@ impl DragDropCanvas {
             available_rect.max
         );
         
+        // With a fixed design size the canvas keeps that size in its own coordinates and is
+        // drawn scaled uniformly into the available area, centered with letterboxing
+        let design_size = self.settings.design_size.filter(|size| size.x > 0.0 && size.y > 0.0);
+        let (actual_canvas_rect, screen_rect) = match design_size {
+            Some(design) => {
+                let scale = (actual_canvas_rect.width() / design.x).min(actual_canvas_rect.height() / design.y);
+                let shown = Rect::from_center_size(actual_canvas_rect.center(), design * scale);
+                let canvas = Rect::from_min_size(actual_canvas_rect.min, design);
+                self.design_transform = Some(egui::emath::TSTransform::new(shown.min.to_vec2() - canvas.min.to_vec2() * scale, scale));
+                (canvas, actual_canvas_rect)
+            }
+            None => {
+                self.design_transform = None;
+                (actual_canvas_rect, actual_canvas_rect)
+            }
+        };
+        
         // Check if canvas size changed (for dynamic repositioning)
         let canvas_size_changed = self.canvas_rect != Rect::NOTHING && 
                                  (self.canvas_rect.width() != actual_canvas_rect.width() || 
                                   self.canvas_rect.height() != actual_canvas_rect.height());
         
-        self.canvas_rect = actual_canvas_rect;
+        let previous_canvas_rect = std::mem::replace(&mut self.canvas_rect, actual_canvas_rect);
+        
+        // A layout loaded before the first frame (e.g. restored app state) moves onto the canvas
+        if self.needs_origin_offset {
+            let was_clean = !self.is_dirty();
+            let offset = self.canvas_rect.min.to_vec2();
+            for widget in &mut self.widgets {
+                widget.position += offset;
+            }
+            self.needs_origin_offset = false;
+            if was_clean {
+                self.mark_clean();
+            }
+        }
         
         // Reposition canvas widgets if needed (after canvas size is known)
         if self.needs_repositioning {
             self.reposition_canvas_widgets();
             self.needs_repositioning = false;
         } else if canvas_size_changed {
-            // Canvas size changed - reposition widgets to maintain tight grid
-            self.reposition_canvas_widgets_for_resize();
+            // Canvas size changed - move widgets as the resize policy says
+            self.reposition_canvas_widgets_for_resize(previous_canvas_rect);
+        }
+        self.keep_settings_docked();
+        
+        self.frame_time = ui.ctx().input(|i| i.time);
+        
+        // Track the widget under the pointer, ignoring windows and popups drawn over the canvas
+        let hover_pos = ui.ctx().input(|i| i.pointer.hover_pos());
+        self.hovered_widget_id = hover_pos
+            .filter(|pos| ui.ctx().layer_id_at(*pos).map_or(true, |layer| layer == ui.layer_id()))
+            .map(|pos| self.screen_to_canvas(pos))
+            .filter(|pos| self.visible_canvas_rect().contains(*pos) && !self.settings_icon_rect().contains(*pos))
+            .and_then(|pos| self.widget_at(pos))
+            .map(|idx| self.widgets[idx].id);
+        
+        // Repair overlaps from a layout loaded before the canvas size was known
+        if self.needs_overlap_resolution {
+            let was_clean = !self.is_dirty();
+            self.resolve_overlaps();
+            self.needs_overlap_resolution = false;
+            if was_clean {
+                self.mark_clean(); // The repair is part of loading, not an edit
+            }
         }
 
-        // Draw canvas background
-        ui.painter().rect_filled(actual_canvas_rect, 0.0, BLACK);
+        // Draw canvas background; everything painted from here on is in canvas coordinates
+        ui.painter().rect_filled(screen_rect, 0.0, background);
+        let first_canvas_shape = ui.ctx().graphics_mut(|g| g.entry(ui.layer_id()).next_idx());
 
-        // Handle drag and drop input (only when edit window is not open)
-        if !self.show_edit_window {
+        // Let a flicked widget coast, and one pulled past its panel's edge spring back
+        self.advance_glide(ui);
+        self.advance_spring_back(ui);
+
+        // A live canvas drops any edit that was under way when it went live
+        if self.is_live() {
+            self.show_edit_window = false;
+            self.context_menu = None;
+            self.palette_dragging = None;
+        }
+        
+        // Handle drag and drop input (only when no edit window, settings popup or context menu is open)
+        if !self.show_edit_window && !self.show_settings_popup && self.context_menu.is_none() {
             self.handle_drag_drop(ui);
         }
+        
+        // Keep grid-layout panels tidy as children are added, removed and dropped
+        self.reflow_grid_panels();
+        
+        // Onboarding hint on a truly empty canvas, gone as soon as a palette drag starts
+        if self.widgets.is_empty() && self.palette_dragging.is_none() {
+            self.render_empty_hint(ui);
+        }
+
+        // Faint snap grid under the widgets
+        if self.settings.snap_to_grid {
+            self.render_snap_grid(ui);
+        }
 
         // Collect which widgets should be rendered (not in minimized panels)
         let widgets_to_render: Vec<bool> = self.widgets.iter()
             .map(|w| !self.is_widget_in_minimized_panel(w.id))
             .collect();
         
+        // Signal routing cables go under the widgets they join
+        self.render_connections(ui, &widgets_to_render);
+        
         // Render widgets that should be visible
-        for (widget, &should_render) in self.widgets.iter_mut().zip(widgets_to_render.iter()) {
+        let adjusting = [self.interacting_widget, self.fine_tuning_widget, self.dragging_widget];
+        for idx in self.render_order() {
+            let widget = &mut self.widgets[idx];
+            let active = adjusting.contains(&Some(idx))
+                || [self.hovered_widget_id, self.selected_widget].contains(&Some(widget.id));
+            if widgets_to_render[idx] {
+                Self::paint_widget(ui, widget, active, &self.custom_renderers);
+            }
+        }
+
+        // Describe visible widgets to screen readers (the canvas paints manually,
+        // so each widget gets a hover-only response purely to carry its accessibility info)
+        for (widget, &should_render) in self.widgets.iter().zip(widgets_to_render.iter()) {
             if should_render {
-                widget.render(ui);
+                let response = ui.interact(self.canvas_to_screen_rect(widget.get_rect()), ui.id().with(("canvas_widget", widget.id)), egui::Sense::hover());
+                response.widget_info(|| widget.widget_type.accessibility_info());
             }
         }
 
+        // Draw link curves between ganged widgets
+        if self.settings.show_links {
+            self.render_links(ui, &widgets_to_render);
+        }
+
+        // Outline widgets the host just changed, and a refused drop into a panel
+        self.render_flashes(ui, &widgets_to_render);
+        self.render_rejected_drop(ui);
+        
+        // Outline the multi-selection and draw the marquee being dragged
+        self.render_selection(ui, &widgets_to_render);
+
         // Draw alignment guides
         let painter = ui.painter();
         for guide in &self.alignment_guides {
             let (color, width) = match guide.guide_type {
                 AlignmentType::CenterHorizontal | AlignmentType::CenterVertical => (PINK, 2.0),
                 AlignmentType::WidgetAlignHorizontal | AlignmentType::WidgetAlignVertical => (YELLOW, 1.5),
+                AlignmentType::PanelEdgeHorizontal | AlignmentType::PanelEdgeVertical => (CYAN, 1.5),
             };
             
             painter.line_segment([guide.start, guide.end], Stroke::new(width, color));
@@ -585,11 +1495,15 @@ impl DragDropCanvas {
 
         // Note: Removed visible selection borders around widgets as requested
         
-        // Draw hover highlight for panel during drag
+        // Draw hover highlight for panel during drag, red if the dragged widget may not go in
         if let Some(hover_panel_id) = self.drag_hover_panel {
             if let Some(hover_panel) = self.widgets.iter().find(|w| w.id == hover_panel_id) {
                 let rect = hover_panel.get_rect().expand(2.0);
-                let stroke = Stroke::new(3.0, GREEN);
+                let accepted = match self.dragging_widget.and_then(|idx| self.widgets.get(idx)) {
+                    Some(dragged) => self.may_adopt(dragged.id, hover_panel_id),
+                    None => true,
+                };
+                let stroke = Stroke::new(3.0, if accepted { GREEN } else { RED });
                 
                 // Draw highlight border using line segments
                 painter.line_segment([rect.left_top(), rect.right_top()], stroke);
@@ -622,10 +1536,40 @@ impl DragDropCanvas {
             }
         }
         // No main canvas selection highlighting needed anymore
+        
+        // Outline the widget reached by Alt+click cycling, since it may be hidden underneath others
+        if self.selection_cycle.is_some() {
+            if let Some(selected) = self.selected_widget.and_then(|id| self.widgets.iter().find(|w| w.id == id)) {
+                painter.rect_stroke(selected.get_rect().expand(2.0), 2.0, Stroke::new(1.5, YELLOW), egui::StrokeKind::Outside);
+            }
+        }
+
+        // Padlock in the corner of widgets pinned in place (everything is pinned in performance mode)
+        if !self.layout_locked() {
+            for (widget, _) in self.widgets.iter().zip(&widgets_to_render).filter(|(w, &shown)| shown && w.locked) {
+                painter.text(widget.get_rect().right_top() + Vec2::new(-4.0, 4.0), Align2::RIGHT_TOP, "🔒", FontId::proportional(11.0), YELLOW);
+            }
+            
+            // Resize grip on the hovered or selected widget; panels and status bars draw their own
+            let pointed = [self.hovered_widget_id, self.selected_widget];
+            for (widget, _) in self.widgets.iter().zip(&widgets_to_render).filter(|(w, &shown)| shown && !w.locked && pointed.contains(&Some(w.id))) {
+                if matches!(widget.widget_type, WidgetType::Panel { .. } | WidgetType::StatusBar { .. }) {
+                    continue;
+                }
+                if let Some(handle) = widget.render_rects().resize_handle {
+                    rendering::render_resize_grip(painter, handle, GRAY_400);
+                }
+            }
+        }
 
-        // Draw static settings icon in top-left
+        // Draw settings icon in top-left
         self.render_settings_icon(ui);
         
+        // Show tooltip for the widget under the pointer
+        if self.settings.show_tooltips {
+            self.show_widget_tooltip(ui);
+        }
+        
         
         // Draw palette dragging preview
         if let (Some(widget_type), Some(pos)) = (&self.palette_dragging, self.palette_drag_pos) {
@@ -640,61 +1584,172 @@ impl DragDropCanvas {
             painter.text(
                 preview_rect.center(),
                 Align2::CENTER_CENTER,
-                match widget_type {
-                    WidgetType::Knob { .. } => "Knob",
-                    WidgetType::ToggleSwitch { .. } => "Toggle",
-                    WidgetType::PushButton { .. } => "Button",
-                    WidgetType::VuMeter { .. } => "VU Meter",
-                    WidgetType::HorizontalSlider { .. } => "H Slider",
-                    WidgetType::VerticalSlider { .. } => "V Slider",
-                    WidgetType::LevelIndicator { .. } => "Level",
-                    WidgetType::TextLabel { .. } => "Text",
-                    WidgetType::Panel { .. } => "Panel",
-                    WidgetType::StatusBar { .. } => "Status",
-                    WidgetType::IconButton { .. } => "Icon",
-                    WidgetType::Settings { .. } => "Settings",
-                },
+                widget_type.type_name(),
                 FontId::monospace(12.0),
                 WHITE,
             );
         }
         
-        // Show edit window
-        if self.show_edit_window {
-            self.show_edit_window(ui);
-        }
+        // Zoom everything painted on the canvas this frame, then scale it into the letterboxed design area
+        let transform = self.view_transform();
+        if transform != egui::emath::TSTransform::IDENTITY {
+            let shown = self.design_transform.map_or(self.canvas_rect, |design| design.mul_rect(self.canvas_rect));
+            ui.ctx().graphics_mut(|g| {
+                let shapes = g.entry(ui.layer_id());
+                let end = shapes.next_idx();
+                shapes.transform_range(first_canvas_shape, end, transform);
+                for idx in first_canvas_shape.0..end.0 {
+                    shapes.mutate_shape(egui::layers::ShapeIdx(idx), |shape| shape.clip_rect = shown);
+                }
+            });
+            if self.design_transform.is_some() {
+                ui.painter().rect_stroke(shown, 0.0, Stroke::new(1.0, GRAY_600), egui::StrokeKind::Outside);
+            }
+        }
+        
+        // Show the right-click menu
+        if self.context_menu.is_some() {
+            self.show_context_menu(ui);
+        }
+        
+        // Show edit window
+        if self.show_edit_window {
+            self.show_edit_window(ui);
+        }
+        
+        // Show canvas settings popup
+        if self.show_settings_popup {
+            self.show_settings_popup(ui);
+        }
+        
+        // Show keyboard shortcut overlay
+        if self.show_shortcuts {
+            self.show_shortcut_overlay(ui);
+        }
+        
+        // Ask before Clear Canvas wipes the layout
+        if self.confirm_clear {
+            self.show_clear_prompt(ui);
+        }
     }
 
     fn handle_drag_drop(&mut self, ui: &mut Ui) {
-        let mouse_pos = ui.ctx().input(|i| i.pointer.interact_pos());
+        let mouse_pos = ui.ctx().input(|i| i.pointer.interact_pos()).map(|pos| self.screen_to_canvas(pos));
         let mouse_pressed = ui.ctx().input(|i| i.pointer.primary_pressed());
         let mouse_released = ui.ctx().input(|i| i.pointer.primary_released());
         let right_clicked = ui.ctx().input(|i| i.pointer.secondary_pressed());
         let mouse_held = ui.ctx().input(|i| i.pointer.primary_down());
+        let right_held = ui.ctx().input(|i| i.pointer.secondary_down());
         
         // Handle clicks
         
+        // Settings icon takes priority over any widget underneath it
+        if mouse_pressed && self.palette_dragging.is_none() {
+            if let Some(pos) = mouse_pos {
+                if self.settings_icon_rect().contains(pos) {
+                    self.show_settings_popup = true;
+                    return;
+                }
+            }
+        }
+        
+        // Mouse wheel adjusts the value under the pointer, pans or zooms the view
+        if self.palette_dragging.is_none() && self.dragging_widget.is_none() && self.resizing_widget.is_none() {
+            self.handle_scroll(ui);
+        }
+        
+        // Keyboard control of the selected widget
+        self.handle_keyboard(ui);
+        
+        // Double-click on a knob dial or slider track returns it to its default value
+        if ui.ctx().input(|i| i.pointer.button_double_clicked(egui::PointerButton::Primary)) {
+            if let Some(pos) = mouse_pos {
+                self.reset_value_at(pos);
+            }
+        }
+        
+        // Alt+click cycles the selection down through stacked widgets
+        let alt_held = ui.ctx().input(|i| i.modifiers.alt);
+        if mouse_pressed && alt_held && self.palette_dragging.is_none() && self.interacting_widget.is_none() && self.resizing_widget.is_none() {
+            if let Some(pos) = mouse_pos {
+                if self.visible_canvas_rect().contains(pos) && self.cycle_selection_at(pos) {
+                    return;
+                }
+            }
+        }
+        
+        // Shift+click adds or removes a widget from the multi-selection without pressing or
+        // dragging it (a Shift press on a resize handle still starts an aspect-locked resize).
+        // While the layout is editable the press may also become a Shift+drag cable, so it is
+        // only settled on release
+        let shift_held = ui.ctx().input(|i| i.modifiers.shift);
+        if mouse_pressed && shift_held && self.palette_dragging.is_none() && self.interacting_widget.is_none() && self.resizing_widget.is_none() {
+            if let Some(idx) = mouse_pos.filter(|pos| self.visible_canvas_rect().contains(*pos)).and_then(|pos| {
+                self.widget_at(pos).filter(|&idx| !self.widgets[idx].render_rects().resize_handle.is_some_and(|handle| handle.contains(pos)))
+            }) {
+                if self.layout_locked() {
+                    self.toggle_selected(self.widgets[idx].id);
+                } else {
+                    self.connecting = Some(self.widgets[idx].id);
+                }
+                return;
+            }
+        }
+        
+        // Releasing a Shift+drag over another widget connects the two (or disconnects them if
+        // already connected), over the source widget it was a Shift+click, and anywhere else
+        // it is dropped
+        if let Some(source_id) = self.connecting {
+            if !mouse_held {
+                self.connecting = None;
+                let target_id = mouse_pos
+                    .filter(|pos| self.visible_canvas_rect().contains(*pos))
+                    .and_then(|pos| self.widget_at(pos))
+                    .map(|idx| self.widgets[idx].id);
+                match target_id {
+                    Some(target_id) if target_id == source_id => self.toggle_selected(source_id),
+                    Some(target_id) => {
+                        self.push_undo();
+                        if !self.connect(source_id, target_id) {
+                            self.connections.retain(|&connection| connection != (source_id, target_id));
+                        }
+                    }
+                    None => {}
+                }
+            }
+            return;
+        }
+        
         // Handle click operations (both widget placement and panel selection)
         if mouse_pressed {
             if let Some(pos) = mouse_pos {
                 // Check if on canvas (not on side panel)
-                if pos.x > PALETTE_WIDTH { // Beyond the palette width
+                if self.visible_canvas_rect().contains(pos) {
+                    // A plain click selects the topmost widget and ends any Alt+click cycle;
+                    // clicking outside the multi-selection drops it
+                    self.selected_widget = self.widget_at(pos).map(|idx| self.widgets[idx].id);
+                    self.selection_cycle = None;
+                    if !shift_held && !self.selected_widget.is_some_and(|id| self.selected_widgets.contains(&id)) {
+                        self.selected_widgets.clear();
+                    }
+                    
+                    // A press on empty canvas starts a marquee selection
+                    if self.selected_widget.is_none() && self.visible_canvas_rect().contains(pos) {
+                        self.marquee = Some((pos, pos));
+                    }
+                    
                     // Check if we clicked on a panel
                     let mut clicked_panel_id = None;
-                    for widget in self.widgets.iter().rev() {
+                    for widget in self.render_order().into_iter().rev().map(|idx| &self.widgets[idx]) {
                         if widget.get_rect().contains(pos) {
                             match &widget.widget_type {
-                                WidgetType::Panel { collapsed, .. } => {
-                                    if !collapsed {
-                                        clicked_panel_id = Some(widget.id);
-                                        break;
-                                    }
+                                WidgetType::Panel { collapsed, .. } if !collapsed => {
+                                    clicked_panel_id = Some(widget.id);
+                                    break;
                                 }
-                                WidgetType::Settings { minimized, .. } => {
-                                    if !minimized {
-                                        clicked_panel_id = Some(widget.id);
-                                        break;
-                                    }
+                                WidgetType::Settings { minimized, .. } if !minimized => {
+                                    clicked_panel_id = Some(widget.id);
+                                    break;
                                 }
                                 _ => {}
                             }
@@ -721,24 +1776,23 @@ impl DragDropCanvas {
                 
                 // If mouse released, drop the widget
                 if mouse_released {
+                    // Widgets land where the preview was drawn (centered on the cursor)
+                    let drop_pos = pos - DraggableWidget::calculate_size(&widget_type) / 2.0;
+                    
                     // Check if dropped on canvas (not on side panel)
-                    if pos.x > PALETTE_WIDTH { // Beyond the palette width
+                    if self.visible_canvas_rect().contains(pos) {
                         // Check if we dropped on a panel
                         let mut dropped_on_panel_id = None;
-                        for widget in self.widgets.iter().rev() {
+                        for widget in self.render_order().into_iter().rev().map(|idx| &self.widgets[idx]) {
                             if widget.get_rect().contains(pos) {
                                 match &widget.widget_type {
-                                    WidgetType::Panel { collapsed, .. } => {
-                                        if !collapsed {
-                                            dropped_on_panel_id = Some(widget.id);
-                                            break;
-                                        }
+                                    WidgetType::Panel { collapsed, .. } if !collapsed => {
+                                        dropped_on_panel_id = Some(widget.id);
+                                        break;
                                     }
-                                    WidgetType::Settings { minimized, .. } => {
-                                        if !minimized {
-                                            dropped_on_panel_id = Some(widget.id);
-                                            break;
-                                        }
+                                    WidgetType::Settings { minimized, .. } if !minimized => {
+                                        dropped_on_panel_id = Some(widget.id);
+                                        break;
                                     }
                                     _ => {}
                                 }
@@ -749,7 +1803,7 @@ impl DragDropCanvas {
                         if let Some(panel_id) = dropped_on_panel_id {
                             // Dropped on a panel - place widget in that panel and select it
                             self.selected_panel = Some(panel_id);
-                            self.add_widget_to_selected_panel(widget_type, pos);
+                            self.add_widget_to_selected_panel(widget_type, drop_pos);
                         } else if let Some(panel_id) = self.selected_panel {
                             // Have a selected panel - check if drop is within that panel
                             let drop_in_selected_panel = self.widgets.iter()
@@ -759,14 +1813,14 @@ impl DragDropCanvas {
                             
                             if drop_in_selected_panel {
                                 // Drop is inside the selected panel - place widget there
-                                self.add_widget_to_selected_panel(widget_type, pos);
+                                self.add_widget_to_selected_panel(widget_type, drop_pos);
                             } else {
                                 // Drop is outside the selected panel - place on canvas
-                                self.add_widget(widget_type, pos);
+                                self.add_widget(widget_type, drop_pos);
                             }
                         } else {
                             // No panel selected - place on canvas
-                            self.add_widget(widget_type, pos);
+                            self.add_widget(widget_type, drop_pos);
                         }
                     }
                     
@@ -777,16 +1831,50 @@ impl DragDropCanvas {
             return; // Don't process other drag operations while palette dragging
         }
 
-        // Handle right-click for editing
-        if right_clicked {
+        // Handle right-click: a context menu for the widget under the pointer
+        if right_clicked && !self.is_live() && self.dragging_widget.is_none() && self.resizing_widget.is_none() {
             if let Some(pos) = mouse_pos {
-                for (idx, widget) in self.widgets.iter().enumerate().rev() {
-                    if widget.get_rect().contains(pos) {
-                        self.editing_widget = Some(idx);
-                        self.show_edit_window = true;
-                        break;
+                // With right-drag fine-tuning, a right-press on a knob or slider waits to see
+                // whether it becomes a drag (scrub the value) or a click (open the context menu)
+                let fine_tune_target = self.widget_at(pos)
+                    .filter(|_| self.settings.right_drag_fine_tune)
+                    .filter(|&idx| matches!(
+                        self.widgets[idx].widget_type,
+                        WidgetType::Knob { .. } | WidgetType::HorizontalSlider { .. } | WidgetType::VerticalSlider { .. }
+                    ));
+                
+                if let Some(idx) = fine_tune_target {
+                    self.fine_tuning_widget = Some(idx);
+                    self.fine_tune_press_pos = Some(pos);
+                    self.fine_tune_last_pos = Some(pos);
+                    self.selected_widget = Some(self.widgets[idx].id);
+                } else if let Some(idx) = self.widget_at(pos) {
+                    self.context_menu = Some((self.widgets[idx].id, pos));
+                }
+            }
+        }
+        
+        // Handle right-drag fine-tuning
+        if let Some(idx) = self.fine_tuning_widget {
+            if right_held {
+                if let (Some(current_pos), Some(last_pos)) = (mouse_pos, self.fine_tune_last_pos) {
+                    let drag_threshold = 3.0;
+                    if self.fine_tune_press_pos.is_some_and(|press_pos| (current_pos - press_pos).length() > drag_threshold) {
+                        self.fine_tune_press_pos = None;
                     }
+                    if self.fine_tune_press_pos.is_none() {
+                        self.handle_fine_tune(idx, current_pos - last_pos, shift_held);
+                    }
+                    self.fine_tune_last_pos = Some(current_pos);
+                }
+            } else {
+                // Released without dragging - behave like a plain right-click
+                if let Some(press_pos) = self.fine_tune_press_pos.filter(|_| idx < self.widgets.len() && !self.is_live()) {
+                    self.context_menu = Some((self.widgets[idx].id, press_pos));
                 }
+                self.fine_tuning_widget = None;
+                self.fine_tune_press_pos = None;
+                self.fine_tune_last_pos = None;
             }
         }
 
@@ -796,34 +1884,27 @@ impl DragDropCanvas {
                 // First, assume we clicked on empty space
                 let mut _clicked_widget = false;
                 
-                for (idx, widget) in self.widgets.iter().enumerate().rev() {
+                for idx in self.render_order().into_iter().rev() {
+                    let widget = &self.widgets[idx];
                     if widget.get_rect().contains(pos) {
+                        let geometry = widget.render_rects();
+                        
                         // Check if clicking on panel or status bar resize handle
-                        if matches!(widget.widget_type, WidgetType::Panel { .. } | WidgetType::StatusBar { .. }) {
-                            let rect = widget.get_rect();
-                            let handle_size = 12.0;
-                            let handle_rect = Rect::from_min_size(
-                                Pos2::new(rect.max.x - handle_size, rect.max.y - handle_size),
-                                Vec2::splat(handle_size),
-                            );
-                            
-                            if handle_rect.contains(pos) {
-                                self.resizing_widget = Some(idx);
-                                self.resize_start_size = widget.size;
-                                self.last_mouse_pos = Some(pos);
-                                break;
-                            }
+                        if !self.layout_locked() && !widget.locked && geometry.resize_handle.is_some_and(|handle_rect| handle_rect.contains(pos)) {
+                            self.resizing_widget = Some(idx);
+                            self.resize_start_size = widget.size;
+                            self.resize_start_pointer = pos;
+                            self.resize_start_position = widget.position;
+                            self.last_mouse_pos = Some(pos);
+                            break;
                         }
                         
                         // Check if clicking on interactive widgets (knobs, toggles, buttons)
                         match widget.widget_type {
                             WidgetType::Knob { .. } => {
-                                let knob_center = Pos2::new(
-                                    widget.position.x + widget.size.x / 2.0,
-                                    widget.position.y + 37.0
-                                );
-                                let distance = (pos - knob_center).length();
-                                if distance <= 32.0 { // Within knob radius
+                                let dial = geometry.control.unwrap_or(geometry.rect);
+                                let distance = (pos - dial.center()).length();
+                                if distance <= dial.width() / 2.0 { // Within knob radius
                                     // Check if this widget is inside a panel and preserve panel selection
                                     let widget_panel_id = PanelManager::find_widget_container_panel_id(&self.widgets, widget.id);
                                     if let Some(panel_id) = widget_panel_id {
@@ -836,6 +1917,52 @@ impl DragDropCanvas {
                                     break;
                                 }
                             }
+                            WidgetType::RotaryEncoder { .. } => {
+                                let dial = geometry.control.unwrap_or(geometry.rect);
+                                if (pos - dial.center()).length() <= dial.width() / 2.0 {
+                                    let widget_panel_id = PanelManager::find_widget_container_panel_id(&self.widgets, widget.id);
+                                    if let Some(panel_id) = widget_panel_id {
+                                        self.selected_panel = Some(panel_id);
+                                    }
+                                    
+                                    self.interacting_widget = Some(idx);
+                                    self.encoder_travel = 0.0;
+                                    self.last_mouse_pos = Some(pos);
+                                    break;
+                                }
+                            }
+                            WidgetType::RegionSelector { start, end, duration, .. } => {
+                                // Grab the nearer handle if the press lands on one; elsewhere the widget drags as usual
+                                let track_rect = geometry.control.unwrap_or(geometry.rect);
+                                let x_at = |time: f32| track_rect.left() + (time / duration.max(f32::EPSILON)).clamp(0.0, 1.0) * track_rect.width();
+                                let (start_dist, end_dist) = ((pos.x - x_at(start)).abs(), (pos.x - x_at(end)).abs());
+                                let grab_radius = 8.0;
+                                if pos.y <= track_rect.bottom() + 10.0 && start_dist.min(end_dist) <= grab_radius {
+                                    // Ties (handles on top of each other) pick whichever can still move toward the pointer
+                                    self.region_handle = Some(if start_dist < end_dist || (start_dist == end_dist && pos.x < x_at(start)) {
+                                        RegionHandle::Start
+                                    } else {
+                                        RegionHandle::End
+                                    });
+                                    self.interacting_widget = Some(idx);
+                                    self.last_mouse_pos = Some(pos);
+                                    break;
+                                }
+                            }
+                            // A press on the pad jumps the cursor there; the readout strip below drags the widget
+                            WidgetType::XyPad { .. } if geometry.control.is_some_and(|pad| pad.contains(pos)) => {
+                                self.interacting_widget = Some(idx);
+                                self.last_mouse_pos = Some(pos);
+                                self.handle_widget_interaction(idx, pos);
+                                break;
+                            }
+                            // A momentary button is held down by a press on its face; its label still drags it
+                            WidgetType::PushButton { momentary: true, .. } |
+                            WidgetType::IconButton { momentary: true, .. } if !alt_held && geometry.control.is_some_and(|face| face.contains(pos)) => {
+                                self.interacting_widget = Some(idx);
+                                self.set_momentary(idx, true);
+                                break;
+                            }
                             WidgetType::ToggleSwitch { .. } | 
                             WidgetType::PushButton { .. } | 
                             WidgetType::IconButton { .. } => {
@@ -846,7 +1973,7 @@ impl DragDropCanvas {
                                 // Check if clicking on collapse triangle
                                 let title_area = Rect::from_min_size(
                                     widget.position,
-                                    Vec2::new(widget.size.x, 40.0),
+                                    Vec2::new(widget.size.x, PANEL_TITLE_HEIGHT),
                                 );
                                 if title_area.contains(pos) && pos.x < widget.position.x + 30.0 {
                                     // Handle Panel collapse click - maintain panel selection
@@ -854,7 +1981,12 @@ impl DragDropCanvas {
                                     self.handle_widget_interaction(idx, pos);
                                     return; // Exit early
                                 }
-                                // Just allow dragging the panel - no area selection
+                                
+                                // Panels move only by their title bar; a body click just selects the panel
+                                if !title_area.contains(pos) {
+                                    self.selected_panel = Some(widget.id);
+                                    return; // Exit early
+                                }
                             }
                             _ => {}
                         }
@@ -866,6 +1998,16 @@ impl DragDropCanvas {
                             self.selected_panel = Some(panel_id);
                         }
                         
+                        // Layout is locked in performance mode (or this widget is locked): nothing can move,
+                        // so buttons and toggles fire on press instead of waiting to rule out a drag, and
+                        // sliders jump to the pointer
+                        if self.layout_locked() || widget.locked {
+                            if !matches!(widget.widget_type, WidgetType::Panel { .. } | WidgetType::Settings { .. } | WidgetType::Knob { .. } | WidgetType::XyPad { .. }) {
+                                self.handle_widget_interaction(idx, pos);
+                            }
+                            return; // Exit early
+                        }
+                        
                         // For non-knob widgets or outside knob center, allow for dragging
                         self.dragging_widget = Some(idx);
                         self.drag_offset = pos - widget.position;
@@ -882,16 +2024,51 @@ impl DragDropCanvas {
         if let Some(idx) = self.interacting_widget {
             if mouse_held {
                 if let (Some(current_pos), Some(last_pos)) = (mouse_pos, self.last_mouse_pos) {
-                    let delta_y = last_pos.y - current_pos.y; // Invert for natural feel
-                    self.handle_knob_interaction(idx, delta_y);
+                    if let Some(handle) = self.region_handle {
+                        self.handle_region_interaction(idx, handle, current_pos);
+                    } else {
+                        let delta_y = last_pos.y - current_pos.y; // Invert for natural feel
+                        match self.widgets.get(idx).map(|w| &w.widget_type) {
+                            Some(WidgetType::XyPad { .. }) => self.handle_widget_interaction(idx, current_pos),
+                            Some(WidgetType::RotaryEncoder { .. }) => self.handle_encoder_interaction(idx, delta_y, current_pos),
+                            _ => self.handle_knob_interaction(idx, delta_y, current_pos, shift_held),
+                        }
+                    }
                     self.last_mouse_pos = Some(current_pos);
                 }
             } else {
+                // A held momentary button springs back when let go
+                if self.widgets.get(idx).is_some_and(|w| w.widget_type.is_momentary()) {
+                    self.set_momentary(idx, false);
+                }
                 self.interacting_widget = None;
+                self.region_handle = None;
                 self.last_mouse_pos = None;
             }
         }
 
+        // Marquee selection: every visible widget the rectangle touches is selected on release,
+        // added to the current selection with Shift
+        if let Some((start, _)) = self.marquee {
+            let current = mouse_pos.unwrap_or(start);
+            self.marquee = Some((start, current));
+            if !mouse_held {
+                let marquee = Rect::from_two_pos(start, current);
+                let drag_threshold = 4.0;
+                if marquee.size().max_elem() > drag_threshold {
+                    let mut selection = if shift_held { self.selected_ids() } else { Vec::new() };
+                    for widget in &self.widgets {
+                        if widget.get_rect().intersects(marquee) && !self.is_widget_in_minimized_panel(widget.id) && !selection.contains(&widget.id) {
+                            selection.push(widget.id);
+                        }
+                    }
+                    self.selected_widget = selection.last().copied();
+                    self.selected_widgets = if selection.len() > 1 { selection } else { Vec::new() };
+                }
+                self.marquee = None;
+            }
+        }
+
         // Handle widget dragging
         if let Some(idx) = self.dragging_widget {
             if mouse_held {
@@ -903,66 +2080,101 @@ impl DragDropCanvas {
                         return;
                     };
                     
-                    // Check if widget is contained in any panel and constrain accordingly with 0.5px padding
-                    let mut final_pos = if let Some(container_panel) = PanelManager::find_widget_container_panel(&self.widgets, idx) {
-                        // Use the new constraint method with 0.5px padding
-                        self.constrain_widget_to_panel(new_pos, widget_size, self.widgets[container_panel].id)
-                    } else {
-                        // Constrain to canvas bounds (no padding needed for canvas)
-                        let max_x = (self.canvas_rect.max.x - widget_size.x).max(self.canvas_rect.min.x);
-                        let max_y = (self.canvas_rect.max.y - widget_size.y).max(self.canvas_rect.min.y);
-                        Pos2::new(
-                            new_pos.x.clamp(self.canvas_rect.min.x, max_x),
-                            new_pos.y.clamp(self.canvas_rect.min.y, max_y),
-                        )
-                    };
+                    let mut final_pos = self.constrain_drag_position(idx, new_pos, widget_size);
                     
-                    // Calculate alignment guides and snap if close
-                    self.calculate_alignment_guides(idx, final_pos, widget_size);
+                    // Snap to the grid if enabled, otherwise calculate alignment guides and snap if close
+                    if self.settings.snap_to_grid {
+                        self.alignment_guides.clear();
+                        final_pos = self.constrain_drag_position(idx, self.snap_to_grid(final_pos), widget_size);
+                    } else if self.settings.snapping_enabled {
+                        self.calculate_alignment_guides(idx, final_pos, widget_size);
+                        
+                        // Apply snapping based on guides
+                        final_pos = self.apply_snapping(idx, final_pos, widget_size);
+                    }
                     
-                    // Apply snapping based on guides
-                    final_pos = self.apply_snapping(idx, final_pos, widget_size);
+                    // Past a panel edge the widget stretches after the pointer instead of stopping dead;
+                    // the canvas edge stays a hard stop
+                    if self.container_of(self.widgets[idx].id).is_some() {
+                        final_pos += Self::rubber_band(new_pos - self.constrain_drag_position(idx, new_pos, widget_size));
+                    }
                     
                     // Check for panel hover during drag
-                    self.drag_hover_panel = PanelManager::find_panel_under_position(&self.widgets, pos);
+                    let group = self.drag_group(idx);
+                    let group_ids: Vec<usize> = group.iter().map(|&i| self.widgets[i].id).collect();
+                    self.drag_hover_panel = self.drop_target(pos, &group_ids);
                     
-                    // Update widget position
-                    if let Some(widget) = self.widgets.get_mut(idx) {
+                    // Update widget position, carrying the rest of the multi-selection along
+                    if group.len() > 1 {
+                        self.move_group(&group, final_pos - self.widgets[idx].position);
+                    } else if matches!(self.widgets[idx].widget_type, WidgetType::Settings { minimized: false, .. }) {
+                        self.drag_settings_panel(idx, pos);
+                    } else if let Some(widget) = self.widgets.get_mut(idx) {
                         widget.position = final_pos;
                     }
                 }
             } else {
-                self.dragging_widget = None;
+                // A widget released past its panel's edge springs back (see advance_spring_back);
+                // otherwise a drag released while still moving fast keeps gliding (see advance_glide)
+                let resting = self.widgets.get(idx)
+                    .map(|w| (w.position, self.constrain_drag_position(idx, w.position, w.size)))
+                    .and_then(|(position, target)| (position != target).then_some(target));
+                if let Some(target) = resting {
+                    self.spring_back = Some((idx, target));
+                } else if self.settings.flick_inertia {
+                    let flick_threshold = 300.0; // points per second
+                    let velocity = ui.ctx().input(|i| i.pointer.velocity()) / self.view_transform().scaling;
+                    if velocity.length() > flick_threshold {
+                        self.glide = Some((idx, velocity));
+                    }
+                }
+                
+                // On the release frame the release handling below ends the drag, so it can
+                // still tell a click from a drag and update panel membership
+                if !mouse_released {
+                    self.dragging_widget = None;
+                }
                 self.alignment_guides.clear();
                 self.drag_hover_panel = None;
             }
         }
 
-        // Handle widget resizing
+        // Handle widget resizing, measured from where the resize started:
+        // Alt resizes about the center, Shift keeps the starting proportions
         if let Some(idx) = self.resizing_widget {
-            if mouse_held {
-                if let (Some(current_pos), Some(last_pos)) = (mouse_pos, self.last_mouse_pos) {
-                    let delta = current_pos - last_pos;
+            // Like the press hit-test, only a widget that draws a handle can be resized, so a
+            // panel collapsed mid-resize (e.g. by the host) ends the resize instead of stretching
+            let has_handle = self.widgets.get(idx).is_some_and(|w| w.render_rects().resize_handle.is_some());
+            if mouse_held && has_handle {
+                if let Some(current_pos) = mouse_pos {
+                    let (from_center, keep_aspect) = ui.ctx().input(|i| (i.modifiers.alt, i.modifiers.shift));
+                    let start = self.resize_start_size;
+                    let delta = current_pos - self.resize_start_pointer;
+                    // From the center both edges follow the pointer, so the size changes twice as fast
+                    let delta = if from_center { delta * 2.0 } else { delta };
                     
-                    if let Some(widget) = self.widgets.get_mut(idx) {
-                        match &mut widget.widget_type {
-                            WidgetType::Panel { width, height, .. } => {
-                                *width = (*width + delta.x).max(100.0).min(500.0);
-                                *height = (*height + delta.y).max(100.0).min(400.0);
-                                
-                                // Update widget size
-                                widget.size = Vec2::new(*width, *height);
+                    if let Some(widget) = self.widgets.get(idx) {
+                        let target = match &widget.widget_type {
+                            WidgetType::PushButton { .. } | WidgetType::IconButton { .. } => {
+                                // Aspect-locked: follow whichever axis moved more so the control stays square
+                                let dominant = if delta.x.abs() >= delta.y.abs() { delta.x } else { delta.y };
+                                Vec2::splat(start.x + dominant)
                             }
-                            WidgetType::StatusBar { .. } => {
-                                // Status bars can be resized in width and height
-                                let new_width = (widget.size.x + delta.x).max(200.0).min(800.0);
-                                let new_height = (widget.size.y + delta.y).max(40.0).min(120.0);
-                                
-                                // Update widget size
-                                widget.size = Vec2::new(new_width, new_height);
+                            widget_type if (keep_aspect || widget_type.scale_range().is_some()) && start.x > 0.0 && start.y > 0.0 => {
+                                // Scale by whichever axis grew more (scaled widgets always keep their proportions)
+                                start * ((start.x + delta.x) / start.x).max((start.y + delta.y) / start.y)
                             }
-                            _ => {}
-                        }
+                            _ => start + delta,
+                        };
+                        self.resize_widget(idx, target);
+                        
+                        // Re-centre on the starting rect (or go back to the start corner if Alt was let go)
+                        let size = self.widgets[idx].size;
+                        self.widgets[idx].position = if from_center {
+                            self.resize_start_position + (start - size) / 2.0
+                        } else {
+                            self.resize_start_position
+                        };
                     }
                     
                     self.last_mouse_pos = Some(current_pos);
@@ -996,26 +2208,54 @@ impl DragDropCanvas {
         if mouse_released {
             // Panel drag operations completed
             
-            // Check if widget was dragged out of any panel and remove it from containers
-            if let Some(drag_idx) = self.dragging_widget {
-                if let Some(widget) = self.widgets.get(drag_idx) {
-                    let widget_rect = widget.get_rect();
-                    let widget_id = widget.id;
-                    
-                    // Check if widget is still inside any panel it was originally in
-                    let mut should_remove_from_all = true;
-                    
-                    for panel in &self.widgets {
-                        if PanelManager::is_panel_accepting_widgets(panel) && panel.get_rect().contains(widget_rect.center()) {
-                            should_remove_from_all = false;
-                            break;
-                        }
-                    }
-                    
-                    // If widget is no longer inside any panel, remove it from all containers
-                    if should_remove_from_all {
+            // Check if the dragged widgets left their panels or were dropped into another one
+            let dragged: Vec<usize> = self.dragging_widget
+                .map(|idx| self.drag_group(idx).into_iter().map(|i| self.widgets[i].id).collect())
+                .unwrap_or_default();
+            let target = mouse_pos.and_then(|pos| self.drop_target(pos, &dragged));
+            let mut adopted = Vec::new();
+            for &widget_id in &dragged {
+                let Some(drag_idx) = self.widgets.iter().position(|w| w.id == widget_id) else {
+                    continue;
+                };
+                let widget = &self.widgets[drag_idx];
+                
+                // A widget springing back is judged by where it will come to rest
+                let center = match self.spring_back {
+                    Some((spring_idx, target)) if spring_idx == drag_idx => Rect::from_min_size(target, widget.size).center(),
+                    _ => widget.get_rect().center(),
+                };
+                let inside = |panel_id: usize| self.widgets.iter()
+                    .any(|panel| panel.id == panel_id && PanelManager::is_panel_accepting_widgets(panel) && panel.get_rect().contains(center));
+                
+                // Still inside its own panel: membership is unchanged
+                if self.container_of(widget_id).is_some_and(inside) {
+                    continue;
+                }
+                
+                // Dropped on another panel: it joins it, unless that would nest panels too deeply or
+                // inside themselves, in which case it is left on the canvas and outlined in red
+                if let Some(panel_id) = target.filter(|&panel_id| inside(panel_id)) {
+                    if self.may_adopt(widget_id, panel_id) {
+                        adopted.push((widget_id, panel_id));
+                    } else {
                         PanelManager::remove_widget_from_containers(&mut self.widgets, widget_id);
+                        self.rejected_drop = Some((widget_id, self.frame_time));
                     }
+                    continue;
+                }
+                
+                // If widget is no longer inside any panel, remove it from all containers
+                if !self.widgets.iter().any(|panel| PanelManager::is_panel_accepting_widgets(panel) && panel.get_rect().contains(center)) {
+                    PanelManager::remove_widget_from_containers(&mut self.widgets, widget_id);
+                }
+            }
+            
+            // Moving widgets into panels is undoable as one step
+            if !adopted.is_empty() {
+                self.push_undo();
+                for (widget_id, panel_id) in adopted {
+                    self.adopt_into_panel(widget_id, panel_id);
                 }
             }
             
@@ -1027,7 +2267,8 @@ impl DragDropCanvas {
                         let drag_distance = (widget.position - original_pos).length();
                         
                         // If the widget wasn't actually dragged (very small movement), treat it as a click
-                        if drag_distance < 5.0 {
+                        // (Alt+click only selects, it never presses buttons or flips toggles)
+                        if drag_distance < 5.0 && !alt_held {
                             match widget.widget_type {
                                 WidgetType::ToggleSwitch { .. } | 
                                 WidgetType::PushButton { .. } | 
@@ -1043,6 +2284,7 @@ impl DragDropCanvas {
             
             self.dragging_widget = None;
             self.interacting_widget = None;
+            self.region_handle = None;
             self.resizing_widget = None;
             self.last_mouse_pos = None;
         }
@@ -1082,46 +2324,61 @@ impl DragDropCanvas {
         
         // Handle all other widget types
         if let Some(widget) = self.widgets.get_mut(widget_idx) {
-            let rect = widget.get_rect();
-            match &mut widget.widget_type {
+            let geometry = widget.render_rects();
+            let control_rect = geometry.control.unwrap_or(geometry.rect);
+            let widget_id = widget.id;
+            let value_changed = match &mut widget.widget_type {
                 WidgetType::Knob { value, min, max, .. } => {
-                    let center = Pos2::new(rect.center().x, rect.top() + 37.0);
+                    let center = control_rect.center();
                     let mouse_vec = mouse_pos - center;
                     let angle = mouse_vec.y.atan2(mouse_vec.x);
                     let normalized_angle = (angle + 135.0 * PI / 180.0) / (270.0 * PI / 180.0);
                     *value = (normalized_angle.clamp(0.0, 1.0) * (*max - *min) + *min).clamp(*min, *max);
+                    true
                 }
                 WidgetType::ToggleSwitch { on, .. } => {
                     *on = !*on;
+                    true
                 }
-                WidgetType::PushButton { active, .. } => {
+                // Momentary buttons follow the pointer instead, see set_momentary()
+                WidgetType::PushButton { active, momentary: false, .. } => {
                     *active = !*active;
+                    true
                 }
-                WidgetType::IconButton { active, .. } => {
+                WidgetType::IconButton { active, momentary: false, .. } => {
                     *active = !*active;
+                    true
                 }
                 WidgetType::HorizontalSlider { value, min, max, .. } => {
-                    let slider_rect = Rect::from_center_size(
-                        Pos2::new(rect.center().x + 10.0, rect.center().y),
-                        Vec2::new(96.0, 8.0),
-                    );
+                    let slider_rect = control_rect;
                     if slider_rect.contains(mouse_pos) {
                         let normalized = ((mouse_pos.x - slider_rect.left()) / slider_rect.width()).clamp(0.0, 1.0);
                         *value = normalized * (*max - *min) + *min;
+                        true
+                    } else {
+                        false
                     }
                 }
                 WidgetType::VerticalSlider { value, min, max, .. } => {
-                    let slider_rect = Rect::from_center_size(
-                        Pos2::new(rect.center().x, rect.center().y - 10.0),
-                        Vec2::new(8.0, 96.0),
-                    );
+                    let slider_rect = control_rect;
                     if slider_rect.contains(mouse_pos) {
                         let normalized = 1.0 - ((mouse_pos.y - slider_rect.top()) / slider_rect.height()).clamp(0.0, 1.0);
                         *value = normalized * (*max - *min) + *min;
+                        true
+                    } else {
+                        false
                     }
                 }
                 WidgetType::StatusBar { online, .. } => {
                     *online = !*online;
+                    true
+                }
+                WidgetType::XyPad { x, y, .. } => {
+                    // Pointer outside the pad (mid-drag) pins the cursor to the nearest edge
+                    let pad_rect = control_rect;
+                    *x = ((mouse_pos.x - pad_rect.left()) / pad_rect.width()).clamp(0.0, 1.0);
+                    *y = (1.0 - (mouse_pos.y - pad_rect.top()) / pad_rect.height()).clamp(0.0, 1.0);
+                    true
                 }
                 WidgetType::Panel { collapsed, .. } => {
                     *collapsed = !*collapsed;
@@ -1142,82 +2399,612 @@ impl DragDropCanvas {
                     if let Some(widget) = self.widgets.get_mut(widget_idx) {
                         widget.size = new_size;
                     }
+                    false
+                }
+                _ => false, // Other widgets don't have direct interactions yet
+            };
+            
+            if value_changed {
+                self.changed_widgets.insert(widget_id);
+                CanvasEvent::queue_change(&mut self.events, &self.widgets[widget_idx]);
+                self.apply_exclusive_group(widget_idx);
+            }
+        }
+    }
+    
+    /// Hold a momentary button down or let it go, reporting the change like a click would
+    fn set_momentary(&mut self, widget_idx: usize, down: bool) {
+        let Some(widget) = self.widgets.get_mut(widget_idx) else {
+            return;
+        };
+        let Some(active) = widget.widget_type.switch_state_mut() else {
+            return;
+        };
+        if std::mem::replace(active, down) != down {
+            self.changed_widgets.insert(widget.id);
+            CanvasEvent::queue_change(&mut self.events, widget);
+            self.apply_exclusive_group(widget_idx);
+        }
+    }
+    
+    /// Switch off the rest of a widget's exclusive group once the widget is on
+    fn apply_exclusive_group(&mut self, widget_idx: usize) {
+        let Some(widget) = self.widgets.get(widget_idx) else {
+            return;
+        };
+        let (Some(group), Some(true)) = (widget.exclusive_group.clone(), widget.widget_type.switch_state()) else {
+            return;
+        };
+        let widget_id = widget.id;
+        
+        for other in &mut self.widgets {
+            if other.id == widget_id || other.exclusive_group.as_deref() != Some(group.as_str()) {
+                continue;
+            }
+            if let Some(state) = other.widget_type.switch_state_mut() {
+                if std::mem::take(state) {
+                    self.changed_widgets.insert(other.id);
+                    CanvasEvent::queue_change(&mut self.events, other);
                 }
-                _ => {} // Other widgets don't have direct interactions yet
             }
         }
     }
 
-    fn handle_knob_interaction(&mut self, widget_idx: usize, delta_y: f32) {
+    /// Turn a knob by a vertical drag; with `fine` (Shift held) it is 5x less sensitive
+    fn handle_knob_interaction(&mut self, widget_idx: usize, delta_y: f32, mouse_pos: Pos2, fine: bool) {
+        let distance_multiplier = self.knob_distance_multiplier(widget_idx, mouse_pos);
+        let fine_multiplier = if fine { FINE_ADJUST } else { 1.0 };
+        
         if let Some(widget) = self.widgets.get_mut(widget_idx) {
             if let WidgetType::Knob { value, min, max, .. } = &mut widget.widget_type {
-                let sensitivity = 0.5; // Adjust for desired sensitivity
+                let sensitivity = 0.5 * distance_multiplier * fine_multiplier; // Adjust for desired sensitivity
                 let range = *max - *min;
                 let delta_value = (delta_y * sensitivity / 100.0) * range;
                 *value = (*value + delta_value).clamp(*min, *max);
+                self.changed_widgets.insert(widget.id);
+                CanvasEvent::queue_change(&mut self.events, widget);
             }
         }
     }
-
-    fn calculate_alignment_guides(&mut self, dragging_idx: usize, position: Pos2, size: Vec2) {
-        self.alignment_guides.clear();
-        let threshold = 8.0; // Distance threshold for showing guides
+    
+    /// Step a rotary encoder once the vertical drag adds up to a whole step
+    ///
+    /// Uses the knob's sensitivity, so sweeping through every step takes as
+    /// much travel as turning a knob through its range. Past the first or last
+    /// step the value wraps around if `wrap` is set and stops otherwise.
+    fn handle_encoder_interaction(&mut self, widget_idx: usize, delta_y: f32, mouse_pos: Pos2) {
+        let distance_multiplier = self.knob_distance_multiplier(widget_idx, mouse_pos);
         
-        // Canvas center guides
-        let canvas_center_x = self.canvas_rect.center().x;
-        let canvas_center_y = self.canvas_rect.center().y;
-        let widget_center_x = position.x + size.x / 2.0;
-        let widget_center_y = position.y + size.y / 2.0;
+        if let Some(widget) = self.widgets.get_mut(widget_idx) {
+            if let WidgetType::RotaryEncoder { value, steps, wrap, .. } = &mut widget.widget_type {
+                let steps = (*steps).max(1);
+                let sensitivity = 0.5 * distance_multiplier;
+                self.encoder_travel += (delta_y * sensitivity / 100.0) * steps as f32;
+                
+                let before = *value;
+                while self.encoder_travel.abs() >= 1.0 {
+                    let up = self.encoder_travel > 0.0;
+                    self.encoder_travel -= self.encoder_travel.signum();
+                    *value = match (up, *wrap) {
+                        (true, true) => (*value + 1) % steps,
+                        (false, true) => (*value + steps - 1) % steps,
+                        (true, false) => (*value + 1).min(steps - 1),
+                        (false, false) => value.saturating_sub(1),
+                    };
+                }
+                if *value != before {
+                    self.changed_widgets.insert(widget.id);
+                    CanvasEvent::queue_change(&mut self.events, widget);
+                }
+            }
+        }
+    }
+    
+    /// Snap the knob or slider under `pos` back to its default value
+    ///
+    /// Only the dial of a knob and the track of a slider count, so a
+    /// double-click on the label or readout does nothing.
+    fn reset_value_at(&mut self, pos: Pos2) {
+        let Some(idx) = self.widget_at(pos) else {
+            return;
+        };
+        let widget = &mut self.widgets[idx];
+        let Some(control) = widget.render_rects().control else {
+            return;
+        };
+        let on_control = match widget.widget_type {
+            WidgetType::Knob { .. } => (pos - control.center()).length() <= control.width() / 2.0,
+            _ => control.contains(pos),
+        };
+        let Some(default) = widget.widget_type.default_value().filter(|_| on_control) else {
+            return;
+        };
+        if widget.widget_type.value_mut().is_some_and(|value| std::mem::replace(value, default) != default) {
+            self.changed_widgets.insert(widget.id);
+            CanvasEvent::queue_change(&mut self.events, widget);
+        }
+    }
+    
+    /// Move one handle of a region selector to the pointer, keeping `start <= end`
+    fn handle_region_interaction(&mut self, widget_idx: usize, handle: RegionHandle, mouse_pos: Pos2) {
+        if let Some(widget) = self.widgets.get_mut(widget_idx) {
+            let track_rect = widget.render_rects().control.unwrap_or(widget.get_rect());
+            if let WidgetType::RegionSelector { start, end, duration, .. } = &mut widget.widget_type {
+                let normalized = ((mouse_pos.x - track_rect.left()) / track_rect.width()).clamp(0.0, 1.0);
+                let time = normalized * *duration;
+                match handle {
+                    RegionHandle::Start => *start = time.min(*end),
+                    RegionHandle::End => *end = time.max(*start),
+                }
+                self.changed_widgets.insert(widget.id);
+                CanvasEvent::queue_change(&mut self.events, widget);
+            }
+        }
+    }
+    
+    /// Keyboard shortcuts for the canvas and the selected widget
+    ///
+    /// Ctrl/Cmd+Z undoes, Delete/Backspace delete the selection (see
+    /// delete_widget()), Ctrl/Cmd+D duplicates the selected widget, Ctrl/Cmd+C
+    /// and +V copy and paste (see paste()), the keys in `spawn_keys` spawn
+    /// widgets at the cursor and '?' toggles the shortcut overlay. The arrow
+    /// keys nudge the selection by 1px, 10px with Shift (see nudge_selected()). For knobs
+    /// and sliders, following OS slider conventions: Home sets the value to
+    /// `min`, End to `max`, and PageUp/PageDown step by 10% of the range. Keys
+    /// are left alone while another egui widget (e.g. a text field) has
+    /// keyboard focus. In live mode undo, delete, duplicate, paste and
+    /// spawn hotkeys are ignored, and the layout locks also stop nudging and restacking.
+    fn handle_keyboard(&mut self, ui: &mut Ui) {
+        if ui.ctx().wants_keyboard_input() {
+            return;
+        }
+        if !self.is_live() && ui.ctx().input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z)) {
+            self.undo();
+            return;
+        }
+        if !self.is_live() && ui.ctx().input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Delete) || i.consume_key(egui::Modifiers::NONE, egui::Key::Backspace)) {
+            let targets = match self.selected_ids() {
+                ids if ids.is_empty() => self.editing_widget.into_iter().collect(),
+                ids => ids,
+            };
+            self.delete_widgets(&targets);
+            return;
+        }
+        if !self.is_live() && ui.ctx().input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::D)) {
+            // The widget being edited, else the single selected widget
+            let target = self.editing_widget
+                .or(self.selected_widget.filter(|_| self.selected_widgets.len() <= 1))
+                .and_then(|id| self.widgets.iter().position(|w| w.id == id));
+            if let Some(idx) = target {
+                self.duplicate_widget(idx);
+            }
+            return;
+        }
         
-        // Check horizontal center alignment with canvas
-        if (widget_center_x - canvas_center_x).abs() < threshold {
-            self.alignment_guides.push(AlignmentGuide {
-                start: Pos2::new(canvas_center_x, self.canvas_rect.min.y),
-                end: Pos2::new(canvas_center_x, self.canvas_rect.max.y),
-                guide_type: AlignmentType::CenterHorizontal,
+        // Stacking order: [ and ] step past the next overlapping widget, with Ctrl/Cmd all the way
+        if !self.layout_locked() {
+            let restack_key = ui.ctx().input_mut(|i| {
+                [
+                    (egui::Modifiers::COMMAND, egui::Key::CloseBracket),
+                    (egui::Modifiers::COMMAND, egui::Key::OpenBracket),
+                    (egui::Modifiers::NONE, egui::Key::CloseBracket),
+                    (egui::Modifiers::NONE, egui::Key::OpenBracket),
+                ]
+                .into_iter()
+                .find(|&(modifiers, key)| i.consume_key(modifiers, key))
             });
+            let target = self.editing_widget
+                .or(self.selected_widget.filter(|_| self.selected_widgets.len() <= 1))
+                .and_then(|id| self.widgets.iter().position(|w| w.id == id));
+            if let (Some((modifiers, key)), Some(idx)) = (restack_key, target) {
+                match (modifiers.command, key) {
+                    (true, egui::Key::CloseBracket) => self.bring_to_front(idx),
+                    (true, _) => self.send_to_back(idx),
+                    (false, egui::Key::CloseBracket) => self.raise(idx),
+                    (false, _) => self.lower(idx),
+                };
+                return;
+            }
         }
         
-        // Check vertical center alignment with canvas
-        if (widget_center_y - canvas_center_y).abs() < threshold {
-            self.alignment_guides.push(AlignmentGuide {
-                start: Pos2::new(self.canvas_rect.min.x, canvas_center_y),
-                end: Pos2::new(self.canvas_rect.max.x, canvas_center_y),
-                guide_type: AlignmentType::CenterVertical,
+        // Arrow keys nudge the selection a pixel at a time, ten with Shift
+        if !self.layout_locked() && !self.selected_ids().is_empty() {
+            let nudge = ui.ctx().input_mut(|i| {
+                [
+                    (egui::Key::ArrowLeft, Vec2::new(-1.0, 0.0)),
+                    (egui::Key::ArrowRight, Vec2::new(1.0, 0.0)),
+                    (egui::Key::ArrowUp, Vec2::new(0.0, -1.0)),
+                    (egui::Key::ArrowDown, Vec2::new(0.0, 1.0)),
+                ]
+                .into_iter()
+                .find_map(|(key, step)| {
+                    if i.consume_key(egui::Modifiers::SHIFT, key) {
+                        Some(step * 10.0)
+                    } else {
+                        i.consume_key(egui::Modifiers::NONE, key).then_some(step)
+                    }
+                })
             });
+            if let Some(delta) = nudge {
+                self.nudge_selected(delta);
+                return;
+            }
         }
         
-        // Check alignment with other widgets
-        for (idx, other_widget) in self.widgets.iter().enumerate() {
-            if idx == dragging_idx {
-                continue;
+        // Ctrl+C / Ctrl+V arrive as clipboard events; Shift+V pastes in place
+        let (copy, paste, in_place) = ui.ctx().input(|i| {
+            let copy = i.events.iter().any(|e| matches!(e, egui::Event::Copy));
+            let paste = i.events.iter().find_map(|e| match e {
+                egui::Event::Paste(text) => Some(text.clone()),
+                _ => None,
+            });
+            (copy, paste, i.modifiers.shift)
+        });
+        if copy && self.copy_widget() {
+            if let Some(json) = self.copy_selected() {
+                ui.ctx().copy_text(json);
             }
-            
-            let other_center_x = other_widget.position.x + other_widget.size.x / 2.0;
-            let other_center_y = other_widget.position.y + other_widget.size.y / 2.0;
-            
-            // Horizontal alignment with other widgets
-            if (widget_center_x - other_center_x).abs() < threshold {
-                self.alignment_guides.push(AlignmentGuide {
-                    start: Pos2::new(other_center_x, other_widget.position.y.min(position.y) - 20.0),
-                    end: Pos2::new(other_center_x, (other_widget.position.y + other_widget.size.y).max(position.y + size.y) + 20.0),
-                    guide_type: AlignmentType::WidgetAlignHorizontal,
-                });
+        }
+        if let Some(json) = paste.filter(|_| !self.is_live()) {
+            // System clipboard text that isn't copied widgets (e.g. copied from another
+            // app since) falls back to the canvas's own clipboard
+            let cursor = ui.ctx().pointer_hover_pos().map(|pos| self.screen_to_canvas(pos)).filter(|pos| self.visible_canvas_rect().contains(*pos));
+            let at = if in_place { None } else { cursor };
+            if !self.paste(&json, at) {
+                self.paste_widget(at);
             }
-            
-            // Vertical alignment with other widgets
-            if (widget_center_y - other_center_y).abs() < threshold {
-                self.alignment_guides.push(AlignmentGuide {
-                    start: Pos2::new(other_widget.position.x.min(position.x) - 20.0, other_center_y),
-                    end: Pos2::new((other_widget.position.x + other_widget.size.x).max(position.x + size.x) + 20.0, other_center_y),
-                    guide_type: AlignmentType::WidgetAlignVertical,
-                });
+            return;
+        }
+        
+        // Plain typed characters: spawn hotkeys and the overlay toggle
+        let typed = ui.ctx().input(|i| {
+            if i.modifiers.command || i.modifiers.alt {
+                return None;
             }
-            
-            // Edge alignments (left, right, top, bottom)
-            // Left edge alignment
-            if (position.x - other_widget.position.x).abs() < threshold {
-                self.alignment_guides.push(AlignmentGuide {
+            i.events.iter().find_map(|e| match e {
+                egui::Event::Text(text) if text.chars().count() == 1 => text.chars().next(),
+                _ => None,
+            })
+        });
+        if typed == Some('?') {
+            self.show_shortcuts = !self.show_shortcuts;
+            return;
+        }
+        let spawn_kind = typed.filter(|_| !self.is_live()).and_then(|c| {
+            self.settings.spawn_keys.iter().find(|(key, _)| key.eq_ignore_ascii_case(&c)).map(|&(_, kind)| kind)
+        });
+        if let Some(kind) = spawn_kind {
+            let cursor = ui.ctx().pointer_hover_pos().map(|pos| self.screen_to_canvas(pos)).filter(|pos| self.visible_canvas_rect().contains(*pos));
+            self.spawn_at_cursor(kind.widget_type(self.settings.default_accent), cursor);
+            return;
+        }
+        let Some(idx) = self.selected_widget.and_then(|id| self.widgets.iter().position(|w| w.id == id)) else {
+            return;
+        };
+        if !matches!(
+            self.widgets[idx].widget_type,
+            WidgetType::Knob { .. } | WidgetType::HorizontalSlider { .. } | WidgetType::VerticalSlider { .. }
+        ) {
+            return;
+        }
+        
+        let pressed_key = ui.ctx().input_mut(|i| {
+            [egui::Key::Home, egui::Key::End, egui::Key::PageUp, egui::Key::PageDown]
+                .into_iter()
+                .find(|&key| i.consume_key(egui::Modifiers::NONE, key))
+        });
+        
+        if let Some(key) = pressed_key {
+            self.apply_value_key(idx, key);
+        }
+    }
+    
+    fn apply_value_key(&mut self, widget_idx: usize, key: egui::Key) {
+        let page_step = 0.1; // Fraction of the range per PageUp/PageDown
+        
+        if let Some(widget) = self.widgets.get_mut(widget_idx) {
+            match &mut widget.widget_type {
+                WidgetType::Knob { value, min, max, .. } |
+                WidgetType::HorizontalSlider { value, min, max, .. } |
+                WidgetType::VerticalSlider { value, min, max, .. } => {
+                    let new_value = match key {
+                        egui::Key::Home => *min,
+                        egui::Key::End => *max,
+                        egui::Key::PageUp => *value + page_step * (*max - *min),
+                        egui::Key::PageDown => *value - page_step * (*max - *min),
+                        _ => return,
+                    };
+                    *value = new_value.clamp(*min, *max);
+                    self.changed_widgets.insert(widget.id);
+                    CanvasEvent::queue_change(&mut self.events, widget);
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    /// Select the next widget down the stack under `pos`, Illustrator-style
+    ///
+    /// The first Alt+click at a spot selects the widget just below the topmost
+    /// one (a plain click already reaches the topmost); each further Alt+click
+    /// at the same spot goes one level deeper, wrapping back to the top. The
+    /// selected widget is picked up for dragging. Returns false if there is no
+    /// widget under `pos`.
+    fn cycle_selection_at(&mut self, pos: Pos2) -> bool {
+        let stack: Vec<usize> = self.render_order()
+            .into_iter()
+            .rev()
+            .filter(|&idx| self.widgets[idx].get_rect().contains(pos) && !self.is_widget_in_minimized_panel(self.widgets[idx].id))
+            .collect();
+        if stack.is_empty() {
+            self.selection_cycle = None;
+            return false;
+        }
+        
+        let depth = match self.selection_cycle {
+            Some((last_pos, last_depth)) if (last_pos - pos).length() < 4.0 => (last_depth + 1) % stack.len(),
+            _ => 1 % stack.len(),
+        };
+        self.selection_cycle = Some((pos, depth));
+        
+        let idx = stack[depth];
+        let widget = &self.widgets[idx];
+        let locked = self.layout_locked() || widget.locked;
+        self.selected_widget = Some(widget.id);
+        self.selected_panel = match widget.widget_type {
+            WidgetType::Panel { .. } | WidgetType::Settings { .. } => Some(widget.id),
+            _ => PanelManager::find_widget_container_panel_id(&self.widgets, widget.id),
+        };
+        
+        if !locked {
+            self.dragging_widget = Some(idx);
+            self.drag_offset = pos - widget.position;
+        }
+        true
+    }
+    
+    /// Scrub a knob or slider value with high precision from a right-drag delta
+    ///
+    /// Horizontal sliders follow horizontal movement; knobs and vertical
+    /// sliders follow vertical movement (up increases). Holding Shift
+    /// (`fine`) scales it down another 5x.
+    fn handle_fine_tune(&mut self, widget_idx: usize, delta: Vec2, fine: bool) {
+        let fine_sensitivity = 0.001; // Fraction of the range per point, 5x finer than knob dragging
+        let delta = if fine { delta * FINE_ADJUST } else { delta };
+        
+        if let Some(widget) = self.widgets.get_mut(widget_idx) {
+            let movement = match widget.widget_type {
+                WidgetType::HorizontalSlider { .. } => delta.x,
+                _ => -delta.y,
+            };
+            
+            match &mut widget.widget_type {
+                WidgetType::Knob { value, min, max, .. } |
+                WidgetType::HorizontalSlider { value, min, max, .. } |
+                WidgetType::VerticalSlider { value, min, max, .. } => {
+                    *value = (*value + movement * fine_sensitivity * (*max - *min)).clamp(*min, *max);
+                    self.changed_widgets.insert(widget.id);
+                    CanvasEvent::queue_change(&mut self.events, widget);
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    /// Sensitivity multiplier from the pointer's distance to the knob center
+    ///
+    /// With `distance_sensitivity` enabled, dragging close to the knob gives
+    /// fine control and dragging far away gives coarse control; the pointer is
+    /// at 1x when it is `distance_sensitivity_radius` points from the center.
+    fn knob_distance_multiplier(&self, widget_idx: usize, mouse_pos: Pos2) -> f32 {
+        if !self.settings.distance_sensitivity {
+            return 1.0;
+        }
+        let Some(widget) = self.widgets.get(widget_idx) else {
+            return 1.0;
+        };
+        
+        let knob_center = widget.render_rects().control.unwrap_or(widget.get_rect()).center();
+        let distance = (mouse_pos - knob_center).length();
+        (distance / self.settings.distance_sensitivity_radius.max(1.0)).clamp(0.1, 4.0)
+    }
+    
+    /// Route mouse wheel input over the canvas
+    ///
+    /// Precedence, highest first:
+    /// 1. Ctrl/Cmd+wheel (or a trackpad pinch): zooms the view around the pointer.
+    /// 2. Pointer over a knob or slider: the wheel adjusts its value, in
+    ///    10x finer steps with Shift held.
+    /// 3. Pointer anywhere else on the canvas (empty space, panels, other
+    ///    widgets): the wheel pans the view.
+    /// 4. Pointer outside the canvas: the wheel is left to the surrounding UI.
+    ///
+    /// Dragging with the middle button also pans. In cases 1-3 the scroll
+    /// delta is consumed, so an enclosing `ScrollArea` does not scroll at the same time.
+    fn handle_scroll(&mut self, ui: &mut Ui) {
+        let (scroll_delta, zoom_delta, hover_pos, fine) = ui.ctx().input(|i| {
+            (i.smooth_scroll_delta, i.zoom_delta(), i.pointer.hover_pos(), i.modifiers.shift)
+        });
+        // Skip the press frame, whose delta is the jump from wherever the pointer last was
+        let middle_dragging = ui.ctx().input(|i| {
+            i.pointer.middle_down() && !i.pointer.button_pressed(egui::PointerButton::Middle)
+        });
+        let pointer_delta = ui.ctx().input(|i| i.pointer.delta());
+        let hover_pos = hover_pos.map(|pos| self.screen_to_canvas(pos));
+        let Some(pos) = hover_pos else {
+            return;
+        };
+        if !self.visible_canvas_rect().contains(pos) {
+            return;
+        }
+        
+        if zoom_delta != 1.0 {
+            self.zoom_at(pos, zoom_delta);
+            return;
+        }
+        if middle_dragging && pointer_delta != Vec2::ZERO {
+            // Screen pixels -> canvas units, so the content follows the pointer at any zoom
+            self.pan_view(pointer_delta / self.view_transform().scaling);
+        }
+        if scroll_delta == Vec2::ZERO {
+            return;
+        }
+        
+        let value_widget = self.widget_at(pos)
+            .filter(|&idx| matches!(
+                self.widgets[idx].widget_type,
+                WidgetType::Knob { .. } | WidgetType::HorizontalSlider { .. } | WidgetType::VerticalSlider { .. }
+            ));
+        
+        if let Some(idx) = value_widget {
+            // egui turns Shift+wheel into horizontal scrolling, so the notches arrive on x then
+            let scroll_y = if fine { scroll_delta.x + scroll_delta.y } else { scroll_delta.y };
+            self.adjust_value_by_scroll(idx, scroll_y, fine);
+        } else {
+            self.pan_view(scroll_delta);
+        }
+        
+        ui.ctx().input_mut(|i| {
+            i.smooth_scroll_delta = Vec2::ZERO;
+            i.raw_scroll_delta = Vec2::ZERO;
+        });
+    }
+    
+    fn adjust_value_by_scroll(&mut self, widget_idx: usize, scroll_y: f32, fine: bool) {
+        let points_per_notch = 40.0; // egui's scroll distance for one wheel line
+        let step_per_notch = if fine { 0.002 } else { 0.02 }; // Fraction of the range per notch
+        
+        if let Some(widget) = self.widgets.get_mut(widget_idx) {
+            match &mut widget.widget_type {
+                WidgetType::Knob { value, min, max, .. } |
+                WidgetType::HorizontalSlider { value, min, max, .. } |
+                WidgetType::VerticalSlider { value, min, max, .. } => {
+                    let delta_value = scroll_y / points_per_notch * step_per_notch * (*max - *min);
+                    *value = (*value + delta_value).clamp(*min, *max);
+                    self.changed_widgets.insert(widget.id);
+                    CanvasEvent::queue_change(&mut self.events, widget);
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    /// Scroll the view by moving every widget, without scrolling past the content
+    fn pan_view(&mut self, delta: Vec2) {
+        let content = self.widgets.iter()
+            .filter(|w| !self.is_widget_in_minimized_panel(w.id))
+            .fold(Rect::NOTHING, |bounds, w| bounds.union(w.get_rect()));
+        if content == Rect::NOTHING {
+            return;
+        }
+        
+        // Only allow scrolling towards content that is outside the visible area
+        let view = self.visible_canvas_rect().shrink(self.settings.canvas_margin);
+        let delta = Vec2::new(
+            delta.x.clamp((view.max.x - content.max.x).min(0.0), (view.min.x - content.min.x).max(0.0)),
+            delta.y.clamp((view.max.y - content.max.y).min(0.0), (view.min.y - content.min.y).max(0.0)),
+        );
+        if delta == Vec2::ZERO {
+            return;
+        }
+        
+        for widget in &mut self.widgets {
+            widget.position += delta;
+        }
+        self.pan_offset += delta;
+    }
+
+    /// Content area of the panel holding the dragged widget, None for a widget on the canvas
+    ///
+    /// Inset by PANEL_MARGIN at the sides and bottom and starting under the
+    /// title bar, matching where widgets spawned into a panel go.
+    fn snap_content_rect(&self, dragging_idx: usize) -> Option<Rect> {
+        let panel_id = self.container_of(self.widgets.get(dragging_idx)?.id)?;
+        let panel_rect = self.widgets.iter().find(|w| w.id == panel_id)?.get_rect();
+        Some(Rect::from_min_max(
+            Pos2::new(panel_rect.left() + PANEL_MARGIN, panel_rect.top() + PANEL_TITLE_HEIGHT),
+            Pos2::new(panel_rect.right() - PANEL_MARGIN, panel_rect.bottom() - PANEL_MARGIN),
+        ))
+    }
+    
+    fn calculate_alignment_guides(&mut self, dragging_idx: usize, position: Pos2, size: Vec2) {
+        self.alignment_guides.clear();
+        let threshold = 8.0; // Distance threshold for showing guides
+        
+        // Center guides: the canvas center, or the panel's for a widget inside a panel
+        let content_rect = self.snap_content_rect(dragging_idx);
+        let center_area = content_rect.unwrap_or(self.canvas_rect);
+        let area_center_x = center_area.center().x;
+        let area_center_y = center_area.center().y;
+        let widget_center_x = position.x + size.x / 2.0;
+        let widget_center_y = position.y + size.y / 2.0;
+        
+        // Check horizontal center alignment
+        if (widget_center_x - area_center_x).abs() < threshold {
+            self.alignment_guides.push(AlignmentGuide {
+                start: Pos2::new(area_center_x, center_area.min.y),
+                end: Pos2::new(area_center_x, center_area.max.y),
+                guide_type: AlignmentType::CenterHorizontal,
+            });
+        }
+        
+        // Check vertical center alignment
+        if (widget_center_y - area_center_y).abs() < threshold {
+            self.alignment_guides.push(AlignmentGuide {
+                start: Pos2::new(center_area.min.x, area_center_y),
+                end: Pos2::new(center_area.max.x, area_center_y),
+                guide_type: AlignmentType::CenterVertical,
+            });
+        }
+        
+        // Content edges of the containing panel: left, right and just under the title bar
+        if let Some(content) = content_rect {
+            for (edge, x) in [(position.x, content.left()), (position.x + size.x, content.right())] {
+                if (edge - x).abs() < threshold {
+                    self.alignment_guides.push(AlignmentGuide {
+                        start: Pos2::new(x, content.top()),
+                        end: Pos2::new(x, content.bottom()),
+                        guide_type: AlignmentType::PanelEdgeHorizontal,
+                    });
+                }
+            }
+            if (position.y - content.top()).abs() < threshold {
+                self.alignment_guides.push(AlignmentGuide {
+                    start: Pos2::new(content.left(), content.top()),
+                    end: Pos2::new(content.right(), content.top()),
+                    guide_type: AlignmentType::PanelEdgeVertical,
+                });
+            }
+        }
+        
+        // Check alignment with other widgets in the same container
+        for idx in self.snap_targets(dragging_idx) {
+            let other_widget = &self.widgets[idx];
+            let other_center_x = other_widget.position.x + other_widget.size.x / 2.0;
+            let other_center_y = other_widget.position.y + other_widget.size.y / 2.0;
+            
+            // Horizontal alignment with other widgets
+            if (widget_center_x - other_center_x).abs() < threshold {
+                self.alignment_guides.push(AlignmentGuide {
+                    start: Pos2::new(other_center_x, other_widget.position.y.min(position.y) - 20.0),
+                    end: Pos2::new(other_center_x, (other_widget.position.y + other_widget.size.y).max(position.y + size.y) + 20.0),
+                    guide_type: AlignmentType::WidgetAlignHorizontal,
+                });
+            }
+            
+            // Vertical alignment with other widgets
+            if (widget_center_y - other_center_y).abs() < threshold {
+                self.alignment_guides.push(AlignmentGuide {
+                    start: Pos2::new(other_widget.position.x.min(position.x) - 20.0, other_center_y),
+                    end: Pos2::new((other_widget.position.x + other_widget.size.x).max(position.x + size.x) + 20.0, other_center_y),
+                    guide_type: AlignmentType::WidgetAlignVertical,
+                });
+            }
+            
+            // Edge alignments (left, right, top, bottom)
+            // Left edge alignment
+            if (position.x - other_widget.position.x).abs() < threshold {
+                self.alignment_guides.push(AlignmentGuide {
                     start: Pos2::new(other_widget.position.x, other_widget.position.y.min(position.y) - 20.0),
                     end: Pos2::new(other_widget.position.x, (other_widget.position.y + other_widget.size.y).max(position.y + size.y) + 20.0),
                     guide_type: AlignmentType::WidgetAlignHorizontal,
@@ -1259,20 +3046,32 @@ impl DragDropCanvas {
         let mut final_pos = position;
         let snap_threshold = 8.0;
         
-        // Snap to canvas center
-        if (position.x + size.x / 2.0 - self.canvas_rect.center().x).abs() < snap_threshold {
-            final_pos.x = self.canvas_rect.center().x - size.x / 2.0;
+        // Snap to the canvas center, or the panel's for a widget inside a panel
+        let content_rect = self.snap_content_rect(dragging_idx);
+        let center = content_rect.unwrap_or(self.canvas_rect).center();
+        if (position.x + size.x / 2.0 - center.x).abs() < snap_threshold {
+            final_pos.x = center.x - size.x / 2.0;
         }
-        if (position.y + size.y / 2.0 - self.canvas_rect.center().y).abs() < snap_threshold {
-            final_pos.y = self.canvas_rect.center().y - size.y / 2.0;
+        if (position.y + size.y / 2.0 - center.y).abs() < snap_threshold {
+            final_pos.y = center.y - size.y / 2.0;
         }
         
-        // Snap to other widgets
-        for (idx, other_widget) in self.widgets.iter().enumerate() {
-            if idx == dragging_idx {
-                continue;
+        // Snap against the panel's content edges
+        if let Some(content) = content_rect {
+            if (position.x - content.left()).abs() < snap_threshold {
+                final_pos.x = content.left();
             }
-            
+            if (position.x + size.x - content.right()).abs() < snap_threshold {
+                final_pos.x = content.right() - size.x;
+            }
+            if (position.y - content.top()).abs() < snap_threshold {
+                final_pos.y = content.top();
+            }
+        }
+        
+        // Snap to other widgets in the same container
+        for idx in self.snap_targets(dragging_idx) {
+            let other_widget = &self.widgets[idx];
             let other_center_x = other_widget.position.x + other_widget.size.x / 2.0;
             let other_center_y = other_widget.position.y + other_widget.size.y / 2.0;
             
@@ -1322,6 +3121,8 @@ impl DragDropCanvas {
                 }
             }
         }
+        
+        self.pan_offset = Vec2::ZERO;
     }
     
     fn calculate_grid_position(&self, grid_index: usize, widget_type: &WidgetType) -> Pos2 {
@@ -1335,20 +3136,22 @@ impl DragDropCanvas {
         };
         
         // Define the actual usable canvas area with simple margins
-        let usable_start_x = canvas_rect.min.x + CANVAS_MARGIN;
-        let usable_start_y = canvas_rect.min.y + CANVAS_MARGIN;
-        let usable_end_x = (canvas_rect.max.x - CANVAS_MARGIN).max(usable_start_x + 100.0);
-        let usable_end_y = (canvas_rect.max.y - CANVAS_MARGIN).max(usable_start_y + 100.0);
+        let margin = self.settings.canvas_margin;
+        let usable_start_x = canvas_rect.min.x + margin;
+        let usable_start_y = canvas_rect.min.y + margin;
+        let usable_end_x = (canvas_rect.max.x - margin).max(usable_start_x + 100.0);
+        let usable_end_y = (canvas_rect.max.y - margin).max(usable_start_y + 100.0);
         
         // Grid layout with minimum spacing
         let available_width = usable_end_x - usable_start_x;
-        let widgets_per_row = (available_width / GRID_SPACING).max(1.0) as usize;
+        let grid_size = self.settings.grid_size;
+        let widgets_per_row = (available_width / grid_size).max(1.0) as usize;
         
         let row = grid_index / widgets_per_row;
         let col = grid_index % widgets_per_row;
         
-        let x = usable_start_x + col as f32 * GRID_SPACING;
-        let y = usable_start_y + row as f32 * GRID_SPACING;
+        let x = usable_start_x + col as f32 * grid_size;
+        let y = usable_start_y + row as f32 * grid_size;
         
         // Ensure position keeps widget fully within usable bounds
         let max_x = (usable_end_x - widget_size.x).max(usable_start_x);
@@ -1372,15 +3175,11 @@ impl DragDropCanvas {
         // Check if widget is directly in a minimized/collapsed panel
         for widget in &self.widgets {
             match &widget.widget_type {
-                WidgetType::Panel { collapsed, contained_widgets, .. } => {
-                    if *collapsed && contained_widgets.contains(&widget_id) {
-                        return true;
-                    }
+                WidgetType::Panel { collapsed, contained_widgets, .. } if *collapsed && contained_widgets.contains(&widget_id) => {
+                    return true;
                 }
-                WidgetType::Settings { minimized, contained_widgets, .. } => {
-                    if *minimized && contained_widgets.contains(&widget_id) {
-                        return true;
-                    }
+                WidgetType::Settings { minimized, contained_widgets, .. } if *minimized && contained_widgets.contains(&widget_id) => {
+                    return true;
                 }
                 _ => {}
             }
@@ -1395,13 +3194,51 @@ impl DragDropCanvas {
         
         false
     }
+    
+    /// IDs of every widget inside a panel, including those in nested panels
+    ///
+    /// Depth-first in container order; each descendant appears once. Returns an
+    /// empty list if `panel_id` is not a panel or settings container.
+    pub fn contained_widgets_recursive(&self, panel_id: usize) -> Vec<usize> {
+        let mut descendants = Vec::new();
+        self.contained_widgets_recursive_inner(panel_id, &mut descendants, &mut std::collections::HashSet::new());
+        descendants
+    }
+    
+    fn contained_widgets_recursive_inner(&self, panel_id: usize, descendants: &mut Vec<usize>, visited: &mut std::collections::HashSet<usize>) {
+        // Prevent infinite recursion
+        if visited.contains(&panel_id) {
+            return;
+        }
+        visited.insert(panel_id);
+        
+        let contained = match self.widgets.iter().find(|w| w.id == panel_id).map(|w| &w.widget_type) {
+            Some(WidgetType::Panel { contained_widgets, .. }) | Some(WidgetType::Settings { contained_widgets, .. }) => contained_widgets,
+            _ => return,
+        };
+        
+        for &child_id in contained {
+            if !visited.contains(&child_id) && !descendants.contains(&child_id) {
+                descendants.push(child_id);
+                self.contained_widgets_recursive_inner(child_id, descendants, visited);
+            }
+        }
+    }
 
     pub fn show_widget_palette(&mut self, ui: &mut Ui) {
+        // New widgets take the canvas accent color; existing widgets keep their own
+        let accent = self.settings.default_accent;
+        
         ui.group(|ui| {
             ui.set_min_width(200.0);
             ui.label(RichText::new("Widget Palette").size(16.0).color(WHITE));
             ui.label(RichText::new(format!("Ev2 v{}", APP_VERSION)).size(10.0).color(GRAY_400));
             ui.separator();
+            
+            if self.is_live() {
+                ui.colored_label(YELLOW, "🔒 Live mode: editing is off");
+                ui.disable();
+            }
 
             ui.vertical(|ui| {
                 // Instructions
@@ -1421,7 +3258,8 @@ impl DragDropCanvas {
                         min: 0.0,
                         max: 100.0,
                         label: "KNOB".to_string(),
-                        color: WidgetColor::Cyan,
+                        color: accent,
+                        default_value: None,
                     });
                 }
                 
@@ -1432,7 +3270,31 @@ impl DragDropCanvas {
                         min: 0.0,
                         max: 100.0,
                         label: "KNOB".to_string(),
-                        color: WidgetColor::Cyan,
+                        color: accent,
+                        default_value: None,
+                    });
+                }
+
+                // Rotary Encoder
+                let encoder_btn = ui.button("🔄 Encoder");
+                if encoder_btn.clicked() {
+                    self.spawn_widget_directly(WidgetType::RotaryEncoder {
+                        value: 0,
+                        steps: 4,
+                        labels: ["OFF", "LOW", "MID", "HIGH"].map(String::from).to_vec(),
+                        wrap: false,
+                        color: accent,
+                    });
+                }
+                
+                // Check for drag start on encoder button
+                if encoder_btn.drag_started() {
+                    self.palette_dragging = Some(WidgetType::RotaryEncoder {
+                        value: 0,
+                        steps: 4,
+                        labels: ["OFF", "LOW", "MID", "HIGH"].map(String::from).to_vec(),
+                        wrap: false,
+                        color: accent,
                     });
                 }
 
@@ -1442,7 +3304,7 @@ impl DragDropCanvas {
                     self.spawn_widget_directly(WidgetType::ToggleSwitch {
                         on: false,
                         label: "TOGGLE".to_string(),
-                        color: WidgetColor::Cyan,
+                        color: accent,
                         glow: true,
                     });
                 }
@@ -1452,7 +3314,7 @@ impl DragDropCanvas {
                     self.palette_dragging = Some(WidgetType::ToggleSwitch {
                         on: false,
                         label: "TOGGLE".to_string(),
-                        color: WidgetColor::Cyan,
+                        color: accent,
                         glow: true,
                     });
                 }
@@ -1464,8 +3326,9 @@ impl DragDropCanvas {
                         active: false,
                         icon: "▶".to_string(),
                         label: "PLAY".to_string(),
-                        color: WidgetColor::Green,
+                        color: accent,
                         size: 48.0,
+                        momentary: false,
                     });
                 }
                 
@@ -1475,8 +3338,9 @@ impl DragDropCanvas {
                         active: false,
                         icon: "▶".to_string(),
                         label: "PLAY".to_string(),
-                        color: WidgetColor::Green,
+                        color: accent,
                         size: 48.0,
+                        momentary: false,
                     });
                 }
 
@@ -1487,7 +3351,7 @@ impl DragDropCanvas {
                         level: 75.0,
                         peak_level: 80.0,
                         label: "VU".to_string(),
-                        color: WidgetColor::Green,
+                        color: accent,
                     });
                 }
                 
@@ -1497,7 +3361,7 @@ impl DragDropCanvas {
                         level: 75.0,
                         peak_level: 80.0,
                         label: "VU".to_string(),
-                        color: WidgetColor::Green,
+                        color: accent,
                     });
                 }
 
@@ -1509,7 +3373,8 @@ impl DragDropCanvas {
                         min: 0.0,
                         max: 100.0,
                         label: "LEVEL".to_string(),
-                        color: WidgetColor::Yellow,
+                        color: accent,
+                        default_value: None,
                     });
                 }
                 
@@ -1520,7 +3385,8 @@ impl DragDropCanvas {
                         min: 0.0,
                         max: 100.0,
                         label: "LEVEL".to_string(),
-                        color: WidgetColor::Yellow,
+                        color: accent,
+                        default_value: None,
                     });
                 }
 
@@ -1532,7 +3398,8 @@ impl DragDropCanvas {
                         min: 0.0,
                         max: 100.0,
                         label: "CH1".to_string(),
-                        color: WidgetColor::Pink,
+                        color: accent,
+                        default_value: None,
                     });
                 }
                 
@@ -1543,7 +3410,8 @@ impl DragDropCanvas {
                         min: 0.0,
                         max: 100.0,
                         label: "CH1".to_string(),
-                        color: WidgetColor::Pink,
+                        color: accent,
+                        default_value: None,
                     });
                 }
 
@@ -1566,13 +3434,139 @@ impl DragDropCanvas {
                     });
                 }
 
+                // Readout
+                let readout_btn = ui.button("🔢 Readout");
+                if readout_btn.clicked() {
+                    self.spawn_widget_directly(WidgetType::Readout {
+                        value: -6.0,
+                        unit: "dB".to_string(),
+                        decimals: 1,
+                        color: accent,
+                        label: "PEAK".to_string(),
+                    });
+                }
+                
+                // Check for drag start on readout button
+                if readout_btn.drag_started() {
+                    self.palette_dragging = Some(WidgetType::Readout {
+                        value: -6.0,
+                        unit: "dB".to_string(),
+                        decimals: 1,
+                        color: accent,
+                        label: "PEAK".to_string(),
+                    });
+                }
+
+                // Meter with dB readout
+                let db_meter_btn = ui.button("📶 dB Meter");
+                if db_meter_btn.clicked() {
+                    self.spawn_widget_directly(WidgetType::MeterWithReadout {
+                        level: -18.0,
+                        peak: -12.0,
+                        unit: "dB".to_string(),
+                        color: accent,
+                    });
+                }
+                
+                // Check for drag start on dB meter button
+                if db_meter_btn.drag_started() {
+                    self.palette_dragging = Some(WidgetType::MeterWithReadout {
+                        level: -18.0,
+                        peak: -12.0,
+                        unit: "dB".to_string(),
+                        color: accent,
+                    });
+                }
+
+                // Spectrum Analyzer
+                let spectrum_btn = ui.button("📈 Spectrum");
+                if spectrum_btn.clicked() {
+                    self.spawn_widget_directly(WidgetType::SpectrumAnalyzer {
+                        bands: vec![0.0; 32],
+                        color: accent,
+                        peaks: Vec::new(),
+                    });
+                }
+                
+                // Check for drag start on spectrum button
+                if spectrum_btn.drag_started() {
+                    self.palette_dragging = Some(WidgetType::SpectrumAnalyzer {
+                        bands: vec![0.0; 32],
+                        color: accent,
+                        peaks: Vec::new(),
+                    });
+                }
+
+                // Time Display
+                let time_btn = ui.button("⏱ Time");
+                if time_btn.clicked() {
+                    self.spawn_widget_directly(WidgetType::TimeDisplay {
+                        seconds: 0.0,
+                        mode: TimeMode::HhMmSs,
+                        color: accent,
+                    });
+                }
+                
+                // Check for drag start on time button
+                if time_btn.drag_started() {
+                    self.palette_dragging = Some(WidgetType::TimeDisplay {
+                        seconds: 0.0,
+                        mode: TimeMode::HhMmSs,
+                        color: accent,
+                    });
+                }
+
+                // Region Selector
+                let region_btn = ui.button("🔁 Region");
+                if region_btn.clicked() {
+                    self.spawn_widget_directly(WidgetType::RegionSelector {
+                        start: 4.0,
+                        end: 12.0,
+                        duration: 16.0,
+                        color: accent,
+                    });
+                }
+                
+                // Check for drag start on region button
+                if region_btn.drag_started() {
+                    self.palette_dragging = Some(WidgetType::RegionSelector {
+                        start: 4.0,
+                        end: 12.0,
+                        duration: 16.0,
+                        color: accent,
+                    });
+                }
+
+                // XY Pad
+                let xy_pad_btn = ui.button("🎯 XY Pad");
+                if xy_pad_btn.clicked() {
+                    self.spawn_widget_directly(WidgetType::XyPad {
+                        x: 0.5,
+                        y: 0.5,
+                        x_label: "X".to_string(),
+                        y_label: "Y".to_string(),
+                        color: accent,
+                    });
+                }
+                
+                // Check for drag start on XY pad button
+                if xy_pad_btn.drag_started() {
+                    self.palette_dragging = Some(WidgetType::XyPad {
+                        x: 0.5,
+                        y: 0.5,
+                        x_label: "X".to_string(),
+                        y_label: "Y".to_string(),
+                        color: accent,
+                    });
+                }
+
                 // Text Label
                 let label_btn = ui.button("🏷️ Label");
                 if label_btn.clicked() {
                     self.spawn_widget_directly(WidgetType::TextLabel {
                         text: "LABEL".to_string(),
                         size: 16.0,
-                        color: WidgetColor::Cyan,
+                        color: accent,
                     });
                 }
                 
@@ -1581,7 +3575,7 @@ impl DragDropCanvas {
                     self.palette_dragging = Some(WidgetType::TextLabel {
                         text: "LABEL".to_string(),
                         size: 16.0,
-                        color: WidgetColor::Cyan,
+                        color: accent,
                     });
                 }
 
@@ -1590,12 +3584,13 @@ impl DragDropCanvas {
                 if panel_btn.clicked() {
                     self.spawn_widget_directly(WidgetType::Panel {
                         title: "CONTROL PANEL".to_string(),
-                        color: WidgetColor::Cyan,
+                        color: accent,
                         width: 200.0,
                         height: 150.0,
                         collapsed: false,
                         contained_widgets: Vec::new(),
                         minimize_to_settings_icon: true,
+                        grid_layout: false,
                     });
                 }
                 
@@ -1603,12 +3598,13 @@ impl DragDropCanvas {
                 if panel_btn.drag_started() {
                     self.palette_dragging = Some(WidgetType::Panel {
                         title: "CONTROL PANEL".to_string(),
-                        color: WidgetColor::Cyan,
+                        color: accent,
                         width: 200.0,
                         height: 150.0,
                         collapsed: false,
                         contained_widgets: Vec::new(),
                         minimize_to_settings_icon: true,
+                        grid_layout: false,
                     });
                 }
                 
@@ -1620,6 +3616,7 @@ impl DragDropCanvas {
                         ram: 1.2,
                         latency: 2.3,
                         online: true,
+                        precision: StatusPrecision::default(),
                     });
                 }
                 
@@ -1630,6 +3627,7 @@ impl DragDropCanvas {
                         ram: 1.2,
                         latency: 2.3,
                         online: true,
+                        precision: StatusPrecision::default(),
                     });
                 }
                 
@@ -1638,9 +3636,10 @@ impl DragDropCanvas {
                 if settings_btn.clicked() {
                     self.spawn_widget_directly(WidgetType::Settings {
                         label: "SETTINGS".to_string(),
-                        color: WidgetColor::Cyan,
+                        color: accent,
                         minimized: false,
                         contained_widgets: Vec::new(),
+                        edge: CanvasEdge::None,
                     });
                 }
                 
@@ -1648,9 +3647,10 @@ impl DragDropCanvas {
                 if settings_btn.drag_started() {
                     self.palette_dragging = Some(WidgetType::Settings {
                         label: "SETTINGS".to_string(),
-                        color: WidgetColor::Cyan,
+                        color: accent,
                         minimized: false,
                         contained_widgets: Vec::new(),
+                        edge: CanvasEdge::None,
                     });
                 }
                 
@@ -1665,8 +3665,9 @@ impl DragDropCanvas {
                             icon: IconType::Power,
                             label: "POWER".to_string(),
                             active: false,
-                            color: WidgetColor::Green,
+                            color: accent,
                             size: 48.0,
+                            momentary: false,
                         });
                     }
                     
@@ -1676,8 +3677,9 @@ impl DragDropCanvas {
                             icon: IconType::Power,
                             label: "POWER".to_string(),
                             active: false,
-                            color: WidgetColor::Green,
+                            color: accent,
                             size: 48.0,
+                            momentary: false,
                         });
                     }
                     
@@ -1687,8 +3689,9 @@ impl DragDropCanvas {
                             icon: IconType::Play,
                             label: "PLAY".to_string(),
                             active: false,
-                            color: WidgetColor::Cyan,
+                            color: accent,
                             size: 48.0,
+                            momentary: false,
                         });
                     }
                     
@@ -1698,8 +3701,9 @@ impl DragDropCanvas {
                             icon: IconType::Play,
                             label: "PLAY".to_string(),
                             active: false,
-                            color: WidgetColor::Cyan,
+                            color: accent,
                             size: 48.0,
+                            momentary: false,
                         });
                     }
                     
@@ -1709,8 +3713,9 @@ impl DragDropCanvas {
                             icon: IconType::Pause,
                             label: "PAUSE".to_string(),
                             active: false,
-                            color: WidgetColor::Cyan,
+                            color: accent,
                             size: 48.0,
+                            momentary: false,
                         });
                     }
                     
@@ -1720,8 +3725,9 @@ impl DragDropCanvas {
                             icon: IconType::Pause,
                             label: "PAUSE".to_string(),
                             active: false,
-                            color: WidgetColor::Cyan,
+                            color: accent,
                             size: 48.0,
+                            momentary: false,
                         });
                     }
                     
@@ -1731,8 +3737,9 @@ impl DragDropCanvas {
                             icon: IconType::Settings,
                             label: "CONFIG".to_string(),
                             active: false,
-                            color: WidgetColor::Yellow,
+                            color: accent,
                             size: 48.0,
+                            momentary: false,
                         });
                     }
                     
@@ -1742,8 +3749,9 @@ impl DragDropCanvas {
                             icon: IconType::Settings,
                             label: "CONFIG".to_string(),
                             active: false,
-                            color: WidgetColor::Yellow,
+                            color: accent,
                             size: 48.0,
+                            momentary: false,
                         });
                     }
                     
@@ -1753,8 +3761,9 @@ impl DragDropCanvas {
                             icon: IconType::Mic,
                             label: "MIC".to_string(),
                             active: false,
-                            color: WidgetColor::Pink,
+                            color: accent,
                             size: 40.0,
+                            momentary: false,
                         });
                     }
                     
@@ -1764,8 +3773,9 @@ impl DragDropCanvas {
                             icon: IconType::Mic,
                             label: "MIC".to_string(),
                             active: false,
-                            color: WidgetColor::Pink,
+                            color: accent,
                             size: 40.0,
+                            momentary: false,
                         });
                     }
                     
@@ -1775,8 +3785,9 @@ impl DragDropCanvas {
                             icon: IconType::Mute,
                             label: "MUTE".to_string(),
                             active: false,
-                            color: WidgetColor::Red,
+                            color: accent,
                             size: 40.0,
+                            momentary: false,
                         });
                     }
                     
@@ -1786,8 +3797,9 @@ impl DragDropCanvas {
                             icon: IconType::Mute,
                             label: "MUTE".to_string(),
                             active: false,
-                            color: WidgetColor::Red,
+                            color: accent,
                             size: 40.0,
+                            momentary: false,
                         });
                     }
                 });
@@ -1799,14 +3811,45 @@ impl DragDropCanvas {
             ui.label(RichText::new("Canvas Management").size(14.0).color(YELLOW));
             
             ui.horizontal(|ui| {
-                if ui.button("💾 Save Layout").clicked() {
-                    self.save_layout();
+                if ui.button("📋 Copy Layout").on_hover_text("Copy the layout as JSON to the clipboard").clicked() {
+                    match self.save_layout() {
+                        Ok(json) => ui.ctx().copy_text(json),
+                        Err(err) => log::warn!("Layout could not be serialized: {}", err),
+                    }
+                }
+                if ui.add_enabled(!self.widgets.is_empty(), egui::Button::new("🗑️ Clear Canvas")).clicked() {
+                    self.confirm_clear = true;
+                }
+            });
+            
+            if ui.button("📷 Export PNG").on_hover_text("Render the canvas to a PNG image at the screen's pixel density").clicked() {
+                match self.export_png_scaled(ui.ctx(), ui.ctx().pixels_per_point()) {
+                    Ok(png) => self.exported_png = Some(png),
+                    Err(err) => log::warn!("Canvas could not be exported: {}", err),
+                }
+            }
+            
+            ui.horizontal(|ui| {
+                if ui.button("↺ Reset Values").on_hover_text("Return every control to its neutral value").clicked() {
+                    self.reset_all_values();
                 }
-                if ui.button("🗑️ Clear Canvas").clicked() {
-                    self.clear_canvas();
+                if ui.add_enabled(!self.undo_stack.is_empty(), egui::Button::new("↶ Undo")).clicked() {
+                    self.undo();
                 }
             });
             
+            if ui.button("🧩 De-overlap").clicked() {
+                self.resolve_overlaps();
+            }
+            
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.settings.snap_to_grid, "Snap to grid");
+                ui.add_enabled(
+                    self.settings.snap_to_grid,
+                    egui::DragValue::new(&mut self.settings.snap_grid_size).range(5.0..=200.0).suffix(" px"),
+                );
+            });
+            
             ui.separator();
             
             ui.separator();
@@ -1816,108 +3859,263 @@ impl DragDropCanvas {
             
             ui.separator();
             ui.label("Click to add widgets");
-            ui.label("Right-click to edit");
+            ui.label("Right-click for options");
             
         });
     }
 
+    /// Right-click menu for one widget, anchored where the click happened
+    ///
+    /// Any press outside the menu, or Escape, closes it without acting on the
+    /// canvas underneath. Closes by itself if the widget goes away.
+    fn show_context_menu(&mut self, ui: &mut Ui) {
+        let Some((widget_id, pos)) = self.context_menu else {
+            return;
+        };
+        let Some(idx) = self.widgets.iter().position(|w| w.id == widget_id) else {
+            self.context_menu = None;
+            return;
+        };
+        let locked = self.widgets[idx].locked;
+        let mut action = None;
+        
+        let menu = egui::Area::new(egui::Id::new("widget_context_menu"))
+            .kind(egui::UiKind::Menu)
+            .order(egui::Order::Foreground)
+            .fixed_pos(self.canvas_to_screen(pos))
+            .constrain(true)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::menu(ui.style()).show(ui, |ui| {
+                    ui.set_min_width(140.0);
+                    // Full-width entries, so the whole row is clickable
+                    ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
+                        let entries = [
+                            ("✏ Edit…", ContextAction::Edit),
+                            ("📋 Duplicate", ContextAction::Duplicate),
+                            ("🗑 Delete", ContextAction::Delete),
+                            ("⏫ Bring to Front", ContextAction::BringToFront),
+                            ("⏬ Send to Back", ContextAction::SendToBack),
+                            (if locked { "🔓 Unlock" } else { "🔒 Lock" }, ContextAction::ToggleLock),
+                        ];
+                        for (text, entry) in entries {
+                            if matches!(entry, ContextAction::BringToFront | ContextAction::ToggleLock) {
+                                ui.separator();
+                            }
+                            if ui.add(egui::Button::new(text).frame(false)).clicked() {
+                                action = Some(entry);
+                            }
+                        }
+                    });
+                });
+            });
+        
+        let dismissed = ui.ctx().input(|i| {
+            i.key_pressed(egui::Key::Escape)
+                || (i.pointer.any_pressed() && !i.pointer.interact_pos().is_some_and(|p| menu.response.rect.contains(p)))
+        });
+        if dismissed || action.is_some() {
+            self.context_menu = None;
+        }
+        
+        match action {
+            Some(ContextAction::Edit) => {
+                self.editing_widget = Some(widget_id);
+                self.show_edit_window = true;
+            }
+            Some(ContextAction::Duplicate) => {
+                self.duplicate_widget(idx);
+            }
+            Some(ContextAction::Delete) => {
+                self.delete_widget(widget_id);
+            }
+            Some(ContextAction::BringToFront) => {
+                self.bring_to_front(idx);
+            }
+            Some(ContextAction::SendToBack) => {
+                self.send_to_back(idx);
+            }
+            Some(ContextAction::ToggleLock) => {
+                self.push_undo();
+                self.widgets[idx].locked = !locked;
+            }
+            None => {}
+        }
+    }
+    
     fn show_edit_window(&mut self, ui: &mut Ui) {
-        if let Some(idx) = self.editing_widget {
+        // Resolved by ID every frame: if the widget was removed while the window was open,
+        // the window closes instead of editing whatever now sits at the old index
+        let editing_idx = self.editing_widget.and_then(|id| self.widgets.iter().position(|w| w.id == id));
+        if let Some(idx) = editing_idx {
             let mut open = self.show_edit_window;
             let mut delete_widget = false;
+            let mut match_size_of: Option<usize> = None;
+            let mut arrange_radius: Option<f32> = None;
+            let mut restack: Option<fn(&mut Self, usize) -> bool> = None;
+            
+            // Geometry is edited on copies (position relative to the canvas origin) and applied
+            // after the window so it can be clamped like a drag or resize
+            let origin = self.canvas_origin();
+            let (original_pos, original_size) = match self.widgets.get(idx) {
+                // Panels edit their expanded size, even while collapsed
+                Some(widget @ DraggableWidget { widget_type: WidgetType::Panel { width, height, .. }, .. }) => {
+                    (widget.position - origin.to_vec2(), Vec2::new(*width, *height))
+                }
+                Some(widget) => (widget.position - origin.to_vec2(), widget.size),
+                None => (Pos2::ZERO, Vec2::ZERO),
+            };
+            let (mut edit_pos, mut edit_size) = (original_pos, original_size);
+            
+            // Other multi-selected widgets of the same type get the color, label and range changes too
+            let batch: Vec<usize> = match self.editing_widget {
+                Some(editing_id) if self.selected_widgets.contains(&editing_id) => self.selected_widgets.iter()
+                    .filter_map(|&id| self.widgets.iter().position(|w| w.id == id))
+                    .filter(|&other| other != idx && std::mem::discriminant(&self.widgets[other].widget_type) == std::mem::discriminant(&self.widgets[idx].widget_type))
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let before = self.widgets[idx].widget_type.clone();
+            let mixed: Vec<&str> = [
+                ("color", batch.iter().any(|&other| self.widgets[other].widget_type.color() != before.color())),
+                ("label", batch.iter().any(|&other| self.widgets[other].widget_type.label() != before.label())),
+                ("range", batch.iter().any(|&other| self.widgets[other].widget_type.range() != before.range())),
+            ].into_iter().filter(|(_, differs)| *differs).map(|(name, _)| name).collect();
+            let title = if batch.is_empty() {
+                "Edit Widget".to_string()
+            } else {
+                format!("Edit Widget ({} widgets)", batch.len() + 1)
+            };
+            
+            // Other widgets that can serve as a size reference
+            let size_references: Vec<(usize, String)> = self.widgets.iter()
+                .enumerate()
+                .filter(|(other_idx, _)| *other_idx != idx)
+                .map(|(_, w)| (w.id, format!("#{} {} ({:.0}×{:.0})", w.id, w.widget_type.type_name(), w.size.x, w.size.y)))
+                .collect();
+            
+            // Names used by other widgets, which this one can't take
+            let taken_names: Vec<String> = self.widgets.iter()
+                .enumerate()
+                .filter(|(other_idx, _)| *other_idx != idx)
+                .filter_map(|(_, w)| w.name.clone())
+                .collect();
             
             if let Some(widget) = self.widgets.get_mut(idx) {
-                egui::Window::new("Edit Widget")
+                egui::Window::new(title)
+                    .id(egui::Id::new("edit_widget_window"))
                     .open(&mut open)
                     .show(ui.ctx(), |ui| {
-                        match &mut widget.widget_type {
-                            WidgetType::Knob { value, min, max, label, color } => {
-                                ui.label("Knob Properties:");
-                                ui.add(egui::Slider::new(value, *min..=*max).text("Value"));
-                                ui.add(egui::Slider::new(min, 0.0..=100.0).text("Min"));
-                                ui.add(egui::Slider::new(max, 0.0..=200.0).text("Max"));
-                                ui.text_edit_singleline(label);
-                                ui.horizontal(|ui| {
-                                    ui.label("Color:");
-                                    if ui.radio_value(color, WidgetColor::Cyan, "Cyan").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Pink, "Pink").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Green, "Green").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Yellow, "Yellow").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Red, "Red").clicked() {}
-                                });
+                        // Typed into a buffer, so a name can pass through a taken one on the way
+                        // to a free one; only free names (or none) reach the widget
+                        let name_id = egui::Id::new(("widget_name", widget.id));
+                        let mut name = ui.data_mut(|d| d.get_temp_mut_or_insert_with(name_id, || widget.name.clone().unwrap_or_default()).clone());
+                        let taken = taken_names.iter().any(|other| *other == name.trim());
+                        ui.horizontal(|ui| {
+                            ui.label("Name:");
+                            if ui.text_edit_singleline(&mut name).on_hover_text("Unique name for scripts and the host, e.g. master_volume").changed() {
+                                ui.data_mut(|d| d.insert_temp(name_id, name.clone()));
                             }
-                            WidgetType::ToggleSwitch { on, label, color, glow } => {
-                                ui.label("Toggle Switch Properties:");
+                        });
+                        if taken {
+                            ui.colored_label(RED, format!("⚠ Another widget is already named \"{}\"", name.trim()));
+                        } else {
+                            widget.name = Some(name.trim().to_string()).filter(|name| !name.is_empty());
+                        }
+                        ui.horizontal(|ui| {
+                            ui.label("X:");
+                            ui.add(egui::DragValue::new(&mut edit_pos.x).speed(1.0));
+                            ui.label("Y:");
+                            ui.add(egui::DragValue::new(&mut edit_pos.y).speed(1.0));
+                        });
+                        if widget.widget_type.aspect_lock_label_height().is_some() {
+                            ui.horizontal(|ui| {
+                                ui.label("Size:");
+                                ui.add(egui::DragValue::new(&mut edit_size.x).speed(1.0));
+                            });
+                        } else if let Some((min_scale, max_scale)) = widget.widget_type.scale_range() {
+                            let natural = DraggableWidget::calculate_size(&widget.widget_type);
+                            let mut percent = edit_size.x / natural.x * 100.0;
+                            ui.horizontal(|ui| {
+                                ui.label("Scale:");
+                                let range = min_scale * 100.0..=max_scale * 100.0;
+                                if ui.add(egui::DragValue::new(&mut percent).range(range).speed(1.0).suffix("%")).changed() {
+                                    edit_size = natural * percent / 100.0;
+                                }
+                            });
+                        } else if widget.widget_type.is_resizable() && !matches!(widget.widget_type, WidgetType::TextLabel { .. }) {
+                            // Text labels are sized by their font size, under Text Label Properties
+                            ui.horizontal(|ui| {
+                                ui.label("W:");
+                                ui.add(egui::DragValue::new(&mut edit_size.x).speed(1.0));
+                                ui.label("H:");
+                                ui.add(egui::DragValue::new(&mut edit_size.y).speed(1.0));
+                            });
+                        }
+                        ui.separator();
+                        
+                        egui::ComboBox::from_label("Match size of…")
+                            .selected_text("Choose widget")
+                            .show_ui(ui, |ui| {
+                                for (reference_id, name) in &size_references {
+                                    if ui.selectable_label(false, name).clicked() {
+                                        match_size_of = Some(*reference_id);
+                                    }
+                                }
+                            });
+                        ui.separator();
+                        
+                        if !mixed.is_empty() {
+                            // Mixed fields show this widget's setting; changing one sets it on all of them
+                            ui.label(egui::RichText::new(format!("Mixed {} (showing #{})", mixed.join(", "), widget.id)).italics().weak());
+                        }
+                        
+                        match &mut widget.widget_type {
+                            WidgetType::Knob { value, min, max, label, color, default_value } => {
+                                ui.label("Knob Properties:");
+                                ui.add(egui::Slider::new(value, *min..=*max).text("Value"));
+                                Self::edit_range(ui, value, min, max, default_value);
+                                Self::edit_default_value(ui, default_value, *min, *max);
+                                ui.text_edit_singleline(label);
+                                Self::edit_color(ui, color);
+                            }
+                            WidgetType::ToggleSwitch { on, label, color, glow } => {
+                                ui.label("Toggle Switch Properties:");
                                 ui.checkbox(on, "Current State");
                                 ui.checkbox(glow, "Glow Effect");
                                 ui.text_edit_singleline(label);
-                                ui.horizontal(|ui| {
-                                    ui.label("Color:");
-                                    if ui.radio_value(color, WidgetColor::Cyan, "Cyan").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Pink, "Pink").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Green, "Green").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Yellow, "Yellow").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Red, "Red").clicked() {}
-                                });
+                                Self::edit_color(ui, color);
                             }
-                            WidgetType::PushButton { active, icon, label, color, size } => {
+                            WidgetType::PushButton { active, icon, label, color, size, momentary } => {
                                 ui.label("Push Button Properties:");
                                 ui.checkbox(active, "Active State");
+                                ui.checkbox(momentary, "Momentary (active only while held)");
                                 ui.text_edit_singleline(icon);
                                 ui.text_edit_singleline(label);
                                 ui.add(egui::Slider::new(size, 20.0..=100.0).text("Size"));
-                                ui.horizontal(|ui| {
-                                    ui.label("Color:");
-                                    if ui.radio_value(color, WidgetColor::Cyan, "Cyan").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Pink, "Pink").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Green, "Green").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Yellow, "Yellow").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Red, "Red").clicked() {}
-                                });
+                                Self::edit_color(ui, color);
                             }
                             WidgetType::VuMeter { level, peak_level, label, color } => {
                                 ui.label("VU Meter Properties:");
                                 ui.add(egui::Slider::new(level, 0.0..=100.0).text("Level"));
                                 ui.add(egui::Slider::new(peak_level, 0.0..=100.0).text("Peak Level"));
                                 ui.text_edit_singleline(label);
-                                ui.horizontal(|ui| {
-                                    ui.label("Color:");
-                                    if ui.radio_value(color, WidgetColor::Cyan, "Cyan").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Pink, "Pink").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Green, "Green").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Yellow, "Yellow").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Red, "Red").clicked() {}
-                                });
+                                Self::edit_color(ui, color);
                             }
-                            WidgetType::HorizontalSlider { value, min, max, label, color } => {
+                            WidgetType::HorizontalSlider { value, min, max, label, color, default_value } => {
                                 ui.label("Horizontal Slider Properties:");
                                 ui.add(egui::Slider::new(value, *min..=*max).text("Value"));
-                                ui.add(egui::Slider::new(min, 0.0..=100.0).text("Min"));
-                                ui.add(egui::Slider::new(max, 0.0..=200.0).text("Max"));
+                                Self::edit_range(ui, value, min, max, default_value);
+                                Self::edit_default_value(ui, default_value, *min, *max);
                                 ui.text_edit_singleline(label);
-                                ui.horizontal(|ui| {
-                                    ui.label("Color:");
-                                    if ui.radio_value(color, WidgetColor::Cyan, "Cyan").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Pink, "Pink").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Green, "Green").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Yellow, "Yellow").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Red, "Red").clicked() {}
-                                });
+                                Self::edit_color(ui, color);
                             }
-                            WidgetType::VerticalSlider { value, min, max, label, color } => {
+                            WidgetType::VerticalSlider { value, min, max, label, color, default_value } => {
                                 ui.label("Vertical Slider Properties:");
                                 ui.add(egui::Slider::new(value, *min..=*max).text("Value"));
-                                ui.add(egui::Slider::new(min, 0.0..=100.0).text("Min"));
-                                ui.add(egui::Slider::new(max, 0.0..=200.0).text("Max"));
+                                Self::edit_range(ui, value, min, max, default_value);
+                                Self::edit_default_value(ui, default_value, *min, *max);
                                 ui.text_edit_singleline(label);
-                                ui.horizontal(|ui| {
-                                    ui.label("Color:");
-                                    if ui.radio_value(color, WidgetColor::Cyan, "Cyan").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Pink, "Pink").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Green, "Green").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Yellow, "Yellow").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Red, "Red").clicked() {}
-                                });
+                                Self::edit_color(ui, color);
                             }
                             WidgetType::LevelIndicator { level, segments, label } => {
                                 ui.label("Level Indicator Properties:");
@@ -1929,41 +4127,44 @@ impl DragDropCanvas {
                                 ui.label("Text Label Properties:");
                                 ui.text_edit_singleline(text);
                                 ui.add(egui::Slider::new(size, 8.0..=32.0).text("Font Size"));
-                                ui.horizontal(|ui| {
-                                    ui.label("Color:");
-                                    if ui.radio_value(color, WidgetColor::Cyan, "Cyan").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Pink, "Pink").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Green, "Green").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Yellow, "Yellow").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Red, "Red").clicked() {}
-                                });
+                                Self::edit_color(ui, color);
                             }
-                            WidgetType::Panel { title, color, width, height, contained_widgets, minimize_to_settings_icon, .. } => {
+                            WidgetType::Panel { title, color, width, height, contained_widgets, minimize_to_settings_icon, grid_layout, .. } => {
                                 ui.label("Panel Properties:");
                                 ui.text_edit_singleline(title);
                                 ui.add(egui::Slider::new(width, 100.0..=400.0).text("Width"));
                                 ui.add(egui::Slider::new(height, 100.0..=300.0).text("Height"));
                                 ui.checkbox(minimize_to_settings_icon, "Minimize to ⚙");
+                                ui.checkbox(grid_layout, "Grid layout (snap contents to cells)");
                                 ui.label(format!("Contains {} widgets", contained_widgets.len()));
                                 ui.horizontal(|ui| {
-                                    ui.label("Color:");
-                                    if ui.radio_value(color, WidgetColor::Cyan, "Cyan").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Pink, "Pink").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Green, "Green").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Yellow, "Yellow").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Red, "Red").clicked() {}
+                                    let radius_id = ui.id().with("arrange_radius");
+                                    let mut radius = ui.data_mut(|d| *d.get_temp_mut_or(radius_id, 120.0_f32));
+                                    ui.add(egui::DragValue::new(&mut radius).range(20.0..=400.0).prefix("Radius "));
+                                    ui.data_mut(|d| d.insert_temp(radius_id, radius));
+                                    if ui.add_enabled(contained_widgets.len() >= 2, egui::Button::new("◯ Arrange in circle")).clicked() {
+                                        arrange_radius = Some(radius);
+                                    }
                                 });
+                                Self::edit_color(ui, color);
                             }
-                            WidgetType::StatusBar { cpu, ram, latency, online } => {
+                            WidgetType::StatusBar { cpu, ram, latency, online, precision } => {
                                 ui.label("Status Bar Properties:");
                                 ui.add(egui::Slider::new(cpu, 0.0..=100.0).text("CPU %"));
                                 ui.add(egui::Slider::new(ram, 0.0..=8.0).text("RAM (GB)"));
                                 ui.add(egui::Slider::new(latency, 0.0..=100.0).text("Latency (ms)"));
                                 ui.checkbox(online, "System Online");
+                                ui.horizontal(|ui| {
+                                    ui.label("Decimals:");
+                                    ui.add(egui::DragValue::new(&mut precision.cpu).range(0..=3).prefix("CPU "));
+                                    ui.add(egui::DragValue::new(&mut precision.ram).range(0..=3).prefix("RAM "));
+                                    ui.add(egui::DragValue::new(&mut precision.latency).range(0..=3).prefix("Latency "));
+                                });
                             }
-                            WidgetType::IconButton { icon, label, active, color, size } => {
+                            WidgetType::IconButton { icon, label, active, color, size, momentary } => {
                                 ui.label("Icon Button Properties:");
                                 ui.checkbox(active, "Active State");
+                                ui.checkbox(momentary, "Momentary (active only while held)");
                                 ui.text_edit_singleline(label);
                                 ui.add(egui::Slider::new(size, 20.0..=80.0).text("Size"));
                                 
@@ -1979,30 +4180,206 @@ impl DragDropCanvas {
                                     ui.selectable_value(icon, IconType::Mute, "Mute");
                                 });
                                 
-                                ui.horizontal(|ui| {
-                                    ui.label("Color:");
-                                    if ui.radio_value(color, WidgetColor::Cyan, "Cyan").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Pink, "Pink").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Green, "Green").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Yellow, "Yellow").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Red, "Red").clicked() {}
-                                });
+                                Self::edit_color(ui, color);
                             }
                             WidgetType::Settings { label, color, minimized, .. } => {
                                 ui.label("Settings Properties:");
                                 ui.text_edit_singleline(label);
                                 ui.checkbox(minimized, "Minimized");
+                                Self::edit_color(ui, color);
+                            }
+                            WidgetType::Readout { value, unit, decimals, color, label } => {
+                                ui.label("Readout Properties:");
+                                ui.horizontal(|ui| {
+                                    ui.label("Value:");
+                                    ui.add(egui::DragValue::new(value).speed(0.1));
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label("Unit:");
+                                    ui.text_edit_singleline(unit);
+                                });
+                                ui.add(egui::Slider::new(decimals, 0..=4).text("Decimals"));
+                                ui.text_edit_singleline(label);
+                                Self::edit_color(ui, color);
+                            }
+                            WidgetType::MeterWithReadout { level, peak, unit, color } => {
+                                ui.label("dB Meter Properties:");
+                                ui.horizontal(|ui| {
+                                    ui.label("Level:");
+                                    ui.add(egui::DragValue::new(level).speed(0.1).range(METER_FLOOR_DB..=12.0));
+                                    ui.label("Peak:");
+                                    ui.add(egui::DragValue::new(peak).speed(0.1).range(METER_FLOOR_DB..=12.0));
+                                    if ui.button("Reset peak").clicked() {
+                                        *peak = *level;
+                                    }
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label("Unit:");
+                                    ui.text_edit_singleline(unit);
+                                });
+                                Self::edit_color(ui, color);
+                            }
+                            WidgetType::TimeDisplay { seconds, mode, color } => {
+                                ui.label("Time Display Properties:");
+                                ui.horizontal(|ui| {
+                                    ui.label("Seconds:");
+                                    ui.add(egui::DragValue::new(seconds).speed(0.1).range(0.0..=f32::MAX));
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label("Mode:");
+                                    let bars_beats = matches!(mode, TimeMode::BarsBeats { .. });
+                                    if ui.radio(!bars_beats, "HH:MM:SS").clicked() {
+                                        *mode = TimeMode::HhMmSs;
+                                    }
+                                    if ui.radio(bars_beats, "Bars/Beats").clicked() && !bars_beats {
+                                        *mode = TimeMode::BarsBeats { bpm: 120.0 };
+                                    }
+                                });
+                                if let TimeMode::BarsBeats { bpm } = mode {
+                                    ui.add(egui::Slider::new(bpm, 20.0..=300.0).text("BPM"));
+                                }
+                                Self::edit_color(ui, color);
+                            }
+                            WidgetType::RegionSelector { start, end, duration, color } => {
+                                ui.label("Region Properties:");
+                                ui.horizontal(|ui| {
+                                    ui.label("Duration:");
+                                    ui.add(egui::DragValue::new(duration).speed(0.1).range(0.1..=f32::MAX).suffix(" s"));
+                                });
+                                *end = end.clamp(0.0, *duration);
+                                *start = start.clamp(0.0, *end);
+                                let (max_start, max_end) = (*end, *duration);
+                                ui.horizontal(|ui| {
+                                    ui.label("Start:");
+                                    ui.add(egui::DragValue::new(start).speed(0.1).range(0.0..=max_start).suffix(" s"));
+                                    ui.label("End:");
+                                    ui.add(egui::DragValue::new(end).speed(0.1).range(*start..=max_end).suffix(" s"));
+                                });
+                                Self::edit_color(ui, color);
+                            }
+                            WidgetType::RotaryEncoder { value, steps, labels, wrap, color } => {
+                                ui.label("Encoder Properties:");
                                 ui.horizontal(|ui| {
-                                    ui.label("Color:");
-                                    if ui.radio_value(color, WidgetColor::Cyan, "Cyan").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Pink, "Pink").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Green, "Green").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Yellow, "Yellow").clicked() {}
-                                    if ui.radio_value(color, WidgetColor::Red, "Red").clicked() {}
+                                    ui.label("Steps:");
+                                    if ui.add(egui::DragValue::new(steps).range(2..=32)).changed() {
+                                        labels.resize(*steps, String::new());
+                                    }
+                                });
+                                *value = (*value).min(steps.saturating_sub(1));
+                                ui.add(egui::Slider::new(value, 0..=steps.saturating_sub(1)).text("Step"));
+                                ui.checkbox(wrap, "Wrap around");
+                                ui.label("Step labels:");
+                                for (step, label) in labels.iter_mut().enumerate().take(*steps) {
+                                    ui.horizontal(|ui| {
+                                        ui.label(format!("{}:", step + 1));
+                                        ui.text_edit_singleline(label);
+                                    });
+                                }
+                                Self::edit_color(ui, color);
+                            }
+                            WidgetType::SpectrumAnalyzer { bands, color, peaks } => {
+                                ui.label("Spectrum Properties:");
+                                ui.horizontal(|ui| {
+                                    ui.label("Bands:");
+                                    let mut count = bands.len();
+                                    if ui.add(egui::DragValue::new(&mut count).range(1..=256)).changed() {
+                                        bands.resize(count, 0.0);
+                                    }
+                                    if ui.button("Reset peaks").clicked() {
+                                        peaks.clear();
+                                    }
+                                });
+                                Self::edit_color(ui, color);
+                            }
+                            WidgetType::XyPad { x, y, x_label, y_label, color } => {
+                                ui.label("XY Pad Properties:");
+                                ui.horizontal(|ui| {
+                                    ui.label("X label:");
+                                    ui.text_edit_singleline(x_label);
+                                });
+                                ui.add(egui::Slider::new(x, 0.0..=1.0).text("X"));
+                                ui.horizontal(|ui| {
+                                    ui.label("Y label:");
+                                    ui.text_edit_singleline(y_label);
                                 });
+                                ui.add(egui::Slider::new(y, 0.0..=1.0).text("Y"));
+                                Self::edit_color(ui, color);
                             }
+                            WidgetType::Custom { id } => {
+                                ui.label("Custom Widget Properties:");
+                                ui.horizontal(|ui| {
+                                    ui.label("Renderer id:");
+                                    ui.text_edit_singleline(id);
+                                });
+                            }
+                        }
+                        
+                        if widget.widget_type.has_formatted_value() {
+                            ui.horizontal(|ui| {
+                                ui.label("Format:");
+                                egui::ComboBox::from_id_salt("value_format")
+                                    .selected_text(widget.format.map_or("Default", ValueFormat::name))
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut widget.format, None, "Default");
+                                        for format in ValueFormat::ALL {
+                                            // Re-picking the current kind keeps its parameters
+                                            let selected = widget.format.map(ValueFormat::name) == Some(format.name());
+                                            if ui.selectable_label(selected, format.name()).clicked() && !selected {
+                                                widget.format = Some(format);
+                                            }
+                                        }
+                                    });
+                                if let Some(ValueFormat::Decimal { places }) = &mut widget.format {
+                                    ui.add(egui::DragValue::new(places).range(0..=4).suffix(" places"));
+                                }
+                            });
+                        }
+                        if widget.widget_type.range().is_some() && widget.format.map_or(true, ValueFormat::shows_unit) {
+                            ui.horizontal(|ui| {
+                                ui.label("Unit:");
+                                ui.add(egui::TextEdit::singleline(&mut widget.unit).desired_width(60.0))
+                                    .on_hover_text("Shown after the value, e.g. Hz or dB");
+                            });
                         }
                         
+                        if widget.widget_type.switch_state().is_some() {
+                            ui.horizontal(|ui| {
+                                ui.label("Exclusive group:");
+                                let mut group = widget.exclusive_group.clone().unwrap_or_default();
+                                let edit = egui::TextEdit::singleline(&mut group).hint_text("e.g. 1 or solo");
+                                if ui.add(edit).on_hover_text("Turning this on turns off the others in the group").changed() {
+                                    widget.exclusive_group = (!group.trim().is_empty()).then_some(group);
+                                }
+                            });
+                        }
+                        
+                        ui.horizontal(|ui| {
+                            ui.label("Layer:");
+                            if ui.checkbox(&mut widget.always_on_top, "Always on top").changed() && widget.always_on_top {
+                                widget.always_on_bottom = false;
+                            }
+                            if ui.checkbox(&mut widget.always_on_bottom, "Always on bottom").changed() && widget.always_on_bottom {
+                                widget.always_on_top = false;
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Arrange:");
+                            if ui.button("Bring to Front").on_hover_text("Ctrl+]").clicked() {
+                                restack = Some(Self::bring_to_front);
+                            }
+                            if ui.button("Raise").on_hover_text("]").clicked() {
+                                restack = Some(Self::raise);
+                            }
+                            if ui.button("Lower").on_hover_text("[").clicked() {
+                                restack = Some(Self::lower);
+                            }
+                            if ui.button("Send to Back").on_hover_text("Ctrl+[").clicked() {
+                                restack = Some(Self::send_to_back);
+                            }
+                        });
+                        ui.checkbox(&mut widget.locked, "🔒 Locked")
+                            .on_hover_text("Can't be dragged or resized, but can still be played");
+                        
                         ui.separator();
                         if ui.button("Delete Widget").clicked() {
                             delete_widget = true;
@@ -2011,11 +4388,71 @@ impl DragDropCanvas {
             }
             
             self.show_edit_window = open;
+            if !open {
+                // Next time the window opens, start from the widget's name (undo may have changed it)
+                ui.data_mut(|d| d.remove::<String>(egui::Id::new(("widget_name", self.widgets[idx].id))));
+            }
+            
+            // Only fields the user actually changed are copied, so mixed fields stay mixed
+            let after = self.widgets[idx].widget_type.clone();
+            for &other in &batch {
+                let widget_type = &mut self.widgets[other].widget_type;
+                if after.color() != before.color() {
+                    if let (Some(color), Some(slot)) = (after.color(), widget_type.color_mut()) {
+                        *slot = color;
+                    }
+                }
+                if after.label() != before.label() {
+                    if let (Some(label), Some(slot)) = (after.label(), widget_type.label_mut()) {
+                        *slot = label.to_string();
+                    }
+                }
+                if let (Some((min, max)), Some((old_min, old_max)), Some((slot_min, slot_max))) = (after.range(), before.range(), widget_type.range_mut()) {
+                    // Only the changed bound is copied, and only if the range stays valid
+                    let new_min = if min != old_min { min } else { *slot_min };
+                    let new_max = if max != old_max { max } else { *slot_max };
+                    if new_min < new_max {
+                        (*slot_min, *slot_max) = (new_min, new_max);
+                        if let Some(value) = widget_type.value_mut() {
+                            *value = value.clamp(new_min, new_max);
+                        }
+                    }
+                }
+            }
+            
+            if edit_size != original_size {
+                self.resize_widget(idx, edit_size);
+            }
+            if let WidgetType::TextLabel { .. } = after {
+                // The font size slider sets a text label's size
+                self.widgets[idx].size = DraggableWidget::calculate_size(&after);
+            }
+            if edit_pos != original_pos || edit_size != original_size {
+                if let Some(size) = self.widgets.get(idx).map(|w| w.size) {
+                    let position = self.constrain_drag_position(idx, edit_pos + origin.to_vec2(), size);
+                    self.widgets[idx].position = position;
+                }
+            }
+            
+            if let Some(radius) = arrange_radius {
+                self.selected_panel = self.widgets.get(idx).map(|w| w.id);
+                self.arrange_in_circle(radius);
+            }
+            
+            if let Some(reference_id) = match_size_of {
+                if let Some(widget_id) = self.widgets.get(idx).map(|w| w.id) {
+                    self.match_size(widget_id, reference_id);
+                }
+            }
             
             if delete_widget {
-                self.widgets.remove(idx);
-                self.editing_widget = None;
-                self.show_edit_window = false;
+                if let Some(widget_id) = self.widgets.get(idx).map(|w| w.id) {
+                    self.delete_widget(widget_id);
+                }
+            }
+            
+            if let Some(restack) = restack.filter(|_| !delete_widget) {
+                restack(self, idx);
             }
         } else {
             self.show_edit_window = false;
@@ -2026,43 +4463,2301 @@ impl DragDropCanvas {
         }
     }
     
-    fn render_settings_icon(&self, ui: &mut Ui) {
-        let _icon_size = 30.0;
-        let padding = 15.0;
-        let icon_pos = Pos2::new(padding, padding);
-        
+    /// Dashed drop target with a short instruction in the middle of the canvas
+    fn render_empty_hint(&self, ui: &Ui) {
         let painter = ui.painter();
+        let target = Rect::from_center_size(self.canvas_rect.center(), Vec2::new(360.0, 140.0));
+        let outline = [target.left_top(), target.right_top(), target.right_bottom(), target.left_bottom(), target.left_top()];
+        painter.extend(egui::Shape::dashed_line(&outline, Stroke::new(1.5, GRAY_600), 8.0, 6.0));
         
-        // Simple static settings icon
         painter.text(
-            icon_pos,
-            Align2::LEFT_TOP,
+            target.center() - Vec2::new(0.0, 10.0),
+            Align2::CENTER_CENTER,
+            "Drag a widget from the palette to begin",
+            FontId::proportional(16.0),
+            GRAY_400,
+        );
+        painter.text(
+            target.center() + Vec2::new(0.0, 16.0),
+            Align2::CENTER_CENTER,
+            "or press ? for keyboard shortcuts",
+            FontId::proportional(12.0),
+            GRAY_600,
+        );
+    }
+    
+    /// Screen-to-canvas scale factor: 1 unless a design size is set
+    pub fn design_scale(&self) -> f32 {
+        self.design_transform.map_or(1.0, |transform| transform.scaling)
+    }
+    
+    /// Map a screen position into canvas coordinates (identity when unzoomed and without a design size)
+    pub fn screen_to_canvas(&self, pos: Pos2) -> Pos2 {
+        self.view_transform().inverse().mul_pos(pos)
+    }
+    
+    /// Map a canvas position to where it is drawn on screen (identity when unzoomed and without a design size)
+    pub fn canvas_to_screen(&self, pos: Pos2) -> Pos2 {
+        self.view_transform().mul_pos(pos)
+    }
+    
+    /// Canvas -> screen mapping: the zoom first, then the design-size scaling
+    fn view_transform(&self) -> egui::emath::TSTransform {
+        let design = self.design_transform.unwrap_or_default();
+        if self.zoom == 1.0 && self.zoom_offset == Vec2::ZERO {
+            return design;
+        }
+        // Zoom about the canvas corner, so the view stays put when the window resizes
+        let corner = if self.canvas_rect == Rect::NOTHING { Vec2::ZERO } else { self.canvas_rect.min.to_vec2() };
+        design * egui::emath::TSTransform::new(corner + self.zoom_offset - corner * self.zoom, self.zoom)
+    }
+    
+    /// Part of the canvas visible at the current zoom, in canvas coordinates
+    fn visible_canvas_rect(&self) -> Rect {
+        let zoom_only = self.design_transform.map_or(self.view_transform(), |design| design.inverse() * self.view_transform());
+        zoom_only.inverse().mul_rect(self.canvas_rect)
+    }
+    
+    /// Scale the view by `factor`, keeping the canvas point `anchor` where it is on screen
+    ///
+    /// The zoom is clamped to MIN_ZOOM..=MAX_ZOOM.
+    pub fn zoom_at(&mut self, anchor: Pos2, factor: f32) {
+        let new_zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let corner = if self.canvas_rect == Rect::NOTHING { Pos2::ZERO } else { self.canvas_rect.min };
+        self.zoom_offset += (anchor - corner) * (self.zoom - new_zoom);
+        self.zoom = new_zoom;
+    }
+    
+    /// Back to 100% with the canvas corner in place
+    pub fn reset_zoom(&mut self) {
+        self.zoom = 1.0;
+        self.zoom_offset = Vec2::ZERO;
+    }
+    
+    fn canvas_to_screen_rect(&self, rect: Rect) -> Rect {
+        Rect::from_min_max(self.canvas_to_screen(rect.min), self.canvas_to_screen(rect.max))
+    }
+    
+    /// Clickable area of the settings gear in the canvas top-left corner
+    ///
+    /// Anchored on the canvas itself rather than canvas_origin(), so the gear
+    /// stays put while the view is panned.
+    fn settings_icon_rect(&self) -> Rect {
+        let icon_size = 24.0;
+        let padding = 15.0;
+        let corner = if self.canvas_rect == Rect::NOTHING { Pos2::ZERO } else { self.canvas_rect.min };
+        Rect::from_min_size(corner + Vec2::splat(padding), Vec2::splat(icon_size))
+    }
+    
+    fn render_settings_icon(&self, ui: &mut Ui) {
+        let icon_rect = self.settings_icon_rect();
+        let hovered = ui.ctx().input(|i| i.pointer.hover_pos())
+            .map(|pos| icon_rect.contains(self.screen_to_canvas(pos)))
+            .unwrap_or(false);
+        
+        let color = if hovered || self.show_settings_popup {
+            WHITE
+        } else {
+            Color32::from_rgba_unmultiplied(156, 163, 175, 200) // Semi-transparent gray
+        };
+        
+        ui.painter().text(
+            icon_rect.center(),
+            Align2::CENTER_CENTER,
             "⚙",
             FontId::monospace(20.0),
-            Color32::from_rgba_unmultiplied(156, 163, 175, 200), // Semi-transparent gray
+            color,
         );
     }
     
+    fn show_settings_popup(&mut self, ui: &mut Ui) {
+        let mut open = self.show_settings_popup;
+        
+        egui::Window::new("Canvas Settings")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .fixed_pos(self.canvas_to_screen(self.settings_icon_rect().left_bottom()) + Vec2::new(0.0, 4.0))
+            .show(ui.ctx(), |ui| {
+                ui.checkbox(&mut self.performance_mode, "Performance mode (lock layout)");
+                ui.horizontal(|ui| {
+                    ui.label("Mode:");
+                    ui.radio_value(&mut self.interaction_mode, Mode::Design, "Design");
+                    ui.radio_value(&mut self.interaction_mode, Mode::Live, "Live")
+                        .on_hover_text("Only playing the controls works: no moving, adding, editing, deleting, pasting or undo");
+                });
+                
+                ui.separator();
+                
+                ui.checkbox(&mut self.settings.snapping_enabled, "Snap to alignment guides");
+                ui.checkbox(&mut self.settings.show_tooltips, "Show tooltips");
+                ui.checkbox(&mut self.settings.free_placement, "Free placement (drop at cursor)");
+                ui.checkbox(&mut self.settings.show_links, "Show links between widgets");
+                ui.checkbox(&mut self.settings.flick_inertia, "Flicked widgets glide (touch)");
+                ui.checkbox(&mut self.settings.flash_external_changes, "Flash widgets changed by the host");
+                ui.checkbox(&mut self.settings.recycle_ids, "Reuse IDs of deleted widgets");
+                ui.checkbox(&mut self.settings.delete_panel_cascade, "Deleting a panel deletes its contents");
+                
+                ui.separator();
+                
+                ui.horizontal(|ui| {
+                    ui.label("Grid size:");
+                    ui.add(egui::Slider::new(&mut self.settings.grid_size, 60.0..=240.0));
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Canvas margin:");
+                    ui.add(egui::Slider::new(&mut self.settings.canvas_margin, 0.0..=60.0));
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Palette width:");
+                    ui.add(egui::Slider::new(&mut self.settings.palette_width, 160.0..=400.0));
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Panel nesting depth:");
+                    ui.add(egui::Slider::new(&mut self.settings.max_nesting_depth, 1..=8));
+                });
+                
+                ui.checkbox(&mut self.settings.right_drag_fine_tune, "Right-drag fine-tunes knobs and sliders");
+                ui.checkbox(&mut self.settings.distance_sensitivity, "Knob sensitivity follows pointer distance");
+                ui.add_enabled_ui(self.settings.distance_sensitivity, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("1x distance:");
+                        ui.add(egui::Slider::new(&mut self.settings.distance_sensitivity_radius, 20.0..=300.0));
+                    });
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Theme:");
+                    for theme in [CanvasTheme::Dark, CanvasTheme::Slate] {
+                        ui.radio_value(&mut self.settings.theme, theme, theme.name());
+                    }
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("Accent:");
+                    ui.radio_value(&mut self.settings.default_accent, WidgetColor::Cyan, "Cyan");
+                    ui.radio_value(&mut self.settings.default_accent, WidgetColor::Pink, "Pink");
+                    ui.radio_value(&mut self.settings.default_accent, WidgetColor::Green, "Green");
+                    ui.radio_value(&mut self.settings.default_accent, WidgetColor::Yellow, "Yellow");
+                    ui.radio_value(&mut self.settings.default_accent, WidgetColor::Red, "Red");
+                });
+                
+                ui.separator();
+                
+                ui.horizontal(|ui| {
+                    let mut fixed = self.settings.design_size.is_some();
+                    if ui.checkbox(&mut fixed, "Fixed design size").changed() {
+                        self.settings.design_size = fixed.then_some(self.canvas_rect.size().round());
+                    }
+                    if let Some(size) = &mut self.settings.design_size {
+                        ui.add(egui::DragValue::new(&mut size.x).range(320.0..=7680.0).suffix(" ×"));
+                        ui.add(egui::DragValue::new(&mut size.y).range(240.0..=4320.0));
+                    }
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label("On resize:");
+                    for policy in [ResizePolicy::Proportional, ResizePolicy::Fixed, ResizePolicy::Regrid] {
+                        ui.radio_value(&mut self.settings.resize_policy, policy, policy.name());
+                    }
+                }).response.on_hover_text("How widgets move when the window changes size; Regrid re-packs them into the auto-layout grid");
+                
+                ui.collapsing("Spawn hotkeys", |ui| {
+                    egui::Grid::new("spawn_key_grid").num_columns(2).show(ui, |ui| {
+                        for (key, kind) in &mut self.settings.spawn_keys {
+                            ui.label(kind.name());
+                            let mut text = key.to_string();
+                            if ui.add(egui::TextEdit::singleline(&mut text).char_limit(1).desired_width(20.0)).changed() {
+                                if let Some(c) = text.chars().next() {
+                                    *key = c;
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                
+                ui.separator();
+                
+                if ui.button("Reset to defaults").clicked() {
+                    self.settings = CanvasSettings::default();
+                }
+            });
+        
+        self.show_settings_popup = open;
+    }
+    
+    /// List the canvas keyboard and mouse shortcuts, including the configured spawn keys
+    fn show_shortcut_overlay(&mut self, ui: &mut Ui) {
+        let mut open = self.show_shortcuts;
+        
+        egui::Window::new("Keyboard Shortcuts")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ui.ctx(), |ui| {
+                egui::Grid::new("shortcut_grid").num_columns(2).spacing([24.0, 4.0]).show(ui, |ui| {
+                    let shortcuts = [
+                        ("Ctrl/Cmd+Z", "Undo"),
+                        ("Ctrl/Cmd+D", "Duplicate selected widget"),
+                        ("Delete / Backspace", "Delete selected widgets"),
+                        ("Ctrl/Cmd+C", "Copy selected widget"),
+                        ("Ctrl/Cmd+V", "Paste at cursor (+Shift: in place)"),
+                        ("Arrow keys", "Nudge selection 1px (+Shift: 10px)"),
+                        ("Home / End", "Knob or slider to min / max"),
+                        ("PageUp / PageDown", "Step knob or slider by 10%"),
+                        ("Shift+click", "Add to / remove from selection"),
+                        ("Shift+drag", "Connect one widget to another (again to disconnect)"),
+                        ("Alt+click", "Select the widget underneath"),
+                        ("Ctrl/Cmd+scroll", "Zoom around the pointer"),
+                        ("Middle-drag", "Pan the canvas"),
+                        ("] / [", "Raise / lower past the next overlapping widget"),
+                        ("Ctrl/Cmd+] / [", "Bring to front / send to back"),
+                        ("Alt / Shift + resize", "From the center / keep proportions"),
+                        ("?", "Show or hide this list"),
+                    ];
+                    for (keys, action) in shortcuts {
+                        ui.label(RichText::new(keys).monospace().color(CYAN));
+                        ui.label(action);
+                        ui.end_row();
+                    }
+                    for (key, kind) in &self.settings.spawn_keys {
+                        ui.label(RichText::new(key.to_string()).monospace().color(CYAN));
+                        ui.label(format!("Spawn {} at cursor", kind.name()));
+                        ui.end_row();
+                    }
+                });
+            });
+        
+        self.show_shortcuts = open;
+    }
     
-    pub fn save_layout(&self) {
-        // For now, just print to console - could be extended to save to file
-        println!("💾 Layout saved! {} widgets on canvas", self.widgets.len());
+    /// Confirm or cancel a Clear Canvas; a confirmed clear can still be undone
+    fn show_clear_prompt(&mut self, ui: &mut Ui) {
+        let mut choice = None;
+        
+        egui::Window::new("Clear canvas?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ui.ctx(), |ui| {
+                ui.label(format!("Remove all {} widgets from the canvas?", self.widgets.len()));
+                ui.label(RichText::new("Undo (Ctrl/Cmd+Z) brings them back.").weak());
+                ui.horizontal(|ui| {
+                    if ui.button("Confirm").clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        choice = Some(false);
+                    }
+                });
+            });
         
-        // In a real implementation, you would serialize self.widgets and self.config_panel.items
-        // and save them to a file or local storage
+        if let Some(confirmed) = choice {
+            self.confirm_clear = false;
+            if confirmed {
+                self.clear_canvas();
+            }
+        }
+    }
+    
+    /// Draw one widget, with its registered renderer if it is a custom widget
+    ///
+    /// Widgets are drawn at their natural size, then scaled up or down to the widget's size.
+    fn paint_widget(ui: &mut Ui, widget: &mut DraggableWidget, active: bool, custom_renderers: &HashMap<String, CustomRenderer>) {
+        let custom_renderer = match &widget.widget_type {
+            WidgetType::Custom { id } => custom_renderers.get(id),
+            _ => None,
+        };
         
-        // Example of what could be saved:
-        for (i, widget) in self.widgets.iter().enumerate() {
-            println!("  Widget {}: {:?} at {:?}", i, widget.widget_type, widget.position);
+        let first_shape = ui.ctx().graphics_mut(|g| g.entry(ui.layer_id()).next_idx());
+        if let Some(renderer) = custom_renderer {
+            renderer(ui.painter(), widget.natural_rect());
+        } else {
+            widget.render(ui, active);
+        }
+        if widget.scale() != 1.0 {
+            let transform = widget.scale_transform();
+            ui.ctx().graphics_mut(|g| {
+                let shapes = g.entry(ui.layer_id());
+                let end = shapes.next_idx();
+                shapes.transform_range(first_shape, end, transform);
+            });
         }
     }
     
-    pub fn clear_canvas(&mut self) {
-        self.widgets.clear();
-        println!("🗑️ Canvas cleared!");
+    /// Draw a fading accent outline around each widget with a recent host-driven change
+    fn render_flashes(&mut self, ui: &Ui, visible: &[bool]) {
+        let now = self.frame_time;
+        self.flashes.retain(|_, started| now - *started < FLASH_DURATION);
+        if self.flashes.is_empty() {
+            return;
+        }
+        
+        let accent = self.settings.default_accent.to_color32();
+        for (idx, widget) in self.widgets.iter().enumerate() {
+            let Some(started) = self.flashes.get(&widget.id) else {
+                continue;
+            };
+            if visible[idx] {
+                let fade = (1.0 - (now - started) / FLASH_DURATION) as f32;
+                ui.painter().rect_stroke(widget.get_rect().expand(3.0), 6.0, Stroke::new(2.0, accent.gamma_multiply(fade)), egui::StrokeKind::Outside);
+            }
+        }
+        ui.ctx().request_repaint();
     }
     
+    /// Outline the widget whose move into a panel was just refused in red, fading out
+    fn render_rejected_drop(&mut self, ui: &Ui) {
+        let Some((widget_id, started)) = self.rejected_drop else {
+            return;
+        };
+        let fade = 1.0 - (self.frame_time - started) / FLASH_DURATION;
+        let widget = self.widgets.iter().find(|w| w.id == widget_id);
+        match widget {
+            Some(widget) if fade > 0.0 => {
+                let stroke = Stroke::new(3.0, RED.gamma_multiply(fade as f32));
+                ui.painter().rect_stroke(widget.get_rect().expand(2.0), 4.0, stroke, egui::StrokeKind::Outside);
+                ui.ctx().request_repaint();
+            }
+            _ => self.rejected_drop = None,
+        }
+    }
     
-    // Legacy drop logic removed
+    fn render_snap_grid(&self, ui: &Ui) {
+        let size = self.settings.snap_grid_size;
+        if size < 4.0 {
+            return; // Denser than this the lines would fill the canvas
+        }
+        let painter = ui.painter();
+        let stroke = Stroke::new(1.0, GRAY_800);
+        let origin = self.canvas_origin();
+        let first = |min: f32, origin: f32| origin + ((min - origin) / size).ceil() * size;
+        
+        let mut x = first(self.canvas_rect.left(), origin.x);
+        while x <= self.canvas_rect.right() {
+            painter.line_segment([Pos2::new(x, self.canvas_rect.top()), Pos2::new(x, self.canvas_rect.bottom())], stroke);
+            x += size;
+        }
+        let mut y = first(self.canvas_rect.top(), origin.y);
+        while y <= self.canvas_rect.bottom() {
+            painter.line_segment([Pos2::new(self.canvas_rect.left(), y), Pos2::new(self.canvas_rect.right(), y)], stroke);
+            y += size;
+        }
+    }
+    
+    fn render_selection(&self, ui: &Ui, visible: &[bool]) {
+        let painter = ui.painter();
+        for (widget, _) in self.widgets.iter().zip(visible).filter(|(w, &shown)| shown && self.selected_widgets.contains(&w.id)) {
+            painter.rect_stroke(widget.get_rect().expand(2.0), 4.0, Stroke::new(1.0, CYAN), egui::StrokeKind::Outside);
+        }
+        if let Some((start, current)) = self.marquee {
+            let marquee = Rect::from_two_pos(start, current);
+            painter.rect_filled(marquee, 0.0, CYAN.gamma_multiply(0.1));
+            painter.rect_stroke(marquee, 0.0, Stroke::new(1.0, CYAN), egui::StrokeKind::Inside);
+        }
+    }
+    
+    /// Start the change flash on a widget, if flashes are enabled
+    fn flash(&mut self, widget_id: usize) {
+        if self.settings.flash_external_changes {
+            self.flashes.insert(widget_id, self.frame_time);
+        }
+    }
     
-}
\ No newline at end of file
+    /// Draw a thin curve between each pair of linked, visible widgets (only with show_links on)
+    fn render_links(&self, ui: &Ui, visible: &[bool]) {
+        let painter = ui.painter();
+        let stroke = Stroke::new(1.5, CYAN.gamma_multiply(0.6));
+        
+        for (idx, widget) in self.widgets.iter().enumerate() {
+            if !visible[idx] {
+                continue;
+            }
+            for &partner_id in &widget.linked_to {
+                // Links are usually recorded on both ends; draw each pair once
+                if partner_id <= widget.id && self.widgets.iter().any(|w| w.id == partner_id && w.linked_to.contains(&widget.id)) {
+                    continue;
+                }
+                let Some(partner_idx) = self.widgets.iter().position(|w| w.id == partner_id) else {
+                    continue;
+                };
+                if !visible[partner_idx] {
+                    continue;
+                }
+                
+                // Sag the curve downwards in proportion to its length, like a patch cable
+                let from = widget.get_rect().center();
+                let to = self.widgets[partner_idx].get_rect().center();
+                let sag = Vec2::new(0.0, ((to - from).length() * 0.25).min(80.0));
+                painter.add(egui::epaint::CubicBezierShape::from_points_stroke(
+                    [from, from + sag, to + sag, to],
+                    false,
+                    Color32::TRANSPARENT,
+                    stroke,
+                ));
+            }
+        }
+    }
+    
+    /// Link two widgets so their relationship is recorded on both
+    ///
+    /// Links are for display only: they show up as curves when show_links is
+    /// on, but the widgets' values stay independent. There's no UI to create
+    /// them, so they only exist where the host calls this.
+    /// Returns false if either widget does not exist or they are the same widget.
+    pub fn link_widgets(&mut self, a_id: usize, b_id: usize) -> bool {
+        if a_id == b_id || !self.widgets.iter().any(|w| w.id == a_id) || !self.widgets.iter().any(|w| w.id == b_id) {
+            return false;
+        }
+        for widget in &mut self.widgets {
+            let partner_id = if widget.id == a_id { b_id } else if widget.id == b_id { a_id } else { continue };
+            if !widget.linked_to.contains(&partner_id) {
+                widget.linked_to.push(partner_id);
+            }
+        }
+        true
+    }
+    
+    /// Route a cable from one widget to another, e.g. an output into an input on a patchbay
+    ///
+    /// Connections are directed and only drawn, the same as links: values are
+    /// not carried along them. Deleting either widget removes the connection.
+    /// Returns false if either widget does not exist, they are the same widget
+    /// or the connection is already there.
+    pub fn connect(&mut self, from_id: usize, to_id: usize) -> bool {
+        let exists = |id| self.widgets.iter().any(|w| w.id == id);
+        if from_id == to_id || !exists(from_id) || !exists(to_id) || self.connections.contains(&(from_id, to_id)) {
+            return false;
+        }
+        self.connections.push((from_id, to_id));
+        true
+    }
+    
+    /// Draw each connection between visible widgets, and the cable being Shift+dragged to the pointer
+    ///
+    /// Cables leave and enter the widget centers horizontally, in the source widget's color.
+    fn render_connections(&self, ui: &Ui, visible: &[bool]) {
+        let painter = ui.painter();
+        let cable = |from: Pos2, to: Pos2, color: Color32| {
+            let pull = Vec2::new(((to.x - from.x).abs() * 0.5).max(30.0), 0.0);
+            painter.add(egui::epaint::CubicBezierShape::from_points_stroke(
+                [from, from + pull, to - pull, to],
+                false,
+                Color32::TRANSPARENT,
+                Stroke::new(2.0, color),
+            ));
+        };
+        let source_color = |widget: &DraggableWidget| widget.widget_type.color().map_or(CYAN, |color| color.to_color32());
+        
+        for &(from_id, to_id) in &self.connections {
+            let from_idx = self.widgets.iter().position(|w| w.id == from_id);
+            let to_idx = self.widgets.iter().position(|w| w.id == to_id);
+            let (Some(from_idx), Some(to_idx)) = (from_idx, to_idx) else {
+                continue;
+            };
+            if visible[from_idx] && visible[to_idx] {
+                let source = &self.widgets[from_idx];
+                cable(source.get_rect().center(), self.widgets[to_idx].get_rect().center(), source_color(source));
+            }
+        }
+        
+        if let Some(source) = self.connecting.and_then(|id| self.widgets.iter().find(|w| w.id == id)) {
+            if let Some(pos) = ui.ctx().input(|i| i.pointer.hover_pos()) {
+                cable(source.get_rect().center(), self.screen_to_canvas(pos), source_color(source).gamma_multiply(0.6));
+            }
+        }
+    }
+    
+    /// Edit-window row for a widget color: the five presets, plus a picker for any RGB
+    fn edit_color(ui: &mut Ui, color: &mut WidgetColor) {
+        ui.horizontal(|ui| {
+            ui.label("Color:");
+            ui.radio_value(color, WidgetColor::Cyan, "Cyan");
+            ui.radio_value(color, WidgetColor::Pink, "Pink");
+            ui.radio_value(color, WidgetColor::Green, "Green");
+            ui.radio_value(color, WidgetColor::Yellow, "Yellow");
+            ui.radio_value(color, WidgetColor::Red, "Red");
+            
+            // The picker starts from the current color, so a preset can be fine-tuned from there
+            let [r, g, b, _] = color.to_color32().to_array();
+            let mut rgb = [r, g, b];
+            if egui::color_picker::color_edit_button_srgb(ui, &mut rgb).on_hover_text("Custom color").changed() {
+                *color = WidgetColor::Custom(rgb[0], rgb[1], rgb[2]);
+            }
+        });
+    }
+    
+    /// Edit-window row for a knob or slider's range
+    ///
+    /// Any finite bounds are accepted, so ranges like -60..12 dB work, but an
+    /// edit that would leave min >= max is dropped. The value and a custom
+    /// default are pulled back into the new range.
+    fn edit_range(ui: &mut Ui, value: &mut f32, min: &mut f32, max: &mut f32, default_value: &mut Option<f32>) {
+        let (mut new_min, mut new_max) = (*min, *max);
+        let speed = ((*max - *min) / 200.0).max(0.01); // Half a percent of the range per point dragged
+        ui.horizontal(|ui| {
+            ui.label("Range:");
+            ui.add(egui::DragValue::new(&mut new_min).speed(speed).prefix("min "));
+            ui.add(egui::DragValue::new(&mut new_max).speed(speed).prefix("max "));
+        });
+        
+        let valid = new_min.is_finite() && new_max.is_finite() && new_min < new_max;
+        if valid && (new_min, new_max) != (*min, *max) {
+            *min = new_min;
+            *max = new_max;
+            *value = value.clamp(new_min, new_max);
+            if let Some(default) = default_value {
+                *default = default.clamp(new_min, new_max);
+            }
+        }
+    }
+    
+    /// Edit-window row for a knob or slider's double-click default
+    ///
+    /// Unchecked, the default follows the middle of the range.
+    fn edit_default_value(ui: &mut Ui, default_value: &mut Option<f32>, min: f32, max: f32) {
+        ui.horizontal(|ui| {
+            let mut custom = default_value.is_some();
+            if ui.checkbox(&mut custom, "Default").changed() {
+                *default_value = custom.then_some((min + max) / 2.0);
+            }
+            match default_value {
+                Some(default) => ui.add(egui::Slider::new(default, min..=max)),
+                None => ui.label(format!("middle of range ({:.1})", (min + max) / 2.0)),
+            };
+        });
+    }
+    
+    /// Show the type, label and current value of the hovered widget while nothing is being dragged
+    ///
+    /// Only the topmost widget under the pointer gets a tooltip (see hovered_widget()).
+    fn show_widget_tooltip(&self, ui: &mut Ui) {
+        if self.dragging_widget.is_some() || self.resizing_widget.is_some() || self.palette_dragging.is_some() {
+            return;
+        }
+        let hovered = self.hovered_widget_id
+            .and_then(|id| self.widgets.iter().find(|w| w.id == id));
+        
+        if let Some(widget) = hovered {
+            let label = match &widget.widget_type {
+                WidgetType::Knob { label, .. }
+                | WidgetType::ToggleSwitch { label, .. }
+                | WidgetType::PushButton { label, .. }
+                | WidgetType::VuMeter { label, .. }
+                | WidgetType::HorizontalSlider { label, .. }
+                | WidgetType::VerticalSlider { label, .. }
+                | WidgetType::LevelIndicator { label, .. }
+                | WidgetType::IconButton { label, .. }
+                | WidgetType::Settings { label, .. }
+                | WidgetType::Readout { label, .. } => label.as_str(),
+                WidgetType::Custom { id } => id.as_str(),
+                WidgetType::TextLabel { text, .. } => text.as_str(),
+                WidgetType::Panel { title, .. } => title.as_str(),
+                WidgetType::StatusBar { .. }
+                | WidgetType::MeterWithReadout { .. }
+                | WidgetType::TimeDisplay { .. }
+                | WidgetType::RegionSelector { .. }
+                | WidgetType::XyPad { .. }
+                | WidgetType::RotaryEncoder { .. }
+                | WidgetType::SpectrumAnalyzer { .. } => "",
+            };
+            
+            egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), egui::Id::new(("canvas_widget_tooltip", widget.id)), |ui| {
+                ui.label(RichText::new(widget.widget_type.type_name()).strong());
+                if !label.is_empty() {
+                    ui.label(label);
+                }
+                if let Some(value) = widget.value_text() {
+                    ui.label(RichText::new(value).monospace());
+                }
+            });
+        }
+    }
+    
+    
+    /// Copy the size of the reference widget onto another widget
+    ///
+    /// Panels also take over the reference size as their expanded width/height.
+    /// Returns false if either widget does not exist.
+    pub fn match_size(&mut self, widget_id: usize, reference_id: usize) -> bool {
+        let Some(reference_size) = self.widgets.iter().find(|w| w.id == reference_id).map(|w| w.size) else {
+            return false;
+        };
+        let Some(widget) = self.widgets.iter_mut().find(|w| w.id == widget_id) else {
+            return false;
+        };
+        
+        if let WidgetType::Panel { width, height, .. } = &mut widget.widget_type {
+            *width = reference_size.x;
+            *height = reference_size.y;
+            widget.size = DraggableWidget::calculate_size(&widget.widget_type);
+        } else {
+            widget.set_size(reference_size);
+        }
+        true
+    }
+    
+    /// IDs of the widgets layout commands act on: the direct contents of the selected panel
+    fn selected_group(&self) -> Vec<usize> {
+        match self.selected_panel.and_then(|id| self.widgets.iter().find(|w| w.id == id)).map(|w| &w.widget_type) {
+            Some(WidgetType::Panel { contained_widgets, .. }) | Some(WidgetType::Settings { contained_widgets, .. }) => contained_widgets.clone(),
+            _ => Vec::new(),
+        }
+    }
+    
+    /// IDs of the selected widgets: the multi-selection, or else the single selected widget
+    fn selected_ids(&self) -> Vec<usize> {
+        if self.selected_widgets.is_empty() {
+            self.selected_widget.into_iter().collect()
+        } else {
+            self.selected_widgets.clone()
+        }
+    }
+    
+    /// Move the selected widgets by `delta`, keeping each inside its panel or the canvas
+    ///
+    /// The selection moves together: the step is cut short on an axis where any
+    /// selected widget would leave its bounds. A panel's contents come along and
+    /// locked widgets stay put. Undoable; nudges of the same selection less than
+    /// NUDGE_BURST_GAP apart undo as one step. Returns false if nothing moved.
+    pub fn nudge_selected(&mut self, delta: Vec2) -> bool {
+        let ids = self.selected_ids();
+        let roots: Vec<usize> = self.widgets.iter()
+            .enumerate()
+            .filter(|(_, w)| ids.contains(&w.id) && !w.locked)
+            // A selected panel already carries its contents
+            .filter(|(_, w)| !ids.iter().any(|&other| other != w.id && self.contained_widgets_recursive(other).contains(&w.id)))
+            .map(|(idx, _)| idx)
+            .collect();
+        
+        // Shortest allowed step on each axis across the selection
+        let mut step = delta;
+        for &idx in &roots {
+            let widget = &self.widgets[idx];
+            let allowed = self.constrain_drag_position(idx, widget.position + delta, widget.size) - widget.position;
+            let shortest = |wanted: f32, allowed: f32| if allowed * wanted <= 0.0 { 0.0 } else if allowed.abs() < wanted.abs() { allowed } else { wanted };
+            step = Vec2::new(shortest(step.x, allowed.x), shortest(step.y, allowed.y));
+        }
+        if step == Vec2::ZERO {
+            return false;
+        }
+        
+        let continues_burst = self.nudge_burst.as_ref()
+            .is_some_and(|(burst_ids, at)| *burst_ids == ids && self.frame_time - at < NUDGE_BURST_GAP);
+        if !continues_burst {
+            self.push_undo();
+        }
+        self.nudge_burst = Some((ids, self.frame_time));
+        
+        for idx in roots {
+            self.move_widget_with_contents(idx, step);
+        }
+        true
+    }
+    
+    /// Nudge every selected knob, slider and VU meter by `percent` of its own range
+    ///
+    /// A one-shot, mixer-style group trim: each widget moves relative to where it
+    /// is, clamped to its range, so their relative balance is kept until one hits
+    /// an end. Other selected widgets are ignored. Moved widgets are reported
+    /// through take_changed(). Undoable. Returns false if nothing moved.
+    pub fn trim_selected(&mut self, percent: f32) -> bool {
+        let fraction = percent / 100.0;
+        let ids = self.selected_ids();
+        let before = self.snapshot();
+        let mut moved = Vec::new();
+        
+        for widget in self.widgets.iter_mut().filter(|w| ids.contains(&w.id)) {
+            // VU levels are percentages; knobs and sliders use their own range
+            let (min, max) = match &widget.widget_type {
+                WidgetType::VuMeter { .. } => (0.0, 100.0),
+                other => match other.range() {
+                    Some(range) => range,
+                    None => continue,
+                },
+            };
+            let value = match &mut widget.widget_type {
+                WidgetType::VuMeter { level, .. } => level,
+                other => match other.value_mut() {
+                    Some(value) => value,
+                    None => continue,
+                },
+            };
+            let trimmed = (*value + fraction * (max - min)).clamp(min, max);
+            if std::mem::replace(value, trimmed) != trimmed {
+                moved.push(widget.id);
+                CanvasEvent::queue_change(&mut self.events, widget);
+            }
+        }
+        
+        if moved.is_empty() {
+            return false;
+        }
+        self.push_undo_snapshot(before);
+        self.changed_widgets.extend(moved);
+        true
+    }
+    
+    /// Spread the selected widgets evenly on a circle around their centroid
+    ///
+    /// Widgets go clockwise in selection order, the first at the top; each
+    /// widget's center lands on the circle. Panels carry their contents along.
+    /// Undoable. Returns false if fewer than two widgets are selected.
+    pub fn arrange_in_circle(&mut self, radius: f32) -> bool {
+        let indices: Vec<usize> = self.selected_group().iter()
+            .filter_map(|id| self.widgets.iter().position(|w| w.id == *id))
+            .collect();
+        if indices.len() < 2 {
+            return false;
+        }
+        self.push_undo();
+        
+        let centroid = indices.iter()
+            .fold(Vec2::ZERO, |sum, &idx| sum + self.widgets[idx].get_rect().center().to_vec2())
+            / indices.len() as f32;
+        for (i, &idx) in indices.iter().enumerate() {
+            let angle = -PI / 2.0 + i as f32 * 2.0 * PI / indices.len() as f32;
+            let target = centroid + radius * Vec2::angled(angle);
+            let delta = target - self.widgets[idx].get_rect().center().to_vec2();
+            self.move_widget_with_contents(idx, delta);
+        }
+        true
+    }
+    
+    /// Add a widget to the multi-selection, or remove it if it is already there
+    ///
+    /// The first Shift+click starts the multi-selection from the current single
+    /// selection, so Shift+clicking a second widget selects both.
+    fn toggle_selected(&mut self, widget_id: usize) {
+        if self.selected_widgets.is_empty() {
+            self.selected_widgets.extend(self.selected_widget.filter(|&id| id != widget_id));
+        }
+        if let Some(pos) = self.selected_widgets.iter().position(|&id| id == widget_id) {
+            self.selected_widgets.remove(pos);
+            self.selected_widget = self.selected_widgets.last().copied();
+        } else {
+            self.selected_widgets.push(widget_id);
+            self.selected_widget = Some(widget_id);
+        }
+    }
+    
+    /// Indices of the widgets that move with a drag of `idx`: the whole multi-selection if it is a member
+    ///
+    /// Locked members stay where they are.
+    fn drag_group(&self, idx: usize) -> Vec<usize> {
+        let Some(id) = self.widgets.get(idx).map(|w| w.id) else {
+            return Vec::new();
+        };
+        if !self.selected_widgets.contains(&id) {
+            return vec![idx];
+        }
+        self.widgets.iter()
+            .enumerate()
+            .filter(|(_, w)| self.selected_widgets.contains(&w.id) && !w.locked)
+            .map(|(i, _)| i)
+            .collect()
+    }
+    
+    /// Move several widgets by the same delta, shortened so none of them leaves the canvas
+    fn move_group(&mut self, group: &[usize], delta: Vec2) {
+        let Some(bounds) = group.iter()
+            .filter_map(|&idx| self.widgets.get(idx))
+            .map(|w| w.get_rect())
+            .reduce(|a, b| a.union(b))
+        else {
+            return;
+        };
+        let canvas = self.canvas_rect;
+        let clamp_axis = |delta: f32, low: f32, high: f32| delta.clamp(low.min(0.0), high.max(0.0));
+        let delta = Vec2::new(
+            clamp_axis(delta.x, canvas.min.x - bounds.min.x, canvas.max.x - bounds.max.x),
+            clamp_axis(delta.y, canvas.min.y - bounds.min.y, canvas.max.y - bounds.max.y),
+        );
+        for &idx in group {
+            if let Some(widget) = self.widgets.get_mut(idx) {
+                widget.position += delta;
+            }
+        }
+    }
+    
+    /// ID of the topmost visible widget under the pointer, updated every frame in `render`
+    pub fn hovered_widget(&self) -> Option<usize> {
+        self.hovered_widget_id
+    }
+    
+    /// ID of the widget being dragged, so hosts can draw their own overlay beside it
+    pub fn dragging_widget_id(&self) -> Option<usize> {
+        self.widget_id_at_index(self.dragging_widget)
+    }
+    
+    /// ID of the widget being resized by its corner handle
+    pub fn resizing_widget_id(&self) -> Option<usize> {
+        self.widget_id_at_index(self.resizing_widget)
+    }
+    
+    /// ID of the widget whose value is being adjusted (knob turn, slider drag, button hold)
+    pub fn interacting_widget_id(&self) -> Option<usize> {
+        self.widget_id_at_index(self.interacting_widget)
+    }
+    
+    fn widget_id_at_index(&self, index: Option<usize>) -> Option<usize> {
+        index.and_then(|idx| self.widgets.get(idx)).map(|w| w.id)
+    }
+    
+    /// Whether widgets are pinned in place, by performance mode or live mode
+    fn layout_locked(&self) -> bool {
+        self.performance_mode || self.is_live()
+    }
+    
+    fn is_live(&self) -> bool {
+        self.interaction_mode == Mode::Live
+    }
+    
+    /// Index of the topmost widget at a position, skipping widgets hidden in minimized panels
+    fn widget_at(&self, pos: Pos2) -> Option<usize> {
+        self.render_order()
+            .into_iter()
+            .rev()
+            .find(|&idx| self.widgets[idx].get_rect().contains(pos) && !self.is_widget_in_minimized_panel(self.widgets[idx].id))
+    }
+    
+    /// Move a widget above every other widget in its layer
+    ///
+    /// A panel's contents come along and stay above it. The always on top/bottom
+    /// flags still win over the order set here. Undoable. Returns false if `idx`
+    /// is out of range or nothing moved.
+    pub fn bring_to_front(&mut self, idx: usize) -> bool {
+        self.restack(idx, self.widgets.len())
+    }
+    
+    /// Move a widget below every other widget in its layer
+    ///
+    /// A widget inside a panel only goes down to just above that panel, so it
+    /// stays visible. Undoable. Returns false if `idx` is out of range or
+    /// nothing moved.
+    pub fn send_to_back(&mut self, idx: usize) -> bool {
+        let Some(widget) = self.widgets.get(idx) else {
+            return false;
+        };
+        let floor = self.container_of(widget.id)
+            .and_then(|panel_id| self.widgets.iter().position(|w| w.id == panel_id))
+            .map_or(0, |panel_idx| panel_idx + 1);
+        self.restack(idx, floor)
+    }
+    
+    /// Move a widget above the next overlapping widget in its layer
+    ///
+    /// Widgets it doesn't overlap are skipped, so every step is visible.
+    /// Undoable. Returns false if `idx` is out of range or nothing overlaps it
+    /// from above.
+    pub fn raise(&mut self, idx: usize) -> bool {
+        let Some(&top) = self.stack_block(idx).last() else {
+            return false;
+        };
+        let above = (top + 1..self.widgets.len()).find(|&other| self.overlaps_in_layer(idx, other));
+        match above.and_then(|other| self.stack_block(other).last().copied()) {
+            Some(other_top) => self.restack(idx, other_top + 1),
+            None => false,
+        }
+    }
+    
+    /// Move a widget below the next overlapping widget in its layer
+    ///
+    /// Widgets it doesn't overlap are skipped, and a widget never goes below
+    /// the panel it is in. Undoable. Returns false if `idx` is out of range or
+    /// nothing overlaps it from below.
+    pub fn lower(&mut self, idx: usize) -> bool {
+        let Some(&bottom) = self.stack_block(idx).first() else {
+            return false;
+        };
+        let id = self.widgets[idx].id;
+        let below = (0..bottom).rev().find(|&other| self.overlaps_in_layer(idx, other));
+        match below {
+            Some(other) if !self.contained_widgets_recursive(self.widgets[other].id).contains(&id) => self.restack(idx, other),
+            _ => false,
+        }
+    }
+    
+    /// Whether two widgets are drawn in the same layer and overlap on screen
+    fn overlaps_in_layer(&self, idx: usize, other: usize) -> bool {
+        let (widget, other_widget) = (&self.widgets[idx], &self.widgets[other]);
+        widget.z_layer() == other_widget.z_layer()
+            && widget.get_rect().intersects(other_widget.get_rect())
+            && !self.is_widget_in_minimized_panel(other_widget.id)
+            && !self.contained_widgets_recursive(widget.id).contains(&other_widget.id)
+    }
+    
+    /// Indices of a widget and everything inside it, in stacking order (empty if `idx` is out of range)
+    fn stack_block(&self, idx: usize) -> Vec<usize> {
+        let Some(widget) = self.widgets.get(idx) else {
+            return Vec::new();
+        };
+        let mut ids = self.contained_widgets_recursive(widget.id);
+        ids.push(widget.id);
+        (0..self.widgets.len()).filter(|&i| ids.contains(&self.widgets[i].id)).collect()
+    }
+    
+    /// Move a widget and its contents to just below the widget now at `target` (widgets.len() for the top)
+    ///
+    /// Panel contents lists hold IDs and are unaffected; index-based interaction
+    /// state is remapped so it keeps pointing at the same widgets.
+    fn restack(&mut self, idx: usize, target: usize) -> bool {
+        let Some(order) = self.stack_order(idx, target) else {
+            return false;
+        };
+        self.push_undo();
+        self.reorder(&order);
+        true
+    }
+    
+    /// Old indices in their order after restacking `idx` below `target`; None if nothing would move
+    fn stack_order(&self, idx: usize, target: usize) -> Option<Vec<usize>> {
+        let block = self.stack_block(idx);
+        if block.is_empty() {
+            return None;
+        }
+        let mut order: Vec<usize> = (0..self.widgets.len()).filter(|i| !block.contains(i)).collect();
+        let at = order.iter().position(|&i| i >= target).unwrap_or(order.len());
+        order.splice(at..at, block);
+        (!order.iter().copied().eq(0..self.widgets.len())).then_some(order)
+    }
+    
+    /// Rearrange the widgets into `order` (old indices), not undoable on its own
+    fn reorder(&mut self, order: &[usize]) {
+        self.widgets = order.iter().map(|&old| self.widgets[old].clone()).collect();
+        let remap = |old: usize| order.iter().position(|&i| i == old).unwrap_or(old);
+        self.dragging_widget = self.dragging_widget.map(remap);
+        self.interacting_widget = self.interacting_widget.map(remap);
+        self.resizing_widget = self.resizing_widget.map(remap);
+        self.fine_tuning_widget = self.fine_tuning_widget.map(remap);
+        self.glide = self.glide.map(|(idx, velocity)| (remap(idx), velocity));
+        self.spring_back = self.spring_back.map(|(idx, target)| (remap(idx), target));
+    }
+    
+    /// Widget indices from bottom to top, the order they are drawn in
+    ///
+    /// Always-on-bottom widgets come first and always-on-top widgets last. Within
+    /// each layer the dragged widget (with anything inside it, for panels) is lifted
+    /// above the rest, so dragging never pulls a widget out of its layer. Hit-tests
+    /// walk this in reverse so what is visible on top is what gets clicked.
+    fn render_order(&self) -> Vec<usize> {
+        let lifted_ids: Vec<usize> = self.dragging_widget
+            .and_then(|idx| self.widgets.get(idx))
+            .map(|w| std::iter::once(w.id).chain(self.contained_widgets_recursive(w.id)).collect())
+            .unwrap_or_default();
+        let mut order: Vec<usize> = (0..self.widgets.len()).collect();
+        order.sort_by_key(|&idx| (self.widgets[idx].z_layer(), lifted_ids.contains(&self.widgets[idx].id)));
+        order
+    }
+    
+    /// IDs of widgets whose value was changed by user interaction since the last call
+    ///
+    /// Returned in ascending order and cleared, so hosts can poll this once per
+    /// frame and only push the changed widgets to their audio engine. Host-side
+    /// updates such as `set_readout` are not reported.
+    pub fn take_changed(&mut self) -> Vec<usize> {
+        std::mem::take(&mut self.changed_widgets).into_iter().collect()
+    }
+    
+    /// What the user did since the last call, oldest first
+    ///
+    /// A finer-grained alternative to take_changed(): value changes, toggles
+    /// and button presses come with the widget's new state, alongside widgets
+    /// being added and deleted (undo included). Like take_changed(), values set
+    /// by the host and loading a layout are not reported. The queue is cleared;
+    /// if it is never polled only the last EVENT_LIMIT events are kept.
+    pub fn take_events(&mut self) -> Vec<CanvasEvent> {
+        std::mem::take(&mut self.events)
+    }
+    
+    /// Register the drawing routine for `WidgetType::Custom { id }` widgets
+    ///
+    /// Custom widgets are dragged, selected and contained like any other
+    /// widget; only their drawing is delegated. The renderer draws into the widget's
+    /// natural 100×100 rect and the canvas scales the result to the widget's size.
+    /// Registering the same id again replaces the previous renderer. Unregistered
+    /// ids draw a placeholder.
+    pub fn register_custom_renderer(&mut self, id: impl Into<String>, renderer: CustomRenderer) {
+        self.custom_renderers.insert(id.into(), renderer);
+    }
+    
+    /// Switch between building the layout (`Mode::Design`) and playing it (`Mode::Live`)
+    ///
+    /// Live mode is for shipping a finished console: knobs, sliders, buttons
+    /// and the other controls stay playable, but widgets can't be dragged,
+    /// resized, spawned, edited or deleted, and the palette is grayed out.
+    /// An edit window or context menu open at the switch closes on the next frame.
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.interaction_mode = mode;
+    }
+    
+    /// Current interaction mode, see set_mode()
+    pub fn mode(&self) -> Mode {
+        self.interaction_mode
+    }
+    
+    /// Whether the canvas is in live mode; kept for hosts written against the old `edit_locked` field
+    pub fn edit_locked(&self) -> bool {
+        self.is_live()
+    }
+    
+    /// Lock (`Mode::Live`) or unlock (`Mode::Design`) editing; kept for hosts written against the old `edit_locked` field
+    pub fn set_edit_locked(&mut self, on: bool) {
+        self.set_mode(if on { Mode::Live } else { Mode::Design });
+    }
+    
+    /// Widget with the given ID
+    ///
+    /// IDs stay the same when widgets are restacked, deleted or reflowed, so
+    /// keep the ID returned by `add_widget` rather than an index into `widgets`.
+    pub fn get_widget(&self, widget_id: usize) -> Option<&DraggableWidget> {
+        self.widgets.iter().find(|w| w.id == widget_id)
+    }
+    
+    /// Mutable access to the widget with the given ID
+    ///
+    /// Changes made through it are not reported by take_changed() or flashed;
+    /// use the setters below for values driven by the host.
+    pub fn get_widget_mut(&mut self, widget_id: usize) -> Option<&mut DraggableWidget> {
+        self.widgets.iter_mut().find(|w| w.id == widget_id)
+    }
+    
+    /// Move a knob or slider to a value from the host, e.g. DAW automation
+    ///
+    /// The value is clamped to the widget's range. Like the other host-side
+    /// setters it is not reported by take_changed(), and it flashes the widget
+    /// when `flash_external_changes` is on and the value moved. Returns false
+    /// if the widget does not exist or is not a knob or slider.
+    pub fn set_value(&mut self, widget_id: usize, new_value: f32) -> bool {
+        let Some(widget_type) = self.get_widget_mut(widget_id).map(|w| &mut w.widget_type) else {
+            return false;
+        };
+        // Only widgets with a range are host-adjustable; readouts have their own setter
+        let Some((min, max)) = widget_type.range() else {
+            return false;
+        };
+        let clamped = new_value.clamp(min, max);
+        let moved = widget_type.value_mut().is_some_and(|value| std::mem::replace(value, clamped) != clamped);
+        if moved {
+            self.flash(widget_id);
+        }
+        true
+    }
+    
+    /// ID of the widget with the given name, set in the edit window
+    pub fn find_by_name(&self, name: &str) -> Option<usize> {
+        self.widgets.iter().find(|w| w.name.as_deref() == Some(name)).map(|w| w.id)
+    }
+    
+    /// set_value() for the widget with the given name
+    ///
+    /// Returns false if no widget has that name or it is not a knob or slider.
+    pub fn set_value_by_name(&mut self, name: &str, new_value: f32) -> bool {
+        self.find_by_name(name).is_some_and(|widget_id| self.set_value(widget_id, new_value))
+    }
+    
+    /// Update the displayed value of a readout widget from the host
+    ///
+    /// Returns false if the widget does not exist or is not a readout.
+    pub fn set_readout(&mut self, widget_id: usize, new_value: f32) -> bool {
+        let moved = match self.get_widget_mut(widget_id).map(|w| &mut w.widget_type) {
+            Some(WidgetType::Readout { value, .. }) => std::mem::replace(value, new_value) != new_value,
+            _ => return false,
+        };
+        if moved {
+            self.flash(widget_id);
+        }
+        true
+    }
+    
+    /// Feed a new level (in dB) to a meter-with-readout widget from the host
+    ///
+    /// The held peak rises with the level and stays until reset from the edit
+    /// window. Meters are fed continuously, so they never flash. Returns false
+    /// if the widget does not exist or is not a meter with readout.
+    pub fn set_meter(&mut self, widget_id: usize, new_level: f32) -> bool {
+        match self.get_widget_mut(widget_id).map(|w| &mut w.widget_type) {
+            Some(WidgetType::MeterWithReadout { level, peak, .. }) => {
+                *level = new_level;
+                *peak = peak.max(new_level);
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    /// Feed new band magnitudes to a spectrum analyzer from the host, e.g. an FFT each frame
+    ///
+    /// Magnitudes are 0..1, one per bar, so scale FFT output first; out-of-range and
+    /// non-finite values are clamped. The widget takes on the number of bands given.
+    /// Spectra are fed continuously, so they never flash. Returns false if the widget
+    /// does not exist or is not a spectrum analyzer.
+    pub fn set_spectrum(&mut self, widget_id: usize, magnitudes: &[f32]) -> bool {
+        match self.get_widget_mut(widget_id).map(|w| &mut w.widget_type) {
+            Some(WidgetType::SpectrumAnalyzer { bands, .. }) => {
+                bands.clear();
+                bands.extend(magnitudes.iter().map(|&m| if m.is_finite() { m.clamp(0.0, 1.0) } else { 0.0 }));
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    /// Update the displayed time of a time display widget from the host
+    ///
+    /// The transport clock runs continuously, so time displays never flash.
+    /// Returns false if the widget does not exist or is not a time display.
+    pub fn set_time(&mut self, widget_id: usize, new_seconds: f32) -> bool {
+        match self.get_widget_mut(widget_id).map(|w| &mut w.widget_type) {
+            Some(WidgetType::TimeDisplay { seconds, .. }) => {
+                *seconds = new_seconds;
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    /// Loop start of a region selector, in the same units as its duration
+    pub fn region_start(&self, widget_id: usize) -> Option<f32> {
+        match self.get_widget(widget_id).map(|w| &w.widget_type) {
+            Some(WidgetType::RegionSelector { start, .. }) => Some(*start),
+            _ => None,
+        }
+    }
+    
+    /// Loop end of a region selector, in the same units as its duration
+    pub fn region_end(&self, widget_id: usize) -> Option<f32> {
+        match self.get_widget(widget_id).map(|w| &w.widget_type) {
+            Some(WidgetType::RegionSelector { end, .. }) => Some(*end),
+            _ => None,
+        }
+    }
+    
+    /// IDs of all widgets whose type matches `predicate`, in canvas order
+    ///
+    /// The building block for bulk edits and host automation, e.g.
+    /// `canvas.widgets_of_type(|t| matches!(t, WidgetType::Knob { .. }))`.
+    /// IDs rather than indices are returned so they can be passed straight to
+    /// `set_value` and the other host-side setters.
+    pub fn widgets_of_type(&self, predicate: impl Fn(&WidgetType) -> bool) -> Vec<usize> {
+        self.widgets.iter()
+            .filter(|w| predicate(&w.widget_type))
+            .map(|w| w.id)
+            .collect()
+    }
+    
+    /// IDs of all knobs, in canvas order
+    pub fn all_knobs(&self) -> Vec<usize> {
+        self.widgets_of_type(|t| matches!(t, WidgetType::Knob { .. }))
+    }
+    
+    /// IDs of all horizontal and vertical sliders, in canvas order
+    pub fn all_sliders(&self) -> Vec<usize> {
+        self.widgets_of_type(|t| matches!(t, WidgetType::HorizontalSlider { .. } | WidgetType::VerticalSlider { .. }))
+    }
+    
+    /// Round a widget position to the nearest snap-grid point, which scrolls with the canvas
+    fn snap_to_grid(&self, pos: Pos2) -> Pos2 {
+        let size = self.settings.snap_grid_size.max(1.0);
+        let origin = self.canvas_origin();
+        origin + ((pos - origin) / size).round() * size
+    }
+    
+    /// Origin used for layout serialization (scrolled canvas top-left, or zero before the first render)
+    fn canvas_origin(&self) -> Pos2 {
+        if self.canvas_rect == Rect::NOTHING {
+            Pos2::ZERO
+        } else {
+            self.canvas_rect.min + self.pan_offset
+        }
+    }
+    
+    /// Capture the widgets, id counter, selection and view
+    pub fn snapshot(&self) -> CanvasSnapshot {
+        CanvasSnapshot {
+            widgets: self.widgets.clone(),
+            next_id: self.next_id,
+            view_state: Some(ViewState {
+                selected_panel: self.selected_panel,
+                zoom: self.zoom,
+                zoom_offset: self.zoom_offset,
+                pan_offset: self.pan_offset,
+            }),
+            connections: self.connections.clone(),
+        }
+    }
+    
+    /// Replace the canvas state with a previously captured snapshot
+    ///
+    /// Snapshot positions already include the pan, so the saved view state is
+    /// applied as-is. A missing view state clears the selection and pan.
+    pub fn restore(&mut self, snapshot: CanvasSnapshot) {
+        let view_state = snapshot.view_state.unwrap_or_default();
+        self.widgets = snapshot.widgets;
+        self.connections = snapshot.connections;
+        self.next_id = snapshot.next_id;
+        self.free_ids.clear();
+        self.pan_offset = view_state.pan_offset;
+        self.selected_widget = None;
+        self.selected_widgets.clear();
+        self.changed_widgets.clear();
+        
+        // Only restore a selection that still points at a panel
+        self.selected_panel = view_state.selected_panel.filter(|id| {
+            self.widgets.iter().any(|w| {
+                w.id == *id && matches!(w.widget_type, WidgetType::Panel { .. } | WidgetType::Settings { .. })
+            })
+        });
+        self.reset_interaction_state();
+    }
+    
+    /// Clear transient interaction state that refers to widget indices
+    fn reset_interaction_state(&mut self) {
+        self.editing_widget = None;
+        self.show_edit_window = false;
+        self.dragging_widget = None;
+        self.interacting_widget = None;
+        self.resizing_widget = None;
+        self.last_mouse_pos = None;
+        self.fine_tuning_widget = None;
+        self.fine_tune_press_pos = None;
+        self.fine_tune_last_pos = None;
+        self.region_handle = None;
+        self.glide = None;
+        self.spring_back = None;
+        self.alignment_guides.clear();
+        self.drag_hover_panel = None;
+        self.needs_repositioning = false;
+        self.selection_cycle = None;
+        self.marquee = None;
+        self.connecting = None;
+    }
+    
+    /// Serialize the layout to JSON with positions relative to the canvas origin
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.layout_snapshot())
+    }
+    
+    /// Replace the current widgets with a JSON layout, re-offsetting positions by the current canvas origin
+    ///
+    /// The saved pan is re-applied, so the view scrolls back to where it was.
+    pub fn from_json(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let snapshot: CanvasSnapshot = serde_json::from_str(json)?;
+        self.load_layout_snapshot(snapshot);
+        Ok(())
+    }
+    
+    /// Replace the current widgets with a layout exported by the React app
+    ///
+    /// See `canvas::react_import` for the expected input shape. Unknown widget
+    /// types are skipped with a logged warning instead of failing the import.
+    pub fn from_react_json(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let widgets = react_import::parse_react_layout(json)?;
+        let next_id = widgets.len();
+        self.load_layout_snapshot(CanvasSnapshot { widgets, next_id, view_state: None, connections: Vec::new() });
+        Ok(())
+    }
+    
+    /// Serialize the layout to compact bincode, for embedding in app storage or factory presets
+    ///
+    /// Holds the same data as `to_json`; use JSON for layouts meant to be read or edited by hand.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(&self.layout_snapshot())
+    }
+    
+    /// Replace the current widgets with a layout produced by `to_bytes`
+    pub fn from_bytes(&mut self, bytes: &[u8]) -> Result<(), bincode::Error> {
+        let snapshot: CanvasSnapshot = bincode::deserialize(bytes)?;
+        self.load_layout_snapshot(snapshot);
+        Ok(())
+    }
+    
+    /// Snapshot with widget positions made relative to the canvas origin
+    fn layout_snapshot(&self) -> CanvasSnapshot {
+        let origin = self.canvas_origin().to_vec2();
+        let mut snapshot = self.snapshot();
+        for widget in &mut snapshot.widgets {
+            widget.position -= origin;
+        }
+        snapshot
+    }
+    
+    /// Restore a canvas-relative snapshot, then repair overlaps if enabled
+    fn load_layout_snapshot(&mut self, mut snapshot: CanvasSnapshot) {
+        let saved_pan = snapshot.view_state.as_ref().map_or(Vec2::ZERO, |view| view.pan_offset);
+        self.pan_offset = Vec2::ZERO;
+        
+        // The zoom is only taken from layouts; undo leaves the view where it is
+        self.reset_zoom();
+        if let Some(view) = &snapshot.view_state {
+            self.zoom = view.zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+            self.zoom_offset = view.zoom_offset;
+        }
+        let origin = self.canvas_origin().to_vec2() + saved_pan;
+        for widget in &mut snapshot.widgets {
+            widget.position += origin;
+        }
+        self.restore(snapshot);
+        self.needs_origin_offset = self.canvas_rect == Rect::NOTHING;
+        
+        if self.resolve_overlaps_on_load {
+            if self.canvas_rect == Rect::NOTHING {
+                self.needs_overlap_resolution = true;
+            } else {
+                self.resolve_overlaps();
+            }
+        }
+        self.mark_clean();
+    }
+    
+    /// Nudge overlapping top-level widgets apart
+    ///
+    /// Widgets are visited in z-order; each one that intersects an already
+    /// placed widget is moved with the same overlap search used for panel
+    /// placement. Panels carry their contents along. Returns the number of
+    /// widgets that were moved.
+    pub fn resolve_overlaps(&mut self) -> usize {
+        if self.canvas_rect == Rect::NOTHING {
+            return 0;
+        }
+        
+        let bounds = self.canvas_rect;
+        let mut placed_ids: Vec<usize> = Vec::new();
+        let mut moved = 0;
+        
+        for idx in self.get_canvas_widgets() {
+            let (widget_id, start_pos, widget_size) = {
+                let widget = &self.widgets[idx];
+                (widget.id, widget.position, widget.size)
+            };
+            
+            let mut pos = start_pos;
+            for _ in 0..=placed_ids.len() {
+                if !self.rect_overlaps_widgets(Rect::from_min_size(pos, widget_size), &placed_ids) {
+                    break;
+                }
+                pos = self.find_non_overlapping_position(pos, widget_size, &placed_ids, bounds);
+            }
+            if self.rect_overlaps_widgets(Rect::from_min_size(pos, widget_size), &placed_ids) {
+                pos = self.find_first_available_spot(widget_size, &placed_ids, bounds);
+            }
+            
+            if pos != start_pos {
+                self.move_widget_with_contents(idx, pos - start_pos);
+                moved += 1;
+            }
+            placed_ids.push(widget_id);
+        }
+        
+        moved
+    }
+    
+    /// Check a rect against a set of widgets (by ID) using the 1px placement padding
+    fn rect_overlaps_widgets(&self, rect: Rect, widget_ids: &[usize]) -> bool {
+        widget_ids.iter()
+            .filter_map(|id| self.widgets.iter().find(|w| w.id == *id))
+            .any(|w| w.get_rect().expand(1.0).intersects(rect))
+    }
+    
+    /// Move a widget and, for panels, everything nested inside it
+    fn move_widget_with_contents(&mut self, widget_idx: usize, delta: Vec2) {
+        let mut pending = vec![widget_idx];
+        let mut visited = std::collections::HashSet::new();
+        
+        while let Some(idx) = pending.pop() {
+            if !visited.insert(idx) {
+                continue;
+            }
+            let Some(widget) = self.widgets.get_mut(idx) else {
+                continue;
+            };
+            widget.position += delta;
+            
+            if let WidgetType::Panel { contained_widgets, .. } | WidgetType::Settings { contained_widgets, .. } = &widget.widget_type {
+                let children = contained_widgets.clone();
+                pending.extend(children.iter().filter_map(|id| self.widgets.iter().position(|w| w.id == *id)));
+            }
+        }
+    }
+    
+    /// Serialize the layout (widgets, panel contents, next ID and selected panel) to JSON
+    ///
+    /// Same as `to_json`; pair with `load_layout` to round-trip a layout.
+    pub fn save_layout(&self) -> Result<String, serde_json::Error> {
+        self.to_json()
+    }
+    
+    /// Replace the widgets with a layout produced by `save_layout`
+    pub fn load_layout(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        self.from_json(json)
+    }
+    
+    /// Render the canvas content to a PNG image at canvas resolution
+    ///
+    /// Only the widgets are drawn, on the theme background: no palette, selection,
+    /// hover or drag highlights, guides or links. Widgets hidden in collapsed panels
+    /// stay hidden. Fails before the canvas has been laid out once.
+    pub fn export_png(&self, ctx: &egui::Context) -> Result<Vec<u8>, image::ImageError> {
+        self.export_png_scaled(ctx, 1.0)
+    }
+    
+    /// Render the canvas content to a PNG image with `scale` pixels per canvas point, e.g. 2.0 for high-DPI
+    ///
+    /// The widgets are drawn into an offscreen egui context with `ctx`'s fonts and
+    /// style, and rasterized in software (see `canvas::export`). Meter peaks and
+    /// other render-time state are drawn from a copy, so exporting changes nothing.
+    pub fn export_png_scaled(&self, ctx: &egui::Context, scale: f32) -> Result<Vec<u8>, image::ImageError> {
+        let region = self.canvas_rect;
+        let size = (region.size() * scale).round();
+        if !(region.is_positive() && size.x >= 1.0 && size.y >= 1.0) {
+            return Err(image::ImageError::Parameter(image::error::ParameterError::from_kind(
+                image::error::ParameterErrorKind::DimensionMismatch,
+            )));
+        }
+        
+        let offscreen = egui::Context::default();
+        offscreen.set_fonts(ctx.fonts(|fonts| fonts.lock().fonts.definitions().clone()));
+        offscreen.set_style(ctx.style());
+        let mut input = egui::RawInput { screen_rect: Some(region), ..Default::default() };
+        input.viewports.entry(egui::ViewportId::ROOT).or_default().native_pixels_per_point = Some(scale);
+        
+        let mut widgets = self.widgets.clone();
+        let order: Vec<usize> = self.render_order().into_iter()
+            .filter(|&idx| !self.is_widget_in_minimized_panel(self.widgets[idx].id))
+            .collect();
+        let output = offscreen.run(input, |ctx| {
+            egui::CentralPanel::default().frame(egui::Frame::NONE).show(ctx, |ui| {
+                for &idx in &order {
+                    Self::paint_widget(ui, &mut widgets[idx], false, &self.custom_renderers);
+                }
+            });
+        });
+        
+        let primitives = offscreen.tessellate(output.shapes, output.pixels_per_point);
+        let image = export::rasterize(
+            &primitives,
+            &output.textures_delta.set,
+            region.min,
+            output.pixels_per_point,
+            [size.x as usize, size.y as usize],
+            self.settings.theme.background_color(),
+        );
+        export::encode_png(&image)
+    }
+    
+    /// The PNG made by the palette's Export PNG button, if there is a new one
+    ///
+    /// The canvas has no file access of its own; hosts poll this and save the
+    /// bytes wherever suits them. Cleared by the call.
+    pub fn take_exported_png(&mut self) -> Option<Vec<u8>> {
+        self.exported_png.take()
+    }
+    
+    /// Return every playable control to its neutral value, keeping the layout
+    ///
+    /// Knobs and sliders go to their default value (the middle of their range
+    /// unless set in the edit window), toggles switch off and buttons release. Widgets that change are reported through take_changed().
+    /// The previous state is pushed onto the undo stack, so undo() brings the
+    /// values back.
+    pub fn reset_all_values(&mut self) {
+        self.push_undo();
+        for widget in &mut self.widgets {
+            let widget_type = &mut widget.widget_type;
+            let changed = if let Some(neutral) = widget_type.default_value() {
+                widget_type.value_mut().is_some_and(|value| std::mem::replace(value, neutral) != neutral)
+            } else {
+                widget_type.switch_state_mut().is_some_and(std::mem::take)
+            };
+            if changed {
+                self.changed_widgets.insert(widget.id);
+                CanvasEvent::queue_change(&mut self.events, widget);
+            }
+        }
+    }
+    
+    /// Remember the current state so the next undo() returns to it
+    fn push_undo(&mut self) {
+        self.push_undo_snapshot(self.snapshot());
+    }
+    
+    /// Remember a state taken before a command, once the command has turned out to change something
+    fn push_undo_snapshot(&mut self, snapshot: CanvasSnapshot) {
+        self.nudge_burst = None; // Any other undoable command ends a nudge burst
+        if self.undo_stack.len() >= UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(snapshot);
+    }
+    
+    /// Step back to the state before the last undoable command
+    ///
+    /// Every restored widget is reported through take_changed() so the host can
+    /// re-read its values. Returns false if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.undo_stack.pop() else {
+            return false;
+        };
+        self.nudge_burst = None;
+        let (selected_widget, mut selected_widgets) = (self.selected_widget, std::mem::take(&mut self.selected_widgets));
+        let previous = self.widgets.clone();
+        self.restore(snapshot);
+        self.selected_widget = selected_widget.filter(|id| self.widgets.iter().any(|w| w.id == *id));
+        selected_widgets.retain(|id| self.widgets.iter().any(|w| w.id == *id));
+        self.selected_widgets = selected_widgets;
+        self.changed_widgets.extend(self.widgets.iter().map(|w| w.id));
+        
+        // Events only for what the undo actually changed
+        for old in previous.iter().filter(|old| !self.widgets.iter().any(|w| w.id == old.id)) {
+            CanvasEvent::queue(&mut self.events, CanvasEvent::WidgetDeleted { id: old.id });
+        }
+        for widget in &self.widgets {
+            match previous.iter().find(|old| old.id == widget.id) {
+                Some(old) if CanvasEvent::for_state(old) != CanvasEvent::for_state(widget) => CanvasEvent::queue_change(&mut self.events, widget),
+                Some(_) => {}
+                None => CanvasEvent::queue(&mut self.events, CanvasEvent::WidgetAdded { id: widget.id }),
+            }
+        }
+        true
+    }
+    
+    /// Whether the layout differs from the one last saved or loaded
+    ///
+    /// Compares widgets, values and placement; scrolling the view and the
+    /// readings the host feeds to meters, readouts and time displays do not
+    /// count as changes. A canvas that was never saved or loaded is dirty as
+    /// soon as it holds a widget.
+    pub fn is_dirty(&self) -> bool {
+        match &self.clean_layout {
+            Some(clean) => *clean != self.layout_fingerprint(),
+            None => !self.widgets.is_empty(),
+        }
+    }
+    
+    /// Record the current layout as saved; loading a layout does this automatically
+    pub fn mark_clean(&mut self) {
+        self.clean_layout = Some(self.layout_fingerprint());
+    }
+    
+    /// Treat the current layout as never saved, e.g. after restoring auto-saved work
+    pub fn mark_dirty(&mut self) {
+        self.clean_layout = None;
+    }
+    
+    /// Widgets serialized with the view scroll and host-fed readings taken out
+    fn layout_fingerprint(&self) -> String {
+        let mut widgets = self.widgets.clone();
+        for widget in &mut widgets {
+            widget.position -= self.pan_offset;
+            match &mut widget.widget_type {
+                WidgetType::VuMeter { level, peak_level, .. } => (*level, *peak_level) = (0.0, 0.0),
+                WidgetType::LevelIndicator { level, .. } => *level = 0.0,
+                WidgetType::StatusBar { cpu, ram, latency, online, .. } => {
+                    (*cpu, *ram, *latency, *online) = (0.0, 0.0, 0.0, false);
+                }
+                WidgetType::Readout { value, .. } => *value = 0.0,
+                WidgetType::MeterWithReadout { level, peak, .. } => (*level, *peak) = (0.0, 0.0),
+                WidgetType::SpectrumAnalyzer { bands, peaks, .. } => {
+                    // The host's feed sets the band count too
+                    bands.clear();
+                    peaks.clear();
+                }
+                WidgetType::TimeDisplay { seconds, .. } => *seconds = 0.0,
+                _ => {}
+            }
+        }
+        serde_json::to_string(&(widgets, &self.connections)).unwrap_or_default()
+    }
+    
+    /// Serialize the selected widget, and for panels everything inside it, for paste()
+    ///
+    /// Positions are relative to the canvas origin. Returns None if nothing is selected.
+    pub fn copy_selected(&self) -> Option<String> {
+        serde_json::to_string(&self.selected_clip()?).ok()
+    }
+    
+    /// Copy the selected widget (with a panel's contents) to the canvas's own clipboard
+    ///
+    /// Unlike copy_selected() this needs no system clipboard. Returns false,
+    /// leaving the clipboard as it was, if nothing is selected.
+    pub fn copy_widget(&mut self) -> bool {
+        let Some(clip) = self.selected_clip() else {
+            return false;
+        };
+        self.clipboard = Some(clip);
+        true
+    }
+    
+    /// Paste the widgets from copy_widget(), as paste() does; a no-op returning false if nothing was copied
+    pub fn paste_widget(&mut self, at: Option<Pos2>) -> bool {
+        match self.clipboard.clone() {
+            Some(clip) => self.paste_clip(clip, at),
+            None => false,
+        }
+    }
+    
+    /// Take the widgets held by copy_widget(), leaving the canvas's clipboard empty
+    ///
+    /// With set_clipboard() this lets a host share one clipboard between
+    /// several canvases, e.g. the pages of `CanvasTabs`.
+    pub fn take_clipboard(&mut self) -> Option<CanvasSnapshot> {
+        self.clipboard.take()
+    }
+    
+    /// Replace the canvas's clipboard, e.g. with one taken from another canvas
+    pub fn set_clipboard(&mut self, clip: Option<CanvasSnapshot>) {
+        self.clipboard = clip;
+    }
+    
+    /// The selected widget and everything nested in it, positioned relative to the canvas origin
+    fn selected_clip(&self) -> Option<CanvasSnapshot> {
+        let root = self.widgets.iter().find(|w| Some(w.id) == self.selected_widget)?;
+        let origin = self.canvas_origin().to_vec2();
+        let mut widgets = vec![root.clone()];
+        widgets.extend(
+            self.contained_widgets_recursive(root.id).into_iter()
+                .filter_map(|id| self.widgets.iter().find(|w| w.id == id).cloned()),
+        );
+        for widget in &mut widgets {
+            widget.position -= origin;
+        }
+        Some(CanvasSnapshot { widgets, next_id: 0, view_state: None, connections: Vec::new() })
+    }
+    
+    /// Add the widgets from copy_selected() to the canvas under fresh IDs
+    ///
+    /// With `at`, the copied widget's top-left lands there, inside the panel
+    /// under `at` if there is one; without it, the copy goes back to its
+    /// original position on the canvas. Either way the copy is then nudged off
+    /// any widget it would cover, so an in-place paste is never hidden behind
+    /// its original. A copied panel brings independent copies of its contents.
+    /// The copy becomes the selected widget. Undoable. Returns false if `json`
+    /// is not copied widgets.
+    pub fn paste(&mut self, json: &str, at: Option<Pos2>) -> bool {
+        match serde_json::from_str::<CanvasSnapshot>(json) {
+            Ok(clip) => self.paste_clip(clip, at),
+            Err(_) => false,
+        }
+    }
+    
+    fn paste_clip(&mut self, clip: CanvasSnapshot, at: Option<Pos2>) -> bool {
+        let Some(root_position) = clip.widgets.first().map(|w| w.position) else {
+            return false;
+        };
+        self.push_undo();
+        
+        let offset = match at {
+            Some(pos) => pos - root_position,
+            None => self.canvas_origin().to_vec2(),
+        };
+        let new_ids: HashMap<usize, usize> = clip.widgets.iter()
+            .map(|w| (w.id, self.allocate_id()))
+            .collect();
+        
+        let first_idx = self.widgets.len();
+        for mut widget in clip.widgets {
+            widget.id = new_ids[&widget.id];
+            widget.position += offset;
+            if let WidgetType::Panel { contained_widgets, .. } | WidgetType::Settings { contained_widgets, .. } = &mut widget.widget_type {
+                *contained_widgets = contained_widgets.iter().filter_map(|id| new_ids.get(id).copied()).collect();
+            }
+            widget.linked_to = widget.linked_to.iter().filter_map(|id| new_ids.get(id).copied()).collect();
+            // Names stay unique: a name already on the canvas is dropped from the copy
+            if widget.name.as_deref().is_some_and(|name| self.find_by_name(name).is_some()) {
+                widget.name = None;
+            }
+            CanvasEvent::queue(&mut self.events, CanvasEvent::WidgetAdded { id: widget.id });
+            self.widgets.push(widget);
+        }
+        
+        // Pasting over an open panel puts the copy inside it, like a palette drop
+        let root_id = self.widgets[first_idx].id;
+        let copied_ids: Vec<usize> = new_ids.values().copied().collect();
+        let target_panel = at
+            .and_then(|pos| self.render_order().into_iter().rev().find(|&idx| {
+                let panel = &self.widgets[idx];
+                !copied_ids.contains(&panel.id) && PanelManager::is_panel_accepting_widgets(panel) && panel.get_rect().contains(pos)
+            }));
+        if let Some(panel_idx) = target_panel {
+            let panel_id = self.widgets[panel_idx].id;
+            let (start_pos, size) = (self.widgets[first_idx].position, self.widgets[first_idx].size);
+            let pos = self.constrain_widget_to_panel(start_pos, size, panel_id);
+            let siblings = self.contained_widgets_recursive(panel_id);
+            let pos = self.find_non_overlapping_position(pos, size, &siblings, self.widgets[panel_idx].get_rect());
+            self.move_widget_with_contents(first_idx, pos - start_pos);
+            PanelManager::add_widget_to_panel(&mut self.widgets, panel_idx, root_id);
+        } else if self.canvas_rect != Rect::NOTHING {
+            // Keep the copy off the widgets already on the canvas
+            let others: Vec<usize> = self.get_canvas_widgets().into_iter()
+                .map(|idx| self.widgets[idx].id)
+                .filter(|id| *id != root_id)
+                .collect();
+            let (start_pos, size) = (self.widgets[first_idx].position, self.widgets[first_idx].size);
+            let mut pos = start_pos;
+            if self.rect_overlaps_widgets(Rect::from_min_size(pos, size), &others) {
+                pos = self.find_non_overlapping_position(pos, size, &others, self.canvas_rect);
+                if self.rect_overlaps_widgets(Rect::from_min_size(pos, size), &others) {
+                    pos = self.find_first_available_spot(size, &others, self.canvas_rect);
+                }
+            }
+            self.move_widget_with_contents(first_idx, pos - start_pos);
+        }
+        
+        self.selected_widget = Some(self.widgets[first_idx].id);
+        true
+    }
+    
+    /// Copy a widget 20pt down and to the right of the original, in the same panel, and select the copy
+    ///
+    /// Size, format and exclusive group come along; links, the name and a panel's
+    /// contents do not (use copy_selected/paste to bring a panel's contents). The copy is
+    /// nudged off its siblings if it would overlap one. Returns false if `idx`
+    /// is out of range.
+    pub fn duplicate_widget(&mut self, idx: usize) -> bool {
+        let Some(original) = self.widgets.get(idx) else {
+            return false;
+        };
+        let mut copy = original.clone();
+        let container = self.container_of(original.id);
+        self.push_undo();
+        
+        copy.id = self.allocate_id();
+        copy.linked_to.clear();
+        copy.name = None;
+        if let WidgetType::Panel { contained_widgets, .. } | WidgetType::Settings { contained_widgets, .. } = &mut copy.widget_type {
+            contained_widgets.clear();
+        }
+        
+        let (siblings, bounds) = match container.and_then(|panel_id| self.widgets.iter().find(|w| w.id == panel_id)) {
+            Some(panel) => (self.contained_widgets_recursive(panel.id), panel.get_rect()),
+            None => (self.get_canvas_widgets().into_iter().map(|i| self.widgets[i].id).collect(), self.canvas_rect),
+        };
+        copy.position = self.find_non_overlapping_position(copy.position + Vec2::splat(20.0), copy.size, &siblings, bounds);
+        
+        let copy_id = copy.id;
+        CanvasEvent::queue(&mut self.events, CanvasEvent::WidgetAdded { id: copy.id });
+        self.widgets.push(copy);
+        if let Some(panel_idx) = container.and_then(|panel_id| self.widgets.iter().position(|w| w.id == panel_id)) {
+            PanelManager::add_widget_to_panel(&mut self.widgets, panel_idx, copy_id);
+        }
+        self.selected_widget = Some(copy_id);
+        self.selected_widgets.clear();
+        true
+    }
+    
+    /// Remove every widget and reset the view, as one undo step
+    ///
+    /// Selections, pending changes and any drag or edit in progress go too,
+    /// since with recycled IDs they would otherwise land on new widgets.
+    pub fn clear_canvas(&mut self) {
+        if !self.widgets.is_empty() {
+            self.push_undo();
+        }
+        for widget in &self.widgets {
+            CanvasEvent::queue(&mut self.events, CanvasEvent::WidgetDeleted { id: widget.id });
+        }
+        self.widgets.clear();
+        self.connections.clear();
+        self.selected_widget = None;
+        self.selected_widgets.clear();
+        self.selected_panel = None;
+        self.context_menu = None;
+        self.changed_widgets.clear();
+        self.flashes.clear();
+        self.rejected_drop = None;
+        self.reset_interaction_state();
+        self.free_ids.clear();
+        if self.settings.recycle_ids {
+            self.next_id = 0; // Nothing is left to collide with
+        }
+        self.pan_offset = Vec2::ZERO;
+        self.reset_zoom();
+        println!("🗑️ Canvas cleared!");
+    }
+    
+    
+    // Legacy drop logic removed
+    
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn knob(label: &str) -> WidgetType {
+        WidgetType::Knob { value: 50.0, min: 0.0, max: 100.0, label: label.to_string(), color: WidgetColor::Cyan, default_value: None }
+    }
+    
+    fn panel() -> WidgetType {
+        WidgetType::Panel {
+            title: "PANEL".to_string(),
+            color: WidgetColor::Cyan,
+            width: 300.0,
+            height: 200.0,
+            collapsed: false,
+            contained_widgets: Vec::new(),
+            minimize_to_settings_icon: false,
+            grid_layout: false,
+        }
+    }
+    
+    /// Empty canvas laid out at `rect`, as if it had been rendered there
+    fn canvas_at(rect: Rect) -> DragDropCanvas {
+        let mut canvas = DragDropCanvas::new();
+        canvas.canvas_rect = rect;
+        canvas
+    }
+    
+    /// Put a widget exactly at `pos`, bypassing auto-placement, and return its ID
+    fn place(canvas: &mut DragDropCanvas, widget_type: WidgetType, pos: Pos2) -> usize {
+        let id = canvas.next_id;
+        canvas.next_id += 1;
+        canvas.widgets.push(DraggableWidget::new(id, widget_type, pos));
+        id
+    }
+    
+    fn position_of(canvas: &DragDropCanvas, id: usize) -> Pos2 {
+        canvas.widgets.iter().find(|w| w.id == id).unwrap().position
+    }
+    
+    #[test]
+    fn layout_round_trips_between_canvas_origins() {
+        let mut saved = canvas_at(Rect::from_min_size(Pos2::new(260.0, 68.0), Vec2::new(900.0, 700.0)));
+        let knob_id = place(&mut saved, knob("GAIN"), Pos2::new(400.0, 200.0));
+        let panel_id = place(&mut saved, panel(), Pos2::new(600.0, 300.0));
+        let json = saved.to_json().unwrap();
+        
+        // Wider palette and a taller header put the other canvas somewhere else on screen
+        let mut loaded = canvas_at(Rect::from_min_size(Pos2::new(8.0, 140.0), Vec2::new(1400.0, 900.0)));
+        loaded.from_json(&json).unwrap();
+        for id in [knob_id, panel_id] {
+            let before = position_of(&saved, id) - saved.canvas_rect.min;
+            let after = position_of(&loaded, id) - loaded.canvas_rect.min;
+            assert_eq!(after, before);
+        }
+        
+        // And back again, byte for byte
+        assert_eq!(loaded.to_json().unwrap(), json);
+    }
+    
+    #[test]
+    fn loading_an_overlapping_layout_leaves_no_top_level_overlaps() {
+        let mut source = canvas_at(Rect::from_min_size(Pos2::ZERO, Vec2::new(1000.0, 800.0)));
+        for i in 0..5 {
+            place(&mut source, knob("PILE"), Pos2::new(100.0 + 10.0 * i as f32, 100.0 + 5.0 * i as f32));
+        }
+        let panel_id = place(&mut source, panel(), Pos2::new(120.0, 90.0));
+        let child_id = place(&mut source, knob("CHILD"), Pos2::new(140.0, 140.0));
+        let panel_idx = source.widgets.iter().position(|w| w.id == panel_id).unwrap();
+        PanelManager::add_widget_to_panel(&mut source.widgets, panel_idx, child_id);
+        
+        let mut canvas = canvas_at(source.canvas_rect);
+        assert!(canvas.resolve_overlaps_on_load);
+        canvas.from_json(&source.to_json().unwrap()).unwrap();
+        
+        let rects: Vec<Rect> = canvas.get_canvas_widgets().into_iter().map(|idx| canvas.widgets[idx].get_rect()).collect();
+        assert_eq!(rects.len(), 6);
+        for (i, a) in rects.iter().enumerate() {
+            for b in &rects[i + 1..] {
+                assert!(!a.intersects(*b), "{a:?} overlaps {b:?}");
+            }
+            assert!(canvas.canvas_rect.contains_rect(*a), "{a:?} pushed off the canvas");
+        }
+        // The panel's contents moved with it
+        let panel_rect = canvas.widgets.iter().find(|w| w.id == panel_id).unwrap().get_rect();
+        assert!(panel_rect.contains(position_of(&canvas, child_id)));
+    }
+    
+    #[test]
+    fn panning_leaves_the_settings_gear_and_saved_positions_alone() {
+        let mut canvas = canvas_at(Rect::from_min_size(Pos2::new(200.0, 100.0), Vec2::new(800.0, 600.0)));
+        place(&mut canvas, knob("TOP"), Pos2::new(300.0, 150.0));
+        let far = place(&mut canvas, knob("FAR"), Pos2::new(300.0, 1400.0)); // Below the visible canvas
+        let gear = canvas.settings_icon_rect();
+        let relative = position_of(&canvas, far) - canvas.canvas_origin();
+        
+        canvas.pan_view(Vec2::new(0.0, -300.0));
+        assert_ne!(canvas.pan_offset, Vec2::ZERO);
+        assert_eq!(position_of(&canvas, far).y, 1100.0);
+        assert_eq!(canvas.settings_icon_rect(), gear);
+        // Layouts are relative to canvas_origin(), which scrolls with the widgets
+        assert_eq!(position_of(&canvas, far) - canvas.canvas_origin(), relative);
+    }
+    
+    fn toggle(on: bool) -> WidgetType {
+        WidgetType::ToggleSwitch { on, label: "SOLO".to_string(), color: WidgetColor::Cyan, glow: true }
+    }
+    
+    #[test]
+    fn binary_layout_round_trips() {
+        let mut saved = canvas_at(Rect::from_min_size(Pos2::new(260.0, 68.0), Vec2::new(900.0, 700.0)));
+        let knob_id = place(&mut saved, knob("GAIN"), Pos2::new(400.0, 200.0));
+        if let WidgetType::Knob { value, .. } = &mut saved.widgets[knob_id].widget_type {
+            *value = 72.5;
+        }
+        let panel_id = place(&mut saved, panel(), Pos2::new(600.0, 300.0));
+        let toggle_id = place(&mut saved, toggle(true), Pos2::new(620.0, 350.0));
+        let panel_idx = saved.widgets.iter().position(|w| w.id == panel_id).unwrap();
+        PanelManager::add_widget_to_panel(&mut saved.widgets, panel_idx, toggle_id);
+        
+        let bytes = saved.to_bytes().unwrap();
+        let mut loaded = canvas_at(Rect::from_min_size(Pos2::new(8.0, 140.0), Vec2::new(1400.0, 900.0)));
+        loaded.from_bytes(&bytes).unwrap();
+        
+        // Same layout as JSON, which is relative to each canvas
+        assert_eq!(loaded.to_json().unwrap(), saved.to_json().unwrap());
+        assert_eq!(loaded.to_bytes().unwrap(), bytes);
+        assert!(bytes.len() < saved.to_json().unwrap().len());
+        assert!(loaded.from_bytes(&bytes[..bytes.len() / 2]).is_err());
+    }
+    
+    #[test]
+    fn contained_widgets_recursive_walks_two_levels_of_nesting() {
+        let mut canvas = canvas_at(Rect::from_min_size(Pos2::ZERO, Vec2::new(1000.0, 800.0)));
+        let outer = place(&mut canvas, panel(), Pos2::new(50.0, 50.0));
+        let outer_knob = place(&mut canvas, knob("OUTER"), Pos2::new(60.0, 90.0));
+        let inner = place(&mut canvas, panel(), Pos2::new(100.0, 100.0));
+        let inner_knob = place(&mut canvas, knob("INNER"), Pos2::new(110.0, 140.0));
+        let outside = place(&mut canvas, knob("OUTSIDE"), Pos2::new(600.0, 50.0));
+        let nest = |canvas: &mut DragDropCanvas, panel_id: usize, child_id: usize| {
+            let panel_idx = canvas.widgets.iter().position(|w| w.id == panel_id).unwrap();
+            assert!(PanelManager::add_widget_to_panel(&mut canvas.widgets, panel_idx, child_id));
+        };
+        nest(&mut canvas, outer, outer_knob);
+        nest(&mut canvas, outer, inner);
+        nest(&mut canvas, inner, inner_knob);
+        
+        assert_eq!(canvas.contained_widgets_recursive(outer), [outer_knob, inner, inner_knob]);
+        assert_eq!(canvas.contained_widgets_recursive(inner), [inner_knob]);
+        assert!(canvas.contained_widgets_recursive(outside).is_empty());
+        
+        // A corrupt cycle is walked once instead of forever
+        nest(&mut canvas, inner, outer);
+        assert_eq!(canvas.contained_widgets_recursive(outer), [outer_knob, inner, inner_knob]);
+    }
+    
+    /// Run one frame of the canvas in a window of `window` points with the given input events
+    fn run_frame(ctx: &egui::Context, canvas: &mut DragDropCanvas, window: Vec2, events: Vec<egui::Event>) {
+        let input = egui::RawInput { screen_rect: Some(Rect::from_min_size(Pos2::ZERO, window)), events, ..Default::default() };
+        let _ = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| canvas.render(ui));
+        });
+    }
+    
+    fn primary_button(pos: Pos2, pressed: bool) -> egui::Event {
+        egui::Event::PointerButton { pos, button: egui::PointerButton::Primary, pressed, modifiers: egui::Modifiers::NONE }
+    }
+    
+    #[test]
+    fn canvas_resize_leaves_the_widget_under_the_pointer_alone() {
+        for policy in [ResizePolicy::Regrid, ResizePolicy::Proportional] {
+            let ctx = egui::Context::default();
+            let mut canvas = DragDropCanvas::new();
+            canvas.settings.resize_policy = policy;
+            let window = Vec2::new(1200.0, 800.0);
+            run_frame(&ctx, &mut canvas, window, Vec::new());
+            let origin = canvas.canvas_rect.min;
+            let held = place(&mut canvas, knob("HELD"), origin + Vec2::new(700.0, 500.0));
+            let other = place(&mut canvas, knob("OTHER"), origin + Vec2::new(900.0, 600.0));
+            run_frame(&ctx, &mut canvas, window, Vec::new());
+            
+            // Grab the held knob's dial, which turns it rather than dragging it
+            let widget = canvas.widgets.iter().find(|w| w.id == held).unwrap();
+            let grab = widget.position + Vec2::new(widget.size.x / 2.0, 37.0);
+            run_frame(&ctx, &mut canvas, window, vec![egui::Event::PointerMoved(grab)]);
+            run_frame(&ctx, &mut canvas, window, vec![primary_button(grab, true)]);
+            assert_eq!(canvas.interacting_widget.map(|idx| canvas.widgets[idx].id), Some(held));
+            let (held_at, other_at) = (position_of(&canvas, held), position_of(&canvas, other));
+            
+            // The window shrinks while the button is still down
+            run_frame(&ctx, &mut canvas, Vec2::new(700.0, 500.0), Vec::new());
+            assert_eq!(position_of(&canvas, held), held_at, "{policy:?} yanked the held knob");
+            assert_ne!(position_of(&canvas, other), other_at, "{policy:?} didn't reflow at all");
+            assert_eq!(canvas.interacting_widget.map(|idx| canvas.widgets[idx].id), Some(held));
+        }
+    }
+    
+    #[test]
+    fn edit_window_follows_its_widget_by_id_and_closes_when_it_is_removed() {
+        let ctx = egui::Context::default();
+        let window = Vec2::new(1200.0, 800.0);
+        let mut canvas = DragDropCanvas::new();
+        run_frame(&ctx, &mut canvas, window, Vec::new());
+        let origin = canvas.canvas_rect.min;
+        place(&mut canvas, knob("FIRST"), origin + Vec2::new(100.0, 100.0));
+        let second = place(&mut canvas, knob("SECOND"), origin + Vec2::new(300.0, 100.0));
+        let third = place(&mut canvas, knob("THIRD"), origin + Vec2::new(500.0, 100.0));
+        
+        // Removing a widget in front of the edited one shifts its index, not its edit window
+        canvas.editing_widget = Some(third);
+        canvas.show_edit_window = true;
+        canvas.widgets.remove(0);
+        run_frame(&ctx, &mut canvas, window, Vec::new());
+        assert!(canvas.show_edit_window);
+        assert_eq!(canvas.editing_widget, Some(third));
+        
+        // Removing the edited widget itself, as a host could, closes the window
+        let before: Vec<(usize, Pos2)> = canvas.widgets.iter().map(|w| (w.id, w.position)).collect();
+        canvas.editing_widget = Some(second);
+        let idx = canvas.widgets.iter().position(|w| w.id == second).unwrap();
+        canvas.widgets.remove(idx);
+        run_frame(&ctx, &mut canvas, window, Vec::new());
+        assert!(!canvas.show_edit_window);
+        assert_eq!(canvas.editing_widget, None);
+        let after: Vec<(usize, Pos2)> = canvas.widgets.iter().map(|w| (w.id, w.position)).collect();
+        assert_eq!(after, before.into_iter().filter(|&(id, _)| id != second).collect::<Vec<_>>());
+    }
+    
+    #[test]
+    fn collapsed_panel_has_no_resize_handle_to_grab() {
+        let window = Vec2::new(1200.0, 800.0);
+        for collapsed in [false, true] {
+            let ctx = egui::Context::default();
+            let mut canvas = DragDropCanvas::new();
+            run_frame(&ctx, &mut canvas, window, Vec::new());
+            let corner = canvas.canvas_rect.min + Vec2::new(100.0, 100.0);
+            let panel_id = place(&mut canvas, panel(), corner);
+            let panel_idx = canvas.widgets.iter().position(|w| w.id == panel_id).unwrap();
+            if let WidgetType::Panel { collapsed: panel_collapsed, .. } = &mut canvas.widgets[panel_idx].widget_type {
+                *panel_collapsed = collapsed;
+            }
+            canvas.widgets[panel_idx].size.y = if collapsed { 40.0 } else { 200.0 };
+            run_frame(&ctx, &mut canvas, window, Vec::new());
+            
+            // Where the grip sits on the panel as drawn: its bottom-right corner
+            let grip = crate::canvas::widgets::rendering::resize_handle_rect(canvas.widgets[panel_idx].get_rect()).center();
+            run_frame(&ctx, &mut canvas, window, vec![egui::Event::PointerMoved(grip)]);
+            run_frame(&ctx, &mut canvas, window, vec![primary_button(grip, true)]);
+            run_frame(&ctx, &mut canvas, window, vec![egui::Event::PointerMoved(grip + Vec2::new(60.0, 60.0))]);
+            
+            let resizing = canvas.resizing_widget.map(|idx| canvas.widgets[idx].id);
+            if collapsed {
+                assert_eq!(resizing, None);
+                assert_eq!(canvas.widgets[panel_idx].size.y, 40.0);
+            } else {
+                assert_eq!(resizing, Some(panel_id), "an expanded panel's grip does start a resize");
+            }
+        }
+    }
+    
+    /// Lay the canvas out in a window of `window` points, as one frame would
+    fn render_in(canvas: &mut DragDropCanvas, window: Vec2) {
+        run_frame(&egui::Context::default(), canvas, window, Vec::new());
+    }
+    
+    fn assert_near(a: Pos2, b: Pos2) {
+        assert!((a - b).length() < 1e-3, "{a:?} != {b:?}");
+    }
+    
+    /// Points spread over and just outside the canvas, in canvas coordinates
+    fn sample_points(canvas: &DragDropCanvas) -> Vec<Pos2> {
+        let rect = canvas.canvas_rect.expand(50.0);
+        (0..=4).flat_map(|i| (0..=4).map(move |j| rect.lerp_inside(Vec2::new(i as f32 / 4.0, j as f32 / 4.0)))).collect()
+    }
+    
+    fn assert_round_trips(canvas: &DragDropCanvas) {
+        for point in sample_points(canvas) {
+            assert_near(canvas.screen_to_canvas(canvas.canvas_to_screen(point)), point);
+            assert_near(canvas.canvas_to_screen(canvas.screen_to_canvas(point)), point);
+        }
+    }
+    
+    #[test]
+    fn design_size_letterboxes_and_round_trips() {
+        let design = Vec2::new(1280.0, 800.0);
+        // Wider than the design (bars left and right), taller (bars above and below), and exact
+        for window in [Vec2::new(1600.0, 800.0), Vec2::new(900.0, 1200.0), design + Vec2::new(16.0, 76.0)] {
+            let mut canvas = DragDropCanvas::new();
+            canvas.settings.design_size = Some(design);
+            render_in(&mut canvas, window);
+            assert_eq!(canvas.canvas_rect.size(), design);
+            
+            // The area left by the central panel's 8pt margin and the 60pt canvas header
+            let area = Rect::from_min_max(Pos2::new(8.0, 68.0), (window - Vec2::splat(8.0)).to_pos2());
+            let shown = Rect::from_min_max(canvas.canvas_to_screen(canvas.canvas_rect.min), canvas.canvas_to_screen(canvas.canvas_rect.max));
+            assert!((shown.aspect_ratio() - design.x / design.y).abs() < 1e-3);
+            assert_near(shown.center(), area.center());
+            assert!((shown.width() - area.width()).abs() < 1e-3 || (shown.height() - area.height()).abs() < 1e-3);
+            assert!(area.expand(1e-3).contains_rect(shown));
+            assert_round_trips(&canvas);
+        }
+    }
+    
+    /// Add a widget the way the palette does, IDs and all, at `pos` (free placement) and return its ID
+    fn add(canvas: &mut DragDropCanvas, widget_type: WidgetType, pos: Pos2) -> usize {
+        canvas.settings.free_placement = true;
+        canvas.add_widget(widget_type, pos);
+        canvas.widgets.last().unwrap().id
+    }
+    
+    #[test]
+    fn clear_canvas_forgets_ids_that_recycling_would_reuse() {
+        let mut canvas = canvas_at(Rect::from_min_size(Pos2::ZERO, Vec2::new(1000.0, 800.0)));
+        canvas.settings.recycle_ids = true;
+        let panel_id = add(&mut canvas, panel(), Pos2::new(50.0, 50.0));
+        let knob_id = add(&mut canvas, knob("OLD"), Pos2::new(500.0, 50.0));
+        canvas.selected_panel = Some(panel_id);
+        canvas.selected_widget = Some(knob_id);
+        canvas.selected_widgets = vec![panel_id, knob_id];
+        canvas.editing_widget = Some(knob_id);
+        canvas.show_edit_window = true;
+        canvas.changed_widgets.insert(knob_id);
+        
+        canvas.clear_canvas();
+        let fresh = [add(&mut canvas, knob("NEW"), Pos2::new(50.0, 50.0)), add(&mut canvas, knob("NEW"), Pos2::new(500.0, 50.0))];
+        assert!(fresh.contains(&panel_id) && fresh.contains(&knob_id));
+        assert_eq!((canvas.selected_widget, canvas.selected_panel, canvas.editing_widget), (None, None, None));
+        assert!(canvas.selected_widgets.is_empty() && !canvas.show_edit_window);
+        assert!(canvas.take_changed().is_empty());
+    }
+    
+    #[test]
+    fn deleted_widget_leaves_no_change_or_flash_for_its_recycled_id() {
+        let mut canvas = canvas_at(Rect::from_min_size(Pos2::ZERO, Vec2::new(1000.0, 800.0)));
+        canvas.settings.recycle_ids = true;
+        canvas.settings.flash_external_changes = true;
+        let old = add(&mut canvas, knob("OLD"), Pos2::new(50.0, 50.0));
+        canvas.changed_widgets.insert(old);
+        assert!(canvas.set_value(old, 80.0));
+        assert!(canvas.flashes.contains_key(&old));
+        
+        assert!(canvas.delete_widget(old));
+        assert_eq!(add(&mut canvas, knob("NEW"), Pos2::new(50.0, 50.0)), old);
+        assert!(canvas.take_changed().is_empty());
+        assert!(!canvas.flashes.contains_key(&old));
+    }
+    
+    fn assert_unique_ids(canvas: &DragDropCanvas) {
+        let mut ids: Vec<usize> = canvas.widgets.iter().map(|w| w.id).collect();
+        ids.sort_unstable();
+        let count = ids.len();
+        ids.dedup();
+        assert_eq!(ids.len(), count, "duplicate widget IDs");
+    }
+    
+    #[test]
+    fn hammering_add_and_delete_never_collides_ids() {
+        // Same seed in both modes, so both runs make the same sequence of edits
+        let final_next_id = [false, true].map(|recycle_ids| {
+            let mut canvas = canvas_at(Rect::from_min_size(Pos2::ZERO, Vec2::new(1600.0, 1200.0)));
+            canvas.settings.recycle_ids = recycle_ids;
+            let mut seed = 12345_u32;
+            let mut next = move |n: usize| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (seed >> 16) as usize % n
+            };
+            let mut saved = None;
+            
+            for step in 0..2000 {
+                match next(10) {
+                    0..=4 => {
+                        add(&mut canvas, knob("K"), Pos2::new(next(1500) as f32, next(1100) as f32));
+                    }
+                    5..=7 if !canvas.widgets.is_empty() => {
+                        let id = canvas.widgets[next(canvas.widgets.len())].id;
+                        assert!(canvas.delete_widget(id));
+                    }
+                    8 => {
+                        canvas.undo();
+                    }
+                    _ => match saved.take() {
+                        Some(snapshot) => canvas.restore(snapshot),
+                        None => saved = Some(canvas.snapshot()),
+                    },
+                }
+                assert_unique_ids(&canvas);
+                assert!(canvas.widgets.iter().all(|w| w.id < canvas.next_id), "step {step}: ID at or past next_id");
+            }
+            canvas.next_id
+        });
+        
+        // Recycling reused deleted IDs instead of counting up for every add
+        assert!(final_next_id[1] < final_next_id[0], "next_id {:?}", final_next_id);
+    }
+    
+    #[test]
+    fn zoom_keeps_its_anchor_and_round_trips() {
+        let mut canvas = DragDropCanvas::new();
+        render_in(&mut canvas, Vec2::new(1200.0, 800.0));
+        let anchor = canvas.canvas_rect.center() + Vec2::new(120.0, -40.0);
+        assert_near(canvas.canvas_to_screen(anchor), anchor);
+        
+        canvas.zoom_at(anchor, 2.0);
+        assert_eq!(canvas.zoom, 2.0);
+        assert_near(canvas.canvas_to_screen(anchor), anchor);
+        let offset = Vec2::new(10.0, 10.0);
+        assert_near(canvas.canvas_to_screen(anchor + offset), anchor + offset * 2.0);
+        assert_round_trips(&canvas);
+    }
+    
+    #[test]
+    fn zoom_and_design_size_combine_and_round_trip() {
+        for window in [Vec2::new(1600.0, 800.0), Vec2::new(900.0, 1200.0)] {
+            let mut canvas = DragDropCanvas::new();
+            canvas.settings.design_size = Some(Vec2::new(1280.0, 800.0));
+            render_in(&mut canvas, window);
+            let anchor = canvas.canvas_rect.center() + Vec2::new(-200.0, 90.0);
+            let anchor_on_screen = canvas.canvas_to_screen(anchor);
+            let scale = canvas.design_scale();
+            
+            canvas.zoom_at(anchor, 1.5);
+            assert_near(canvas.canvas_to_screen(anchor), anchor_on_screen);
+            let offset = Vec2::new(10.0, 10.0);
+            assert_near(canvas.canvas_to_screen(anchor + offset), anchor_on_screen + offset * 1.5 * scale);
+            assert_round_trips(&canvas);
+            
+            // The view survives another frame at the same window size
+            render_in(&mut canvas, window);
+            assert_near(canvas.canvas_to_screen(anchor), anchor_on_screen);
+        }
+    }
+    
+    #[test]
+    fn activating_a_grouped_toggle_turns_off_the_rest_of_its_group() {
+        let mut canvas = canvas_at(Rect::from_min_size(Pos2::ZERO, Vec2::new(1000.0, 800.0)));
+        let grouped: Vec<usize> = (0..3)
+            .map(|i| place(&mut canvas, toggle(i < 2), Pos2::new(50.0 + 150.0 * i as f32, 50.0)))
+            .collect();
+        for &id in &grouped {
+            canvas.get_widget_mut(id).unwrap().exclusive_group = Some("1".to_string());
+        }
+        let independent = place(&mut canvas, toggle(true), Pos2::new(50.0, 300.0));
+        canvas.take_changed();
+        
+        let idx = canvas.widgets.iter().position(|w| w.id == grouped[2]).unwrap();
+        let face = canvas.widgets[idx].get_rect().center();
+        canvas.handle_widget_interaction(idx, face);
+        
+        let state = |id| canvas.get_widget(id).unwrap().widget_type.switch_state();
+        assert_eq!(grouped.iter().map(|&id| state(id)).collect::<Vec<_>>(), [Some(false), Some(false), Some(true)]);
+        assert_eq!(state(independent), Some(true));
+        assert_eq!(canvas.take_changed(), grouped);
+    }
+    
+    #[test]
+    fn trim_that_moves_nothing_keeps_the_full_undo_history_and_the_nudge_burst() {
+        let mut canvas = canvas_at(Rect::from_min_size(Pos2::ZERO, Vec2::new(1000.0, 800.0)));
+        let maxed = WidgetType::Knob { value: 100.0, min: 0.0, max: 100.0, label: "GAIN".to_string(), color: WidgetColor::Cyan, default_value: None };
+        let id = place(&mut canvas, maxed, Pos2::new(200.0, 200.0));
+        canvas.selected_widget = Some(id);
+        for marker in 0..UNDO_LIMIT {
+            canvas.next_id = 100 + marker;
+            canvas.push_undo();
+        }
+        
+        assert!(canvas.nudge_selected(Vec2::new(1.0, 0.0)));
+        assert!(!canvas.trim_selected(10.0), "a knob at its maximum can't trim up");
+        assert!(canvas.nudge_selected(Vec2::new(1.0, 0.0)));
+        
+        // Only the first nudge took a step, dropping just the oldest snapshot
+        assert_eq!(canvas.undo_stack.len(), UNDO_LIMIT);
+        assert_eq!(canvas.undo_stack[0].next_id, 101);
+        
+        assert!(canvas.trim_selected(-10.0));
+        assert_eq!(canvas.undo_stack[0].next_id, 102);
+    }
+}
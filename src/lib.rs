@@ -2,5 +2,7 @@
 
 mod app;
 mod audio_controls;
-mod drag_drop_canvas;
-pub use app::TemplateApp;
\ No newline at end of file
+pub mod canvas;
+pub mod canvas_tabs;
+pub mod drag_drop_canvas;
+pub use app::TemplateApp;
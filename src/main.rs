@@ -10,7 +10,7 @@ fn main() -> eframe::Result {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1200.0, 800.0])
             .with_min_inner_size([800.0, 600.0])
-            .with_title(&format!("Ev2 v{} - Audio Control Matrix", env!("CARGO_PKG_VERSION")))
+            .with_title(format!("Ev2 v{} - Audio Control Matrix", env!("CARGO_PKG_VERSION")))
             .with_icon(
                 // Load the custom Ev2 icon
                 eframe::icon_data::from_png_bytes(&include_bytes!("../assets/icon-256.png")[..])